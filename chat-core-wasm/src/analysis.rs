@@ -1,10 +1,44 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Write;
+
+use crate::error::AnalyzeError;
+use crate::filters::TextFilters;
+use crate::fingerprint;
+use crate::head_to_head;
+use crate::highlights;
+use crate::input::{self, InputMessage};
 use crate::journey;
 use crate::metrics;
-use crate::parsing;
+use crate::options::AnalyzeOptions;
+use crate::parsing::{self, Message, ParseReport};
 use crate::phrases;
+use crate::sampling;
 use crate::sentiment;
-use crate::text::CONVERSATION_GAP_MINUTES;
-use crate::types::Summary;
+use crate::text::{REPLY_WINDOW_MINUTES, STOPWORD_LANGUAGE_AUTO, WHATSAPP_EXTRAS};
+use crate::types::{Count, PhraseMinCount, RelationshipScore, SampleInfo, StopwordConfig, Summary};
+use crate::warnings;
+
+/// Summary fields built from the sample when `AnalyzeOptions::sample_fraction` is set.
+/// The head-to-head, relationship score and swings only take their sentiment from it.
+const SAMPLED_FIELDS: [&str; 17] = [
+    "salient_phrases",
+    "top_phrases",
+    "top_phrases_no_stop",
+    "top_phrase_examples",
+    "top_question_phrases",
+    "phrase_min_count",
+    "per_person_phrases",
+    "per_person_phrases_no_stop",
+    "sentiment_by_day",
+    "sentiment_overall",
+    "overall_sentiment",
+    "sentiment_exchange",
+    "sentiment_series",
+    "swings",
+    "head_to_head",
+    "relationship_score",
+    "journey",
+];
 
 // Performance timing helpers, enabled via `--features timing` for debugging.
 #[cfg(all(target_arch = "wasm32", feature = "timing"))]
@@ -47,83 +81,446 @@ pub fn analyze_chat_native(
     top_words_n: usize,
     top_emojis_n: usize,
 ) -> Result<String, String> {
-    let summary = summarize(raw, top_words_n, top_emojis_n)?;
+    let summary = summarize(raw, top_words_n, top_emojis_n).map_err(|e| e.to_string())?;
     serde_json::to_string(&summary).map_err(|e| e.to_string())
 }
 
-pub fn summarize(raw: &str, top_words_n: usize, top_emojis_n: usize) -> Result<Summary, String> {
+/// Like `analyze_chat_native`, but streams the JSON into `w` instead of building the
+/// whole string first, which matters for large group summaries.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn summarize_to_writer<W: Write>(
+    raw: &str,
+    top_words_n: usize,
+    top_emojis_n: usize,
+    options: &AnalyzeOptions,
+    w: W,
+) -> Result<(), AnalyzeError> {
+    let summary = summarize_with_options(raw, top_words_n, top_emojis_n, options)?;
+    write_summary(&summary, w, false)
+}
+
+/// `summarize_to_writer` with indented output.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn summarize_to_writer_pretty<W: Write>(
+    raw: &str,
+    top_words_n: usize,
+    top_emojis_n: usize,
+    options: &AnalyzeOptions,
+    w: W,
+) -> Result<(), AnalyzeError> {
+    let summary = summarize_with_options(raw, top_words_n, top_emojis_n, options)?;
+    write_summary(&summary, w, true)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_summary<W: Write>(summary: &Summary, w: W, pretty: bool) -> Result<(), AnalyzeError> {
+    if pretty {
+        serde_json::to_writer_pretty(w, summary)
+    } else {
+        serde_json::to_writer(w, summary)
+    }
+    .map_err(|e| AnalyzeError::Output(e.to_string()))
+}
+
+pub fn summarize(
+    raw: &str,
+    top_words_n: usize,
+    top_emojis_n: usize,
+) -> Result<Summary, AnalyzeError> {
+    summarize_with_options(raw, top_words_n, top_emojis_n, &AnalyzeOptions::default())
+}
+
+pub fn summarize_with_options(
+    raw: &str,
+    top_words_n: usize,
+    top_emojis_n: usize,
+    options: &AnalyzeOptions,
+) -> Result<Summary, AnalyzeError> {
+    #[cfg(all(target_arch = "wasm32", feature = "timing"))]
+    let _total_guard = TimingGuard::new("summarize_total");
+
+    let (messages, parse_report) = parse_with_options(raw, options)?;
+    let fingerprint = fingerprint::content_fingerprint(raw, top_words_n, top_emojis_n, options);
+    summarize_parsed(
+        messages,
+        parse_report,
+        fingerprint,
+        top_words_n,
+        top_emojis_n,
+        options,
+    )
+}
+
+/// Validates `options` and parses `raw` under them (size limit, custom header pattern,
+/// line length cap).
+pub(crate) fn parse_with_options(
+    raw: &str,
+    options: &AnalyzeOptions,
+) -> Result<(Vec<Message>, ParseReport), AnalyzeError> {
+    options.validate()?;
+    check_limit("max_bytes", raw.len(), options.limits.max_bytes)?;
+    let custom_line_regex = options
+        .custom_line_regex
+        .as_deref()
+        .map(parsing::compile_line_regex)
+        .transpose()
+        .map_err(AnalyzeError::Analysis)?;
+    Ok(parsing::parse_messages_with(
+        raw,
+        custom_line_regex.as_ref(),
+        options.limits.max_line_length,
+    ))
+}
+
+pub(crate) fn check_limit(
+    limit: &'static str,
+    observed: usize,
+    max: usize,
+) -> Result<(), AnalyzeError> {
+    if observed > max {
+        return Err(AnalyzeError::TooLarge {
+            limit,
+            observed,
+            max,
+        });
+    }
+    Ok(())
+}
+
+/// `summarize_with_options` for messages that are already structured, skipping the txt
+/// parser. Entries without a usable timestamp or sender are left out and reported in
+/// `Summary::warnings`.
+pub fn summarize_messages(
+    input: &[InputMessage],
+    top_words_n: usize,
+    top_emojis_n: usize,
+    options: &AnalyzeOptions,
+) -> Result<Summary, AnalyzeError> {
     #[cfg(all(target_arch = "wasm32", feature = "timing"))]
     let _total_guard = TimingGuard::new("summarize_total");
 
-    let messages = parsing::parse_messages(raw);
+    options.validate()?;
+    let (messages, parse_report) =
+        input::messages_from_input(input, options.limits.max_line_length);
+    let fingerprint = fingerprint::messages_fingerprint(input, top_words_n, top_emojis_n, options);
+    summarize_parsed(
+        messages,
+        parse_report,
+        fingerprint,
+        top_words_n,
+        top_emojis_n,
+        options,
+    )
+}
+
+/// Everything after parsing; `options` are already validated.
+fn summarize_parsed(
+    mut messages: Vec<Message>,
+    mut parse_report: ParseReport,
+    fingerprint: String,
+    top_words_n: usize,
+    top_emojis_n: usize,
+    options: &AnalyzeOptions,
+) -> Result<Summary, AnalyzeError> {
+    let text_filters =
+        TextFilters::compile(&options.text_filters).map_err(AnalyzeError::Analysis)?;
     if messages.is_empty() {
-        return Err("No messages parsed".into());
+        return Err(AnalyzeError::Analysis("No messages parsed".into()));
+    }
+    check_limit("max_messages", messages.len(), options.limits.max_messages)?;
+    if options.anonymize {
+        let aliases = parsing::anonymize_senders(&mut messages);
+        parse_report.anonymize(&aliases);
     }
+    // The one chronological order every metric reads; `sorted_view` borrows it as is.
+    messages.sort_by_key(Message::order_key);
 
     #[cfg(all(target_arch = "wasm32", feature = "timing"))]
     let _guard = TimingGuard::new("metrics_and_phrases");
 
     let (del_you, del_others) = metrics::deleted_counts(&messages);
-    let (conversation_starters, conversation_count) =
-        metrics::conversation_initiations(&messages, CONVERSATION_GAP_MINUTES);
-    let (sentiment_by_day, sentiment_overall) = sentiment::sentiment_breakdown(&messages);
-
-    let word_cloud_val = phrases::word_cloud(&messages, 150, true);
-    let word_cloud_no_stop_val = phrases::word_cloud(&messages, 150, false);
-    let salient_phrases_val = phrases::salient_phrases(&messages, 50);
-    let top_phrases_val = phrases::top_phrases(&messages, 100, true);
-    let top_phrases_no_stop_val = phrases::top_phrases(&messages, 100, false);
-    let per_person_phrases_val = phrases::per_person_phrases(&messages, 20, true);
-    let per_person_phrases_no_stop_val = phrases::per_person_phrases(&messages, 20, false);
-
-    let person_stats_val = metrics::person_stats(&messages);
+    let gap_minutes = options.conversation_gap_minutes as i64;
+    let (conversation_starters, conversation_count) = metrics::conversation_initiations(
+        &messages,
+        gap_minutes,
+        !options.credit_placeholder_starters,
+    );
+    let starter_success =
+        metrics::starter_success(&messages, gap_minutes, !options.credit_placeholder_starters);
+    let presence_share = metrics::presence_share(&messages, gap_minutes);
+    let revivals = metrics::revivals(&messages, options.revival_gap_hours);
+    let conversations_per_month = metrics::conversations_per_month(&messages, gap_minutes);
+    let (conversation_start_hours, conversation_start_weekdays) =
+        metrics::conversation_start_histograms(&messages, gap_minutes);
+    let total_conversation_minutes = metrics::conversation_minutes(&messages, gap_minutes);
+    let message_density = metrics::message_density(messages.len(), total_conversation_minutes);
+    let edits_per_conversation = metrics::edits_per_conversation(&messages, gap_minutes);
+
+    // Spam runs collapse to one message for phrase and journey inputs so a
+    // wall of identical lines can't dominate them; raw counts still see every message.
+    let (deduped, spam_runs) = metrics::collapse_spam_runs(&messages);
+
+    // Text-based stats read the filtered text; counts and the journey keep the original.
+    let text_view = text_filters.apply_all(&messages);
+    let deduped_text = text_filters.apply_all(&deduped);
+
+    // Heavy metrics (phrases, sentiment, journey) optionally run on a sample.
+    let sample = options
+        .sample_fraction
+        .map(|fraction| sampling::sample_messages(&messages, fraction, options.sample_seed));
+    let sample_deduped = sample
+        .as_ref()
+        .map(|sample| metrics::collapse_spam_runs(sample).0);
+    let sample_text_view = sample.as_ref().map(|sample| text_filters.apply_all(sample));
+    let sample_deduped_text = sample_deduped
+        .as_ref()
+        .map(|deduped| text_filters.apply_all(deduped));
+    let heavy_deduped: &[Message] = sample_deduped.as_deref().unwrap_or(&deduped);
+    let heavy_text_view: &[Message] = sample_text_view.as_deref().unwrap_or(&*text_view);
+    let heavy_deduped_text: &[Message] = sample_deduped_text.as_deref().unwrap_or(&*deduped_text);
+    let emoji_weights = options.emoji_weights();
+    let sentiment_val = sentiment::sentiment_breakdown(heavy_text_view, &emoji_weights);
+    let sentiment_series = if options.per_message_sentiment {
+        sentiment::per_message_scores(heavy_text_view, &emoji_weights)
+    } else {
+        Vec::new()
+    };
+
+    let filter_numeric = options.filter_numeric_tokens;
+    let word_cloud_val = phrases::word_cloud(&text_view, 150, true, filter_numeric);
+    let word_cloud_no_stop_val = phrases::word_cloud(&text_view, 150, false, filter_numeric);
+    let keyword_counts_val = phrases::keyword_counts(&text_view, &options.track_keywords);
+    let theme_counts = metrics::theme_counts(&text_view, &options.theme_clusters);
+    let max_tokens = options.long_message_tokens;
+    let salient_phrases_val = phrases::salient_phrases(heavy_deduped_text, 50, max_tokens);
+    let (top_phrase_examples, top_min_count) = phrases::top_phrases_with_examples(
+        heavy_deduped_text,
+        100,
+        max_tokens,
+        options.phrase_examples,
+    );
+    let top_phrases_val: Vec<Count> = top_phrase_examples
+        .iter()
+        .map(|p| Count {
+            label: p.label.clone(),
+            value: p.value,
+        })
+        .collect();
+    let top_phrase_examples = if options.phrase_examples {
+        top_phrase_examples
+    } else {
+        Vec::new()
+    };
+    let top_question_phrases = phrases::top_question_phrases(heavy_deduped_text, 20, max_tokens);
+    let phrase_min_count = PhraseMinCount {
+        top_phrases: top_min_count,
+        salient_phrases: phrases::salient_min_count(heavy_deduped_text.len()),
+    };
+    let top_phrases_no_stop_val = phrases::top_phrases(heavy_deduped_text, 100, false, max_tokens);
+    let per_person_phrases_val =
+        phrases::per_person_phrases(heavy_deduped_text, 20, true, max_tokens);
+    let per_person_phrases_no_stop_val =
+        phrases::per_person_phrases(heavy_deduped_text, 20, false, max_tokens);
+    let long_messages = metrics::long_messages(&deduped_text, max_tokens);
+    let poll_stats = metrics::poll_stats(&messages);
+
+    // Optionally re-join long messages that exported as same-second parts.
+    // Person stats and fun facts share this view so their word counts agree.
+    let joined = options
+        .join_split_messages
+        .then(|| metrics::join_split_parts(&text_view));
+    let stats_view: &[Message] = joined.as_deref().unwrap_or(&text_view);
+    let person_stats_val = metrics::person_stats(stats_view);
+    let word_firsts = metrics::word_firsts(&text_view);
     let by_sender = metrics::count_by_sender(&messages);
+    let participants = metrics::participants(&by_sender);
+    let share_of_speech = metrics::share_of_speech(&by_sender);
+    let imbalance = metrics::imbalance(&share_of_speech);
     let daily = metrics::daily_counts(&messages);
+    let streak_ignore = options.streak_ignore_dates();
+    let longest_streak = metrics::daily_streak(&daily, &streak_ignore);
     let hourly = metrics::hourly_counts(&messages);
-    let top_emojis_val = phrases::top_emojis(&messages, top_emojis_n);
-    let top_words_val = phrases::top_words(&messages, top_words_n, true);
-    let top_words_no_stop_val = phrases::top_words(&messages, top_words_n, false);
-    let timeline_val = metrics::timeline(&messages);
+    let routines = metrics::routine_detection(&messages);
+    let first_message_hour_by_month = metrics::first_message_hour_by_month(&messages);
+    let first_message_hour_by_person = metrics::first_message_hour_by_person(&messages);
+    let language_timeline = metrics::language_timeline(&text_view);
+    let top_emojis_val = phrases::top_emojis(&text_view, top_emojis_n);
+    let recent_emojis = phrases::recent_emojis(
+        &text_view,
+        top_emojis_n,
+        phrases::RECENT_EMOJI_HALF_LIFE_DAYS,
+    );
+    let top_words_val = phrases::top_words(&text_view, top_words_n, true, filter_numeric);
+    let top_words_no_stop_val = phrases::top_words(&text_view, top_words_n, false, filter_numeric);
+    let top_word = top_words_val.first().map(|c| c.label.clone());
+    let daily_timeline = metrics::timeline(&messages);
+    let annotations = metrics::timeline_annotations(&messages);
+    let special_days = metrics::special_days(
+        &text_view,
+        &options.special_dates,
+        options.long_message_tokens,
+        &emoji_weights,
+    );
+    let volume_trend = metrics::volume_trend_from_timeline(&daily_timeline);
+    let timeline_unit = metrics::timeline_unit(&daily_timeline, options.timeline_granularity);
+    let timeline_val = metrics::timeline_at(daily_timeline, timeline_unit);
     let weekly = metrics::weekly_counts(&messages);
+    let iso_weekly = metrics::iso_weekly_counts(&messages);
+    let weekly_streak = metrics::weekly_streak(&messages, options.weekly_streak_threshold);
     let monthly = metrics::monthly_counts(&messages);
+    let (busiest_month, quietest_month) = metrics::busiest_and_quietest_month(&monthly);
+    let swings = metrics::month_swings(&monthly, &sentiment_val.by_day);
+    let quarterly = metrics::quarterly_counts(&monthly);
+    let vocab_source = parsing::sorted_view(&deduped_text);
+    let vocab_tokens = phrases::tokenize_messages(&vocab_source);
+    let vocab_drift = phrases::vocab_drift(&vocab_tokens);
+    let word_origins = phrases::word_origins(&vocab_tokens);
+    let seasonal = metrics::seasonal_profile(&monthly, options.hemisphere);
     let buckets = metrics::buckets_by_person(&messages);
-    let emoji_cloud_val = phrases::emoji_cloud(&messages, 1000);
-    let fun_facts_val = metrics::fun_facts(&messages);
-    let per_person_daily_val = metrics::per_person_daily(&messages);
-    let journey_val = journey::build_journey(&messages);
-
-    Ok(Summary {
+    let (weekday_leader, weekday_share) = metrics::weekday_leaders(&buckets);
+    let emoji_cloud_val = phrases::emoji_cloud(&text_view, 1000);
+    let emoji_cloud_sentiment = sentiment::emoji_cloud_sentiment(&emoji_cloud_val, &emoji_weights);
+    let emoji_spam_by_sender = metrics::emoji_spam_by_sender(&messages);
+    let emoji_categories = phrases::emoji_categories(&text_view);
+    let emoji_pairs = phrases::emoji_pairs(text_view.iter().map(|m| m.text.as_str()), 20);
+    let emoji_trend = metrics::emoji_trend_by_person(&text_view);
+    let (punctuation_energy, punctuation_energy_by_person) =
+        metrics::punctuation_energy(&text_view);
+    let per_person_emoji_monthly = if options.per_person_emoji_monthly {
+        phrases::per_person_emoji_monthly(&text_view)
+    } else {
+        Vec::new()
+    };
+    let fun_facts_val = metrics::fun_facts(stats_view);
+    let per_person_daily_val = metrics::per_person_daily(&messages, &streak_ignore);
+    let presence_calendar = metrics::presence_calendar(&per_person_daily_val, &by_sender);
+    let journey_val = journey::build_journey(
+        heavy_deduped,
+        gap_minutes,
+        options.long_message_tokens,
+        options.journey_length_bonus_cap,
+        &options.self_strategy,
+        &emoji_weights,
+        journey::courtship(&messages),
+    );
+    let voice_notes_val = metrics::voice_notes(&messages);
+    let reply_gaps = metrics::reply_gaps(&messages, REPLY_WINDOW_MINUTES);
+    let response_latency_val = metrics::response_latency(&reply_gaps);
+    let avg_response_minutes = metrics::avg_response_minutes(&reply_gaps);
+    let reply_time_histogram = metrics::reply_time_histogram(&reply_gaps);
+    let biggest_conversation_val = metrics::biggest_conversation(&messages, gap_minutes);
+
+    let mut summary = Summary {
         total_messages: messages.len(),
-        by_sender: by_sender.clone(),
+        by_sender,
+        participants,
         daily,
+        longest_streak,
         hourly,
+        routines,
+        first_message_hour_by_month,
+        first_message_hour_by_person,
         top_emojis: top_emojis_val,
+        recent_emojis,
         top_words: top_words_val,
         top_words_no_stop: top_words_no_stop_val,
+        top_word,
+        stopword_config: StopwordConfig {
+            language: STOPWORD_LANGUAGE_AUTO.to_string(),
+            extra_count: WHATSAPP_EXTRAS.len() as u32,
+        },
+        language_timeline,
         deleted_you: del_you,
         deleted_others: del_others,
         timeline: timeline_val,
+        timeline_unit,
+        volume_trend,
+        annotations,
+        special_days,
         weekly,
+        iso_weekly,
+        weekly_streak,
         monthly,
-        share_of_speech: by_sender,
+        busiest_month,
+        quietest_month,
+        swings,
+        quarterly,
+        vocab_drift,
+        word_origins,
+        seasonal,
+        share_of_speech,
+        imbalance,
         buckets_by_person: buckets,
+        weekday_leader,
+        weekday_share,
         word_cloud: word_cloud_val,
+        keyword_counts: keyword_counts_val,
+        theme_counts,
         word_cloud_no_stop: word_cloud_no_stop_val,
         emoji_cloud: emoji_cloud_val,
+        emoji_cloud_sentiment,
+        emoji_spam_by_sender,
+        per_person_emoji_monthly,
+        emoji_categories,
+        emoji_pairs,
+        emoji_trend,
+        punctuation_energy,
+        punctuation_energy_by_person,
         salient_phrases: salient_phrases_val,
         top_phrases: top_phrases_val,
         top_phrases_no_stop: top_phrases_no_stop_val,
+        top_phrase_examples,
+        top_question_phrases,
+        phrase_min_count,
         per_person_phrases: per_person_phrases_val,
         per_person_phrases_no_stop: per_person_phrases_no_stop_val,
         fun_facts: fun_facts_val,
+        highlights: Vec::new(),
+        spam_runs,
+        long_messages,
+        poll_stats,
         person_stats: person_stats_val,
+        word_firsts,
         per_person_daily: per_person_daily_val,
-        sentiment_by_day,
-        sentiment_overall,
+        presence_calendar,
+        sentiment_by_day: sentiment_val.by_day,
+        sentiment_overall: sentiment_val.overall,
+        overall_sentiment: sentiment_val.chat_mean,
+        sentiment_exchange: sentiment_val.exchange,
+        sentiment_series,
         conversation_starters,
+        starter_success,
+        presence_share,
+        revivals,
         conversation_count,
+        total_conversation_minutes,
+        message_density,
+        conversations_per_month,
+        conversation_start_hours,
+        conversation_start_weekdays,
+        edits_per_conversation,
+        response_latency: response_latency_val,
+        avg_response_minutes,
+        reply_time_histogram,
+        biggest_conversation: biggest_conversation_val,
+        voice_notes: voice_notes_val,
+        head_to_head: None,
+        relationship_score: RelationshipScore::default(),
         journey: journey_val,
-    })
+        warnings: warnings::build_warnings(&parse_report),
+        merged: false,
+        sampled: sample.as_ref().map(|sample| SampleInfo {
+            fraction: options.sample_fraction.unwrap_or(1.0),
+            seed: options.sample_seed,
+            messages: sample.len(),
+            fields: SAMPLED_FIELDS.iter().map(|f| f.to_string()).collect(),
+        }),
+        analysis_meta: fingerprint::analysis_meta(fingerprint, options),
+    };
+    summary.head_to_head = head_to_head::build_head_to_head(&summary, &messages);
+    summary.relationship_score = metrics::relationship_score(&summary);
+    summary.highlights = highlights::build_highlights(&summary);
+    Ok(summary)
 }
 
 #[cfg(test)]
@@ -132,7 +529,9 @@ mod tests {
     use chrono::{Datelike, NaiveDateTime};
     use std::collections::HashMap;
 
+    use crate::options::{Limits, TextFilter, TimelineGranularity};
     use crate::parsing::Message;
+    use crate::types::TimelineUnit;
     use crate::{metrics, parsing, phrases, text, Count};
 
     fn msg(sender: &str, text: &str) -> Message {
@@ -140,6 +539,7 @@ mod tests {
             dt: NaiveDateTime::parse_from_str("2020-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap(),
             sender: sender.to_string(),
             text: text.to_string(),
+            line: 0,
         }
     }
 
@@ -561,6 +961,61 @@ mod tests {
         assert_eq!(summary.by_sender.len(), 2);
     }
 
+    #[test]
+    fn conversation_starters_match_segments_on_same_second_bursts() {
+        // Five messages stamped with the same second per burst; file order decides who
+        // opened each conversation.
+        let senders = ["Ana", "Ben", "Cy"];
+        let mut lines = Vec::new();
+        for day in 1..=6 {
+            for (burst, time) in ["9:00:00 AM", "2:00:00 PM", "8:00:00 PM"]
+                .iter()
+                .enumerate()
+            {
+                for i in 0..5 {
+                    let sender = senders[(day + burst + i) % senders.len()];
+                    lines.push(format!("[1/{day}/24, {time}] {sender}: message {i}"));
+                }
+            }
+        }
+        let raw = lines.join("\n");
+        let summary = summarize(&raw, 5, 5).unwrap();
+
+        let parsed = parsing::parse_messages(&raw);
+        let sorted = parsing::sorted_view(&parsed);
+        let segments = metrics::segment_conversations(&sorted, text::CONVERSATION_GAP_MINUTES);
+        assert_eq!(segments.len(), 18);
+        assert_eq!(summary.conversation_count, segments.len());
+
+        let mut openers: HashMap<String, u32> = HashMap::new();
+        for segment in &segments {
+            *openers
+                .entry(sorted[segment.start].sender.clone())
+                .or_insert(0) += 1;
+        }
+        let starters: HashMap<String, u32> = summary
+            .conversation_starters
+            .iter()
+            .map(|c| (c.label.clone(), c.value))
+            .collect();
+        assert_eq!(starters, openers);
+        assert_eq!(starters.values().sum::<u32>() as usize, segments.len());
+
+        let journey = summary.journey.unwrap();
+        assert_eq!(journey.first_messages[0].sender, sorted[0].sender);
+        assert_eq!(journey.first_messages[0].text, "message 0");
+    }
+
+    #[test]
+    fn participants_are_distinct_and_sorted() {
+        let raw = "[1/1/24, 1:00:00 PM] Zoe: hi\n\
+[1/1/24, 1:01:00 PM] Max: hey\n\
+[1/1/24, 1:02:00 PM] Zoe: how are you\n\
+[1/1/24, 1:03:00 PM] Ana: hello all";
+        let summary = summarize(raw, 5, 5).unwrap();
+        assert_eq!(summary.participants, vec!["Ana", "Max", "Zoe"]);
+    }
+
     #[test]
     fn color_tie_break_is_alphabetical() {
         let raw = "[8/19/19, 5:00:00 PM] A: red red\n[8/19/19, 5:01:00 PM] A: blue blue";
@@ -590,10 +1045,322 @@ mod tests {
             .any(|s| s.name == "Bob" && s.mean < 0.0));
     }
 
+    #[test]
+    fn sentiment_exchange_reflects_asymmetric_dm() {
+        let raw = "[8/19/19, 5:00:00 PM] Alice: I love this, so happy!\n\
+[8/19/19, 5:01:00 PM] Bob: ugh, terrible day\n\
+[8/19/19, 5:02:00 PM] Alice: awesome, thanks 😊\n\
+[8/19/19, 5:03:00 PM] Bob: meh, I'm tired and annoyed";
+        let summary = summarize(raw, 5, 5).unwrap();
+        let exchange = summary.sentiment_exchange.expect("DM has exchange");
+        let alice = exchange.iter().find(|e| e.name == "Alice").unwrap();
+        let bob = exchange.iter().find(|e| e.name == "Bob").unwrap();
+        assert!(alice.given_mean > 0.0 && alice.received_mean < 0.0);
+        assert!(bob.given_mean < 0.0 && bob.received_mean > 0.0);
+        assert!((alice.delta + bob.delta).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn sentiment_exchange_absent_for_group_chats() {
+        let raw = "[8/19/19, 5:00:00 PM] Alice: hi\n[8/19/19, 5:01:00 PM] Bob: hey\n[8/19/19, 5:02:00 PM] Carol: yo";
+        let summary = summarize(raw, 5, 5).unwrap();
+        assert!(summary.sentiment_exchange.is_none());
+    }
+
+    #[test]
+    fn anonymize_replaces_names_everywhere() {
+        let raw = "[8/19/19, 5:00:00 PM] Alice: hi Bob\n\
+[8/19/19, 5:01:00 PM] Bob: hello there\n\
+[8/19/19, 5:02:00 PM] Alice: how are you\n\
+[8/19/19, 5:03:00 PM] alice: typed from my laptop";
+        let options = AnalyzeOptions {
+            anonymize: true,
+            ..Default::default()
+        };
+        let summary = summarize_with_options(raw, 5, 5, &options).unwrap();
+
+        let senders: Vec<&str> = summary.by_sender.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(senders, vec!["Person A", "Person B"]);
+        let mut people: Vec<&str> = summary
+            .person_stats
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        people.sort();
+        assert_eq!(people, vec!["Person A", "Person B"]);
+        let merge_warning =
+            "Sender names differing only in case or spacing were merged: spellings of \"Person A\"";
+        assert!(summary.warnings.iter().any(|w| w == merge_warning));
+
+        let json = serde_json::to_string(&summary).unwrap().to_lowercase();
+        assert!(!json.contains("alice"));
+        assert!(!json.contains("bob"));
+    }
+
+    #[test]
+    fn hemisphere_option_flips_seasonal_profile() {
+        let raw = "[1/15/22, 9:00:00 AM] A: winter up north\n[1/16/22, 9:00:00 AM] B: still january\n[7/1/22, 9:00:00 AM] A: july";
+        let north = summarize(raw, 5, 5).unwrap();
+        assert_eq!(north.seasonal, [0, 1, 0, 2]);
+        assert_eq!(north.quarterly[0].label, "2022-Q1");
+
+        let options = AnalyzeOptions {
+            hemisphere: crate::Hemisphere::Southern,
+            ..Default::default()
+        };
+        let south = summarize_with_options(raw, 5, 5, &options).unwrap();
+        assert_eq!(south.seasonal, [0, 2, 0, 1]);
+    }
+
+    #[test]
+    fn tracked_stopword_keywords_are_counted() {
+        let raw = "[1/1/24, 1:00:00 PM] A: love you\n[1/1/24, 1:01:00 PM] B: love you more";
+        let options = AnalyzeOptions {
+            track_keywords: vec!["you".into(), "love you".into()],
+            ..Default::default()
+        };
+        let summary = summarize_with_options(raw, 10, 5, &options).unwrap();
+        assert!(!summary.top_words.iter().any(|c| c.label == "you"));
+        assert_eq!(summary.keyword_counts[0].value, 2);
+        assert_eq!(summary.keyword_counts[1].value, 2);
+    }
+
+    #[test]
+    fn spam_runs_are_reported_and_collapsed_for_phrases() {
+        let mut raw = String::from("[1/1/24, 9:00:00 AM] A: good morning sunshine\n");
+        for i in 0..50 {
+            raw.push_str(&format!(
+                "[1/1/24, 10:{:02}:{:02} AM] B: spam spam spam\n",
+                i / 60,
+                i % 60
+            ));
+        }
+        raw.push_str("[1/1/24, 11:00:00 AM] A: good morning sunshine");
+        let summary = summarize(&raw, 10, 5).unwrap();
+
+        assert_eq!(summary.total_messages, 52);
+        assert_eq!(summary.spam_runs.len(), 1);
+        assert_eq!(summary.spam_runs[0].count, 50);
+        let spam = summary
+            .top_phrases
+            .iter()
+            .find(|c| c.label == "spam spam")
+            .map(|c| c.value)
+            .unwrap_or(0);
+        assert!(spam <= 2, "collapsed run should count once, got {spam}");
+        // Fun facts count every message, like person stats.
+        let b = summary.fun_facts.iter().find(|f| f.name == "B").unwrap();
+        assert_eq!(b.total_words, 150);
+        let b_stats = summary.person_stats.iter().find(|p| p.name == "B").unwrap();
+        assert_eq!(b.total_words, b_stats.total_words);
+    }
+
+    #[test]
+    fn courtship_counts_openers_the_spam_collapse_drops() {
+        let mut raw = String::new();
+        for i in 0..25 {
+            raw.push_str(&format!("[1/1/24, 10:{i:02}:00 AM] B: hello?\n"));
+        }
+        raw.push_str("[1/1/24, 11:00:00 AM] A: sorry, just saw this");
+        let summary = summarize(&raw, 10, 5).unwrap();
+
+        assert_eq!(summary.spam_runs[0].count, 25);
+        let courtship = summary.journey.unwrap().courtship.unwrap();
+        assert_eq!(courtship.messages_before_reply, 25);
+        assert_eq!(courtship.hours_before_reply, 1.0);
+    }
+
+    #[test]
+    fn chat_streak_bridges_ignored_ranges() {
+        let raw = "[3/1/24, 9:00:00 AM] A: one\n\
+[3/2/24, 9:00:00 AM] B: two\n\
+[3/3/24, 9:00:00 AM] A: three\n\
+[3/6/24, 9:00:00 AM] B: back\n\
+[3/7/24, 9:00:00 AM] A: again";
+        let plain = summarize(raw, 5, 5).unwrap();
+        let streak = plain.longest_streak.as_ref().unwrap();
+        assert_eq!((streak.days, streak.end.as_str()), (3, "2024-03-03"));
+
+        let options = AnalyzeOptions::builder()
+            .streak_ignore_range("2024-03-04", "2024-03-05")
+            .build()
+            .unwrap();
+        let bridged = summarize_with_options(raw, 5, 5, &options).unwrap();
+        let streak = bridged.longest_streak.as_ref().unwrap();
+        assert_eq!(
+            (streak.days, streak.start.as_str(), streak.end.as_str()),
+            (5, "2024-03-01", "2024-03-07")
+        );
+        assert_eq!(crate::diff::diff(&plain, &bridged).longest_streak.new, 5);
+    }
+
+    #[test]
+    fn summarize_reports_median_response_minutes() {
+        let raw = "[1/1/24, 10:00:00 AM] Alice: hey\n[1/1/24, 10:02:00 AM] Bob: hi\n[1/1/24, 10:12:00 AM] Alice: how are you\n[1/1/24, 10:16:00 AM] Bob: good";
+        let summary = summarize(raw, 10, 5).unwrap();
+        // Replies took 2, 10 and 4 minutes.
+        assert!((summary.avg_response_minutes - 4.0).abs() < 1e-6);
+        assert_eq!(summary.response_latency.len(), 2);
+
+        let solo = summarize("[1/1/24, 10:00:00 AM] Alice: hello?", 10, 5).unwrap();
+        assert_eq!(solo.avg_response_minutes, 0.0);
+    }
+
+    #[test]
+    fn summarize_channel_export_has_single_participant() {
+        let raw = "[1/2/24, 3:00 PM] Welcome to the channel\n[1/2/24, 3:05 PM] Big news today\n[1/3/24, 9:00 AM] Another update";
+        let summary = summarize(raw, 10, 5).unwrap();
+        assert_eq!(summary.total_messages, 3);
+        assert_eq!(summary.by_sender.len(), 1);
+        assert_eq!(summary.total_for_sender("Channel"), 3);
+        assert_eq!(summary.person_stats.len(), 1);
+        assert!(summary.sentiment_exchange.is_none());
+        assert_eq!(summary.avg_response_minutes, 0.0);
+    }
+
+    #[test]
+    fn text_filters_keep_code_out_of_word_clouds() {
+        let raw = "[1/1/24, 1:00:00 PM] Dev: fixed it with ```\nconst widget = frobnicate(widget);\nreturn widget;\n```\n[1/1/24, 1:01:00 PM] Ops: try `widget --force` again\n[1/1/24, 1:02:00 PM] Dev: deploy looks good";
+        let has_widget = |s: &Summary| s.word_cloud.iter().any(|c| c.label == "widget");
+
+        let plain = summarize(raw, 10, 5).unwrap();
+        assert!(has_widget(&plain));
+
+        let opts = AnalyzeOptions {
+            text_filters: vec![TextFilter::StripCodeBlocks],
+            ..Default::default()
+        };
+        let filtered = summarize_with_options(raw, 10, 5, &opts).unwrap();
+        assert!(!has_widget(&filtered));
+        assert!(filtered.word_cloud.iter().any(|c| c.label == "deploy"));
+        assert_eq!(filtered.total_messages, plain.total_messages);
+    }
+
+    #[test]
+    fn invalid_text_filter_pattern_is_an_error() {
+        let opts = AnalyzeOptions {
+            text_filters: vec![TextFilter::RegexReplace {
+                pattern: "(oops".into(),
+                replacement: String::new(),
+            }],
+            ..Default::default()
+        };
+        let err = summarize_with_options("[1/1/24, 1:00:00 PM] A: hi", 10, 5, &opts).unwrap_err();
+        assert!(err.to_string().contains("Invalid text filter pattern"));
+    }
+
+    #[test]
+    fn numeric_token_filter_defaults_on_and_can_be_disabled() {
+        let parsed: AnalyzeOptions = serde_json::from_str("{}").unwrap();
+        assert!(parsed.filter_numeric_tokens);
+
+        let raw =
+            "[1/1/24, 1:00:00 PM] A: meet at 7pm 7pm 7pm\n[1/1/24, 1:01:00 PM] B: 2023 2023 rocks";
+        let filtered = summarize(raw, 10, 5).unwrap();
+        assert!(!filtered.top_words_no_stop.iter().any(|c| c.label == "7pm"));
+        assert!(!filtered.word_cloud.iter().any(|c| c.label == "2023"));
+
+        let opts = AnalyzeOptions {
+            filter_numeric_tokens: false,
+            ..Default::default()
+        };
+        let kept = summarize_with_options(raw, 10, 5, &opts).unwrap();
+        assert!(kept.top_words_no_stop.iter().any(|c| c.label == "7pm"));
+        assert!(kept.word_cloud.iter().any(|c| c.label == "2023"));
+    }
+
+    #[test]
+    fn summarize_overall_sentiment_positive_for_happy_chat() {
+        let raw = "[1/1/24, 1:00:00 PM] A: I love this, great day\n[1/1/24, 1:01:00 PM] B: so happy for you\n[1/1/24, 1:02:00 PM] A: awesome\n[1/1/24, 1:03:00 PM] B: ugh traffic was bad";
+        let summary = summarize(raw, 10, 5).unwrap();
+        assert!(summary.overall_sentiment > 0.0);
+    }
+
+    #[test]
+    fn summarize_emoji_cloud_sentiment_hints() {
+        let raw = "[1/1/24, 1:00:00 PM] A: 😂😂 lol\n[1/1/24, 1:01:00 PM] B: 💔";
+        let summary = summarize(raw, 10, 5).unwrap();
+        let hint = |e: &str| {
+            summary
+                .emoji_cloud_sentiment
+                .iter()
+                .find(|i| i.label == e)
+                .map(|i| i.sentiment)
+        };
+        assert_eq!(hint("😂"), Some(1));
+        assert_eq!(hint("💔"), Some(-1));
+    }
+
+    #[test]
+    fn summarize_phrase_min_count_grows_with_chat_size() {
+        let chat = |n: usize| {
+            (0..n)
+                .map(|i| {
+                    let sender = if i % 2 == 0 { "A" } else { "B" };
+                    format!(
+                        "[1/{}/24, 10:{:02}:00 AM] {sender}: note {i} about plans",
+                        i / 60 % 28 + 1,
+                        i % 60
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let small = summarize(&chat(20), 10, 5).unwrap();
+        let large = summarize(&chat(10_001), 10, 5).unwrap();
+        assert!(large.phrase_min_count.salient_phrases > small.phrase_min_count.salient_phrases);
+        assert!(large.phrase_min_count.top_phrases > small.phrase_min_count.top_phrases);
+    }
+
+    #[test]
+    fn summarize_timeline_granularity_option() {
+        let raw = "[1/1/24, 1:00:00 PM] A: hi\n[1/2/24, 1:00:00 PM] B: hey\n[1/20/24, 1:00:00 PM] A: back";
+        let auto = summarize(raw, 10, 5).unwrap();
+        assert_eq!(auto.timeline_unit, TimelineUnit::Day);
+        assert_eq!(auto.timeline.len(), 20);
+
+        let opts = AnalyzeOptions {
+            timeline_granularity: TimelineGranularity::Week,
+            ..Default::default()
+        };
+        let weekly = summarize_with_options(raw, 10, 5, &opts).unwrap();
+        assert_eq!(weekly.timeline_unit, TimelineUnit::Week);
+        assert_eq!(weekly.timeline[0].label, "2024-W01");
+        assert_eq!(weekly.timeline.iter().map(|c| c.value).sum::<u32>(), 3);
+        // The trend is still fitted on days.
+        assert_eq!(weekly.volume_trend.slope, auto.volume_trend.slope);
+    }
+
+    #[test]
+    fn summarize_emoji_diary_is_opt_in() {
+        let raw = "[1/1/24, 1:00:00 PM] A: 😂😂\n[2/1/24, 1:00:00 PM] A: 🔥";
+        assert!(summarize(raw, 10, 5)
+            .unwrap()
+            .per_person_emoji_monthly
+            .is_empty());
+
+        let opts = AnalyzeOptions {
+            per_person_emoji_monthly: true,
+            ..Default::default()
+        };
+        let summary = summarize_with_options(raw, 10, 5, &opts).unwrap();
+        assert_eq!(summary.per_person_emoji_monthly[0].monthly.len(), 2);
+    }
+
+    #[test]
+    fn parse_only_matches_message_count() {
+        let raw = "[1/1/24, 1:00:00 PM] A: hi\n[1/1/24, 1:01:00 PM] B: hey\nsecond line\n[1/2/24, 9:00:00 AM] A: morning";
+        let parsed = parsing::parse_only(raw);
+        assert_eq!(parsed.len(), summarize(raw, 10, 5).unwrap().total_messages);
+        assert_eq!(parsed[0].timestamp, "2024-01-01T13:00:00");
+        assert_eq!(parsed[1].sender, "B");
+        assert_eq!(parsed[1].text, "hey\nsecond line");
+    }
+
     #[test]
     fn summarize_errors_on_empty() {
         let err = summarize("", 5, 5).unwrap_err();
-        assert!(err.contains("No messages parsed"));
+        assert_eq!(err, AnalyzeError::Analysis("No messages parsed".into()));
     }
 
     #[test]
@@ -642,4 +1409,332 @@ mod tests {
         assert_eq!(journey.last_messages[0].text, "Day 2 message");
         assert_eq!(journey.last_messages[1].text, "Day 2 reply");
     }
+
+    #[test]
+    fn poll_lines_count_as_polls_not_words() {
+        let raw = "[1/1/24, 10:00:00 AM] Alice: POLL:\nWhich restaurant?\nOPTION: Trattoria (2 votes)\nOPTION: Bistro (1 vote)\n[1/1/24, 10:01:00 AM] Bob: see you tonight";
+        let summary = summarize(raw, 20, 5).unwrap();
+        let words: Vec<&str> = summary.top_words.iter().map(|c| c.label.as_str()).collect();
+        for poll_word in ["poll", "option", "restaurant", "trattoria", "votes"] {
+            assert!(!words.contains(&poll_word), "{poll_word} in {words:?}");
+        }
+        assert!(words.contains(&"tonight"));
+        assert_eq!(summary.poll_stats.total_polls, 1);
+        assert_eq!(summary.poll_stats.total_votes, 3);
+        assert_eq!(summary.poll_stats.polls[0].question, "Which restaurant?");
+    }
+
+    #[test]
+    fn partially_parsed_files_carry_warnings() {
+        let raw = "[1/1/24, 10:00:00 AM] Alice: hi\n2024-01-01 10:05 - Bob: pasted from elsewhere\n2024-01-01 10:06 - Bob: and again\n[1/2/24, 9:00:00 AM] Bob: morning";
+        let summary = summarize(raw, 5, 5).unwrap();
+        assert_eq!(
+            summary.warnings,
+            vec![
+                "2 lines could not be parsed \u{2014} the file may mix export formats",
+                "2-digit years were interpreted as 20xx",
+            ]
+        );
+
+        let clean = "[1/1/2024, 10:00:00 AM] Alice: hi\n[1/2/2024, 9:00:00 AM] Bob: morning";
+        assert!(summarize(clean, 5, 5).unwrap().warnings.is_empty());
+    }
+
+    #[test]
+    fn corrupted_far_future_timestamp_does_not_panic() {
+        let raw = "[1/1/24, 10:00:00 AM] Alice: hi\n[12/31/9999, 11:59:59 PM] Bob: corrupted\n[1/2/24, 9:00:00 AM] Bob: morning";
+        let summary = summarize(raw, 5, 5).unwrap();
+        assert_eq!(summary.total_messages, 2);
+        assert_eq!(summary.daily.len(), 2);
+        assert_eq!(summary.timeline.len(), 2);
+    }
+
+    #[test]
+    fn same_minute_messages_from_two_files_order_deterministically() {
+        let file_a = "[1/1/24, 10:00 AM] Alice: a1\n[1/1/24, 10:00 AM] Bob: a2";
+        let file_b = "[1/1/24, 10:00 AM] Carol: b1\n[1/1/24, 9:59 AM] Dan: b0";
+        let raw = format!("{file_a}\n{file_b}");
+
+        let mut parsed = parsing::parse_messages(&raw);
+        let lines: Vec<usize> = parsed.iter().map(|m| m.line).collect();
+        assert_eq!(lines, vec![1, 2, 3, 4]);
+        // Any input order sorts back to the same sequence.
+        parsed.reverse();
+        parsed.sort_by_key(Message::order_key);
+        let texts: Vec<&str> = parsed.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, vec!["b0", "a1", "a2", "b1"]);
+
+        let runs: Vec<_> = (0..3)
+            .map(|_| {
+                let summary = summarize(&raw, 5, 5).unwrap();
+                let journey = summary.journey.unwrap();
+                let first: Vec<String> = journey
+                    .first_messages
+                    .iter()
+                    .map(|m| m.text.clone())
+                    .collect();
+                let starters = serde_json::to_string(&summary.conversation_starters).unwrap();
+                (first, starters)
+            })
+            .collect();
+        assert_eq!(runs[0].0, vec!["b0", "a1", "a2", "b1"]);
+        assert!(runs.iter().all(|r| *r == runs[0]));
+    }
+
+    #[test]
+    fn balanced_positive_responsive_chat_scores_high() {
+        let mut lines = Vec::new();
+        for day in 1..=14 {
+            lines.push(format!(
+                "[1/{day}/24, 9:00:00 AM] Alice: good morning love, this is wonderful"
+            ));
+            lines.push(format!(
+                "[1/{day}/24, 9:02:00 AM] Bob: morning! so happy to hear from you"
+            ));
+        }
+        let warm = summarize(&lines.join("\n"), 10, 5).unwrap();
+        let score = &warm.relationship_score;
+        assert!(score.score > 75.0, "{score:?}");
+        assert!(score.reciprocity > 99.0);
+        assert!(score.consistency > 99.0);
+        assert!(score.positivity > 60.0);
+
+        let one_sided = "[1/1/24, 9:00:00 AM] Alice: hello?\n[1/5/24, 9:00:00 AM] Alice: anyone?\n[1/20/24, 9:00:00 AM] Alice: this is terrible";
+        let cold = summarize(one_sided, 10, 5).unwrap();
+        assert!(
+            cold.relationship_score.score < 40.0,
+            "{:?}",
+            cold.relationship_score
+        );
+        assert_eq!(cold.relationship_score.responsiveness, 0.0);
+    }
+
+    #[test]
+    fn summarize_to_writer_matches_the_string_path() {
+        let raw = "[1/1/24, 10:00:00 AM] Alice: hello there\n[1/1/24, 10:01:00 AM] Bob: hi 😀";
+        let summary = summarize(raw, 5, 5).unwrap();
+        let expected = serde_json::to_string(&summary).unwrap();
+
+        let mut compact = Vec::new();
+        write_summary(&summary, &mut compact, false).unwrap();
+        assert_eq!(String::from_utf8(compact).unwrap(), expected);
+
+        let mut pretty = Vec::new();
+        write_summary(&summary, &mut pretty, true).unwrap();
+        assert_eq!(
+            String::from_utf8(pretty).unwrap(),
+            serde_json::to_string_pretty(&summary).unwrap()
+        );
+
+        let mut streamed = Vec::new();
+        summarize_to_writer(raw, 5, 5, &AnalyzeOptions::default(), &mut streamed).unwrap();
+        let streamed: serde_json::Value = serde_json::from_slice(&streamed).unwrap();
+        assert_eq!(streamed["total_messages"], 2);
+
+        let bad = AnalyzeOptions {
+            weekly_streak_threshold: 0,
+            ..AnalyzeOptions::default()
+        };
+        assert!(matches!(
+            summarize_to_writer(raw, 5, 5, &bad, Vec::new()),
+            Err(AnalyzeError::InvalidOption { .. })
+        ));
+    }
+
+    #[test]
+    fn summarize_to_writer_writes_files() {
+        let raw = "[1/1/24, 10:00:00 AM] Alice: hello there\n[1/1/24, 10:01:00 AM] Bob: hi";
+        let path = std::env::temp_dir().join(format!("summary-{}.json", std::process::id()));
+        let file = std::fs::File::create(&path).unwrap();
+        summarize_to_writer_pretty(
+            raw,
+            5,
+            5,
+            &AnalyzeOptions::default(),
+            std::io::BufWriter::new(file),
+        )
+        .unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(parsed["total_messages"], 2);
+        assert_eq!(parsed["by_sender"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn limits_reject_oversized_input() {
+        let raw = "[1/1/24, 10:00:00 AM] Alice: hi\n[1/1/24, 10:01:00 AM] Bob: hey\n[1/1/24, 10:02:00 AM] Alice: ok";
+        let with_limits = |limits: Limits| AnalyzeOptions {
+            limits,
+            ..AnalyzeOptions::default()
+        };
+
+        let small = with_limits(Limits {
+            max_bytes: 20,
+            ..Limits::default()
+        });
+        assert_eq!(
+            summarize_with_options(raw, 5, 5, &small).unwrap_err(),
+            AnalyzeError::TooLarge {
+                limit: "max_bytes",
+                observed: raw.len(),
+                max: 20,
+            }
+        );
+        assert!(summarize_with_options(raw, 5, 5, &small)
+            .unwrap_err()
+            .to_string()
+            .starts_with("Input too large"));
+
+        let few = with_limits(Limits {
+            max_messages: 2,
+            ..Limits::default()
+        });
+        assert_eq!(
+            summarize_with_options(raw, 5, 5, &few).unwrap_err(),
+            AnalyzeError::TooLarge {
+                limit: "max_messages",
+                observed: 3,
+                max: 2,
+            }
+        );
+        assert!(summarize(raw, 5, 5).is_ok());
+    }
+
+    #[test]
+    fn long_lines_are_cut_with_a_warning() {
+        let raw = format!(
+            "[1/1/2024, 10:00:00 AM] Alice: {}\n[1/1/2024, 10:01:00 AM] Bob: hey",
+            "word ".repeat(500)
+        );
+        let options = AnalyzeOptions {
+            limits: Limits {
+                max_line_length: 100,
+                ..Limits::default()
+            },
+            ..AnalyzeOptions::default()
+        };
+        let summary = summarize_with_options(&raw, 5, 5, &options).unwrap();
+        let alice = summary
+            .person_stats
+            .iter()
+            .find(|p| p.name == "Alice")
+            .unwrap();
+        assert!(alice.total_words < 30);
+        assert_eq!(
+            summary.warnings,
+            vec!["1 overly long line was cut short before analysis"]
+        );
+        assert!(summarize(&raw, 5, 5).unwrap().warnings.is_empty());
+    }
+
+    #[test]
+    fn sampling_is_seeded_and_leaves_counts_alone() {
+        let mut raw = String::new();
+        for day in 1..=28 {
+            for (i, (sender, text)) in [
+                ("Alice", "love this sunny beach day"),
+                ("Bob", "awful traffic again today"),
+                ("Alice", "pizza tonight or sushi"),
+            ]
+            .iter()
+            .enumerate()
+            {
+                raw.push_str(&format!(
+                    "[{}/{day}/24, 10:0{i}:00 AM] {sender}: {text} {day}\n",
+                    1 + day % 3
+                ));
+            }
+        }
+        let sampled = |seed| {
+            let options = AnalyzeOptions::builder().sample(0.2, seed).build().unwrap();
+            summarize_with_options(&raw, 10, 5, &options).unwrap()
+        };
+        let (a, b) = (sampled(42), sampled(42));
+        let full = summarize(&raw, 10, 5).unwrap();
+
+        fn json<T: serde::Serialize>(value: &T) -> serde_json::Value {
+            serde_json::to_value(value).unwrap()
+        }
+        assert_eq!(json(&a.sentiment_by_day), json(&b.sentiment_by_day));
+        assert_eq!(a.overall_sentiment, b.overall_sentiment);
+        assert_eq!(json(&a.journey), json(&b.journey));
+
+        let info = a.sampled.as_ref().expect("sampled summaries say so");
+        assert_eq!((info.fraction, info.seed), (0.2, 42));
+        assert!(info.messages < full.total_messages);
+        assert!(info.fields.iter().any(|f| f == "top_phrases"));
+        assert!(full.sampled.is_none());
+
+        assert_eq!(a.total_messages, full.total_messages);
+        assert_eq!(a.total_for_sender("Alice"), full.total_for_sender("Alice"));
+        assert_eq!(json(&a.daily), json(&full.daily));
+        assert_eq!(json(&a.monthly), json(&full.monthly));
+        assert_eq!(json(&a.hourly), json(&full.hourly));
+    }
+
+    #[test]
+    fn split_message_parts_join_when_asked() {
+        let raw = "[1/1/24, 10:00:00 AM] Alice: one two three four\n[1/1/24, 10:00:00 AM] Alice: five six seven eight\n[1/1/24, 10:01:07 AM] Bob: ok";
+        let longest = |options: &AnalyzeOptions| {
+            let summary = summarize_with_options(raw, 10, 5, options).unwrap();
+            assert_eq!(summary.total_messages, 3);
+            summary
+                .person_stats
+                .iter()
+                .find(|p| p.name == "Alice")
+                .unwrap()
+                .longest_message_words
+        };
+        assert_eq!(longest(&AnalyzeOptions::default()), 4);
+        let joined = AnalyzeOptions::builder()
+            .join_split_messages(true)
+            .build()
+            .unwrap();
+        assert_eq!(longest(&joined), 8);
+    }
+
+    #[test]
+    fn top_word_is_the_top_filtered_word() {
+        let raw = "[1/1/2024, 10:00:00 AM] Alice: the pizza is the best\n[1/1/2024, 10:01:00 AM] Bob: pizza and the beach\n[1/1/2024, 10:02:00 AM] Alice: the beach pizza";
+        let summary = summarize(raw, 5, 5).unwrap();
+        assert_eq!(summary.top_words_no_stop[0].label, "the");
+        assert_eq!(summary.top_words[0].label, "pizza");
+        assert_eq!(summary.top_word(), Some("pizza"));
+
+        let media_only = summarize("[1/1/2024, 10:00:00 AM] Alice: <Media omitted>", 5, 5).unwrap();
+        assert!(media_only.top_words.is_empty());
+        assert_eq!(media_only.top_word, None);
+    }
+
+    #[test]
+    fn iso_weekly_splits_on_monday() {
+        // 2023-02-05 is the Sunday ending 2023-W05; the next day starts W06.
+        let raw = "[2/5/2023, 9:00:00 PM] Alice: sunday night\n[2/5/2023, 9:05:00 PM] Bob: still sunday\n[2/6/2023, 8:00:00 AM] Alice: monday morning";
+        let summary = summarize(raw, 5, 5).unwrap();
+        let weeks: Vec<(&str, u32)> = summary
+            .iso_weekly
+            .iter()
+            .map(|c| (c.label.as_str(), c.value))
+            .collect();
+        assert_eq!(weeks, vec![("2023-W05", 2), ("2023-W06", 1)]);
+    }
+
+    #[test]
+    fn conversation_gap_changes_the_count() {
+        let raw = "[1/1/2024, 10:00:00 AM] Alice: morning\n[1/1/2024, 10:20:00 AM] Bob: hi\n[1/1/2024, 11:00:00 AM] Alice: lunch?\n[1/1/2024, 11:05:00 AM] Bob: sure";
+        let count = |minutes| {
+            let options = AnalyzeOptions::builder()
+                .conversation_gap_minutes(minutes)
+                .build()
+                .unwrap();
+            summarize_with_options(raw, 10, 5, &options)
+                .unwrap()
+                .conversation_count
+        };
+        assert_eq!(summarize(raw, 10, 5).unwrap().conversation_count, 2);
+        assert_eq!(count(60), 1);
+        assert_eq!(count(10), 3);
+    }
 }
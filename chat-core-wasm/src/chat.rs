@@ -0,0 +1,220 @@
+use chrono::NaiveDate;
+use wasm_bindgen::prelude::*;
+
+use crate::analysis::{check_limit, parse_with_options};
+use crate::filters::TextFilters;
+use crate::metrics;
+use crate::options::AnalyzeOptions;
+use crate::parsing::{anonymize_senders, Message};
+use crate::phrases;
+use crate::types::Count;
+
+/// A parsed export kept alive between calls, so follow-up queries (e.g. a date-range
+/// slider) skip re-parsing and re-running the full summary. Messages go through the
+/// same options pipeline as the summary (parsing limits, anonymization, text filters,
+/// spam collapsing for phrases), so a range covering the whole chat matches it. Range
+/// queries are never sampled.
+#[wasm_bindgen]
+pub struct ParsedChat {
+    /// Sorted, with text filters already applied.
+    messages: Vec<Message>,
+    filter_numeric_tokens: bool,
+    long_message_tokens: usize,
+}
+
+impl ParsedChat {
+    pub fn parse(raw: &str) -> Result<ParsedChat, String> {
+        ParsedChat::parse_with_options(raw, &AnalyzeOptions::default())
+    }
+
+    pub fn parse_with_options(raw: &str, options: &AnalyzeOptions) -> Result<ParsedChat, String> {
+        let (mut messages, _) = parse_with_options(raw, options).map_err(|e| e.to_string())?;
+        if messages.is_empty() {
+            return Err("No messages parsed".into());
+        }
+        check_limit("max_messages", messages.len(), options.limits.max_messages)
+            .map_err(|e| e.to_string())?;
+        let text_filters = TextFilters::compile(&options.text_filters)?;
+        if options.anonymize {
+            anonymize_senders(&mut messages);
+        }
+        messages.sort_by_key(Message::order_key);
+        let messages = text_filters.apply_all(&messages).into_owned();
+        Ok(ParsedChat {
+            messages,
+            filter_numeric_tokens: options.filter_numeric_tokens,
+            long_message_tokens: options.long_message_tokens,
+        })
+    }
+
+    pub fn message_count(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// `top_words` (stopwords filtered) over messages dated `start..=end`, both
+    /// `YYYY-MM-DD`.
+    pub fn top_words_in_range(
+        &self,
+        start: &str,
+        end: &str,
+        n: usize,
+    ) -> Result<Vec<Count>, String> {
+        Ok(phrases::top_words(
+            &self.in_range(start, end)?,
+            n,
+            true,
+            self.filter_numeric_tokens,
+        ))
+    }
+
+    /// `top_phrases` (stopwords filtered) over messages dated `start..=end`, with spam
+    /// runs collapsed as in the summary.
+    pub fn top_phrases_in_range(
+        &self,
+        start: &str,
+        end: &str,
+        n: usize,
+    ) -> Result<Vec<Count>, String> {
+        let (deduped, _) = metrics::collapse_spam_runs(&self.in_range(start, end)?);
+        Ok(phrases::top_phrases(
+            &deduped,
+            n,
+            true,
+            self.long_message_tokens,
+        ))
+    }
+
+    fn in_range(&self, start: &str, end: &str) -> Result<Vec<Message>, String> {
+        let start = parse_day(start)?;
+        let end = parse_day(end)?;
+        Ok(self
+            .messages
+            .iter()
+            .filter(|m| (start..=end).contains(&m.dt.date()))
+            .cloned()
+            .collect())
+    }
+}
+
+#[wasm_bindgen]
+impl ParsedChat {
+    #[wasm_bindgen(constructor)]
+    pub fn new(raw: &str) -> Result<ParsedChat, JsValue> {
+        ParsedChat::parse(raw).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Like the constructor, with the options the summary was built with.
+    #[wasm_bindgen(js_name = with_options)]
+    pub fn with_options_js(raw: &str, options: AnalyzeOptions) -> Result<ParsedChat, JsValue> {
+        ParsedChat::parse_with_options(raw, &options).map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen(js_name = message_count)]
+    pub fn message_count_js(&self) -> u32 {
+        self.message_count() as u32
+    }
+
+    #[wasm_bindgen(js_name = top_words_in_range)]
+    pub fn top_words_in_range_js(
+        &self,
+        start: &str,
+        end: &str,
+        n: u32,
+    ) -> Result<JsValue, JsValue> {
+        let words = self
+            .top_words_in_range(start, end, n as usize)
+            .map_err(|e| JsValue::from_str(&e))?;
+        serde_wasm_bindgen::to_value(&words).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = top_phrases_in_range)]
+    pub fn top_phrases_in_range_js(
+        &self,
+        start: &str,
+        end: &str,
+        n: u32,
+    ) -> Result<JsValue, JsValue> {
+        let phrases = self
+            .top_phrases_in_range(start, end, n as usize)
+            .map_err(|e| JsValue::from_str(&e))?;
+        serde_wasm_bindgen::to_value(&phrases).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
+
+fn parse_day(day: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(day, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{day}', expected YYYY-MM-DD"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::summarize_with_options;
+    use crate::options::TextFilter;
+
+    const RAW: &str = "[1/1/24, 10:00:00 AM] Alice: pizza pizza tonight\n[1/2/24, 10:00:00 AM] Bob: pizza sounds great\n[3/1/24, 10:00:00 AM] Alice: hiking hiking tomorrow\n[3/2/24, 10:00:00 AM] Bob: hiking sounds fun";
+
+    fn labels(counts: &[Count]) -> Vec<&str> {
+        counts.iter().map(|c| c.label.as_str()).collect()
+    }
+
+    #[test]
+    fn narrowing_the_range_changes_top_words() {
+        let chat = ParsedChat::parse(RAW).unwrap();
+        assert_eq!(chat.message_count(), 4);
+
+        let all = chat
+            .top_words_in_range("2024-01-01", "2024-12-31", 10)
+            .unwrap();
+        assert!(labels(&all).contains(&"pizza"));
+        assert!(labels(&all).contains(&"hiking"));
+
+        let march = chat
+            .top_words_in_range("2024-03-01", "2024-03-31", 10)
+            .unwrap();
+        assert_eq!(march[0].label, "hiking");
+        assert!(!labels(&march).contains(&"pizza"));
+    }
+
+    #[test]
+    fn phrases_respect_the_range() {
+        let chat = ParsedChat::parse(RAW).unwrap();
+        let january = chat
+            .top_phrases_in_range("2024-01-01", "2024-01-31", 50)
+            .unwrap();
+        assert!(labels(&january).iter().all(|p| !p.contains("hiking")));
+    }
+
+    #[test]
+    fn range_queries_apply_the_options() {
+        let raw = format!("{RAW}\n[3/3/24, 10:00:00 AM] Bob: ask Alice about 2024 hiking");
+        let options = AnalyzeOptions::builder()
+            .anonymize(true)
+            .text_filter(TextFilter::RegexReplace {
+                pattern: "pizza".into(),
+                replacement: String::new(),
+            })
+            .build()
+            .unwrap();
+        let chat = ParsedChat::parse_with_options(&raw, &options).unwrap();
+        let words = chat
+            .top_words_in_range("2024-01-01", "2024-12-31", 20)
+            .unwrap();
+        assert!(!labels(&words).contains(&"pizza"));
+        assert!(!labels(&words).contains(&"alice"));
+        assert!(!labels(&words).contains(&"2024"));
+
+        let summary = summarize_with_options(&raw, 20, 5, &options).unwrap();
+        let pairs = |counts: &[Count]| -> Vec<(String, u32)> {
+            counts.iter().map(|c| (c.label.clone(), c.value)).collect()
+        };
+        assert_eq!(pairs(&words), pairs(&summary.top_words));
+    }
+
+    #[test]
+    fn bad_input_is_an_error() {
+        assert!(ParsedChat::parse("not a chat").is_err());
+        let chat = ParsedChat::parse(RAW).unwrap();
+        assert!(chat.top_words_in_range("March", "2024-03-31", 5).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use crate::types::{Count, CountDelta, FloatDelta, Summary, SummaryDiff};
+
+/// Compares an earlier summary of a chat with a later one (e.g. last upload vs. this
+/// one) for a "what changed" view. Senders or months present on only one side are
+/// compared against zero.
+///
+/// ```
+/// let old = chat_core_wasm::summarize("[1/1/24, 9:00:00 AM] Alice: hi", 10, 5).unwrap();
+/// let new = chat_core_wasm::summarize(
+///     "[1/1/24, 9:00:00 AM] Alice: hi\n[1/2/24, 9:00:00 AM] Bob: hello",
+///     10,
+///     5,
+/// )
+/// .unwrap();
+/// let diff = chat_core_wasm::diff(&old, &new);
+/// assert_eq!(diff.total_messages.delta, 1);
+/// ```
+pub fn diff(old: &Summary, new: &Summary) -> SummaryDiff {
+    let streak = |s: &Summary| s.longest_streak.as_ref().map_or(0, |s| s.days);
+    let person_streaks = |s: &Summary| -> Vec<Count> {
+        s.per_person_daily
+            .iter()
+            .map(|p| Count {
+                label: p.name.clone(),
+                value: p.longest_streak,
+            })
+            .collect()
+    };
+
+    SummaryDiff {
+        total_messages: count_delta(
+            "total",
+            old.total_messages as u32,
+            new.total_messages as u32,
+        ),
+        by_sender: count_deltas(&old.by_sender, &new.by_sender),
+        monthly: count_deltas(&old.monthly, &new.monthly),
+        new_top_words: newly_listed(&old.top_words, &new.top_words),
+        new_top_emojis: newly_listed(&old.top_emojis, &new.top_emojis),
+        sentiment: sentiment_deltas(old, new),
+        longest_streak: count_delta("chat", streak(old), streak(new)),
+        longest_streak_by_sender: count_deltas(&person_streaks(old), &person_streaks(new)),
+    }
+}
+
+fn count_delta(label: &str, old: u32, new: u32) -> CountDelta {
+    CountDelta {
+        label: label.to_string(),
+        old,
+        new,
+        delta: new as i32 - old as i32,
+    }
+}
+
+/// One delta per label in either list, sorted by label.
+fn count_deltas(old: &[Count], new: &[Count]) -> Vec<CountDelta> {
+    let mut pairs: BTreeMap<&str, (u32, u32)> = BTreeMap::new();
+    for c in old {
+        pairs.entry(c.label.as_str()).or_default().0 += c.value;
+    }
+    for c in new {
+        pairs.entry(c.label.as_str()).or_default().1 += c.value;
+    }
+    pairs
+        .into_iter()
+        .map(|(label, (o, n))| count_delta(label, o, n))
+        .collect()
+}
+
+/// Labels of `new` missing from `old`, in `new`'s (ranked) order.
+fn newly_listed(old: &[Count], new: &[Count]) -> Vec<String> {
+    let before: HashSet<&str> = old.iter().map(|c| c.label.as_str()).collect();
+    new.iter()
+        .filter(|c| !before.contains(c.label.as_str()))
+        .map(|c| c.label.clone())
+        .collect()
+}
+
+fn sentiment_deltas(old: &Summary, new: &Summary) -> Vec<FloatDelta> {
+    let mean = |s: &Summary, name: &str| {
+        s.sentiment_overall
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.mean)
+    };
+    let names: BTreeSet<&str> = old
+        .sentiment_overall
+        .iter()
+        .chain(&new.sentiment_overall)
+        .map(|p| p.name.as_str())
+        .collect();
+    names
+        .into_iter()
+        .map(|name| {
+            let (o, n) = (mean(old, name), mean(new, name));
+            FloatDelta {
+                label: name.to_string(),
+                old: o,
+                new: n,
+                delta: n.unwrap_or(0.0) - o.unwrap_or(0.0),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::summarize;
+
+    const BASE: &str = "[1/1/24, 9:00:00 AM] Alice: good morning sunshine\n[1/1/24, 9:05:00 AM] Bob: morning\n[1/2/24, 9:00:00 AM] Alice: coffee time\n[1/2/24, 9:10:00 AM] Bob: coffee sounds great";
+
+    fn superset() -> String {
+        format!(
+            "{BASE}\n[1/3/24, 9:00:00 AM] Alice: pancakes pancakes 🥞\n[2/1/24, 8:00:00 AM] Carol: hello everyone, terrible news\n[2/1/24, 8:05:00 AM] Alice: oh no"
+        )
+    }
+
+    fn find<'a>(deltas: &'a [CountDelta], label: &str) -> &'a CountDelta {
+        deltas.iter().find(|d| d.label == label).unwrap()
+    }
+
+    #[test]
+    fn diff_against_superset() {
+        let old = summarize(BASE, 20, 5).unwrap();
+        let new = summarize(&superset(), 20, 5).unwrap();
+        let d = diff(&old, &new);
+
+        assert_eq!((d.total_messages.old, d.total_messages.new), (4, 7));
+        assert_eq!(d.total_messages.delta, 3);
+        assert_eq!(find(&d.by_sender, "Alice").delta, 2);
+        assert_eq!(find(&d.by_sender, "Bob").delta, 0);
+        // Carol only appears in the new summary.
+        let carol = find(&d.by_sender, "Carol");
+        assert_eq!((carol.old, carol.new), (0, 1));
+        assert_eq!(find(&d.monthly, "2024-02").delta, 2);
+        assert!(d.new_top_words.contains(&"pancakes".to_string()));
+        assert!(!d.new_top_words.contains(&"coffee".to_string()));
+        assert_eq!(d.new_top_emojis, vec!["🥞".to_string()]);
+        assert_eq!((d.longest_streak.old, d.longest_streak.new), (2, 3));
+        assert_eq!(find(&d.longest_streak_by_sender, "Alice").delta, 1);
+
+        let carol_mood = d.sentiment.iter().find(|s| s.label == "Carol").unwrap();
+        assert_eq!(carol_mood.old, None);
+        assert!(carol_mood.new.is_some());
+    }
+
+    #[test]
+    fn diff_of_identical_summaries_is_flat() {
+        let a = summarize(BASE, 20, 5).unwrap();
+        let b = summarize(BASE, 20, 5).unwrap();
+        let d = diff(&a, &b);
+        assert_eq!(d.total_messages.delta, 0);
+        assert!(d.by_sender.iter().all(|c| c.delta == 0));
+        assert!(d.new_top_words.is_empty());
+        assert!(d.sentiment.iter().all(|s| s.delta == 0.0));
+    }
+}
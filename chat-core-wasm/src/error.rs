@@ -0,0 +1,45 @@
+use std::fmt;
+
+use wasm_bindgen::JsValue;
+
+/// Why an analysis could not run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnalyzeError {
+    /// An [`crate::AnalyzeOptions`] field was out of range or malformed.
+    InvalidOption { field: &'static str, reason: String },
+    /// The input exceeds one of [`crate::Limits`]; `limit` names the field.
+    TooLarge {
+        limit: &'static str,
+        observed: usize,
+        max: usize,
+    },
+    /// The chat could not be analyzed.
+    Analysis(String),
+    /// The summary could not be serialized or written out.
+    Output(String),
+}
+
+impl fmt::Display for AnalyzeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalyzeError::InvalidOption { field, reason } => {
+                write!(f, "Invalid option `{field}`: {reason}")
+            }
+            AnalyzeError::TooLarge {
+                limit,
+                observed,
+                max,
+            } => write!(f, "Input too large: {observed} exceeds `{limit}` ({max})"),
+            AnalyzeError::Analysis(reason) => f.write_str(reason),
+            AnalyzeError::Output(reason) => write!(f, "Could not write summary: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for AnalyzeError {}
+
+impl From<AnalyzeError> for JsValue {
+    fn from(e: AnalyzeError) -> Self {
+        JsValue::from_str(&e.to_string())
+    }
+}
@@ -0,0 +1,164 @@
+use once_cell::sync::OnceCell;
+use regex::{Regex, RegexBuilder};
+use std::borrow::Cow;
+
+use crate::options::TextFilter;
+use crate::parsing::Message;
+
+/// Caps on user-supplied `RegexReplace` filters. The regex crate matches in linear
+/// time, so these only bound compile cost and per-message work.
+const MAX_REGEX_FILTERS: usize = 8;
+const MAX_PATTERN_LEN: usize = 256;
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+enum CompiledFilter {
+    StripQuotedLines,
+    StripCodeBlocks,
+    RegexReplace { re: Regex, replacement: String },
+}
+
+/// Validated text filters, ready to run over message text.
+pub(crate) struct TextFilters {
+    filters: Vec<CompiledFilter>,
+}
+
+impl TextFilters {
+    pub(crate) fn compile(filters: &[TextFilter]) -> Result<Self, String> {
+        let regex_count = filters
+            .iter()
+            .filter(|f| matches!(f, TextFilter::RegexReplace { .. }))
+            .count();
+        if regex_count > MAX_REGEX_FILTERS {
+            return Err(format!(
+                "Too many regex text filters ({regex_count}, max {MAX_REGEX_FILTERS})"
+            ));
+        }
+
+        let filters = filters
+            .iter()
+            .map(|f| match f {
+                TextFilter::StripQuotedLines => Ok(CompiledFilter::StripQuotedLines),
+                TextFilter::StripCodeBlocks => Ok(CompiledFilter::StripCodeBlocks),
+                TextFilter::RegexReplace {
+                    pattern,
+                    replacement,
+                } => {
+                    if pattern.len() > MAX_PATTERN_LEN {
+                        return Err(format!(
+                            "Text filter pattern is too long (max {MAX_PATTERN_LEN} bytes)"
+                        ));
+                    }
+                    let re = RegexBuilder::new(pattern)
+                        .size_limit(REGEX_SIZE_LIMIT)
+                        .build()
+                        .map_err(|e| format!("Invalid text filter pattern: {e}"))?;
+                    Ok(CompiledFilter::RegexReplace {
+                        re,
+                        replacement: replacement.clone(),
+                    })
+                }
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(TextFilters { filters })
+    }
+
+    pub(crate) fn apply(&self, text: &str) -> String {
+        let mut out = text.to_string();
+        for filter in &self.filters {
+            out = match filter {
+                CompiledFilter::StripQuotedLines => strip_quoted_lines(&out),
+                CompiledFilter::StripCodeBlocks => code_re().replace_all(&out, " ").into_owned(),
+                CompiledFilter::RegexReplace { re, replacement } => {
+                    re.replace_all(&out, replacement.as_str()).into_owned()
+                }
+            };
+        }
+        out
+    }
+
+    /// Filtered copies of `messages`, or the originals untouched when there are no filters.
+    pub(crate) fn apply_all<'a>(&self, messages: &'a [Message]) -> Cow<'a, [Message]> {
+        if self.filters.is_empty() {
+            return Cow::Borrowed(messages);
+        }
+        Cow::Owned(
+            messages
+                .iter()
+                .map(|m| Message {
+                    text: self.apply(&m.text),
+                    ..m.clone()
+                })
+                .collect(),
+        )
+    }
+}
+
+fn strip_quoted_lines(text: &str) -> String {
+    text.lines()
+        .filter(|line| !line.trim_start().starts_with('>'))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Fenced ```blocks``` (possibly spanning lines) and inline `code` spans.
+fn code_re() -> &'static Regex {
+    static RE: OnceCell<Regex> = OnceCell::new();
+    RE.get_or_init(|| {
+        // SAFE: compile-time-constant pattern; independent of user input.
+        Regex::new(r"(?s)```.*?```|`[^`\n]+`").expect("valid regex")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regex_filter(pattern: &str, replacement: &str) -> TextFilter {
+        TextFilter::RegexReplace {
+            pattern: pattern.into(),
+            replacement: replacement.into(),
+        }
+    }
+
+    #[test]
+    fn strips_quoted_lines() {
+        let filters = TextFilters::compile(&[TextFilter::StripQuotedLines]).unwrap();
+        assert_eq!(filters.apply("> old message\nmy reply"), "my reply");
+    }
+
+    #[test]
+    fn strips_fenced_and_inline_code() {
+        let filters = TextFilters::compile(&[TextFilter::StripCodeBlocks]).unwrap();
+        let out = filters.apply("try ```\nfn main() {}\n``` or `cargo run` now");
+        assert!(!out.contains("main"));
+        assert!(!out.contains("cargo"));
+        assert!(out.starts_with("try"));
+        assert!(out.ends_with("now"));
+    }
+
+    #[test]
+    fn regex_replace_runs_in_order() {
+        let filters =
+            TextFilters::compile(&[regex_filter(r"\bu\b", "you"), regex_filter("you", "YOU")])
+                .unwrap();
+        assert_eq!(filters.apply("see u soon"), "see YOU soon");
+    }
+
+    #[test]
+    fn rejects_invalid_and_oversized_patterns() {
+        assert!(TextFilters::compile(&[regex_filter("(unclosed", "")]).is_err());
+        let long = "a".repeat(MAX_PATTERN_LEN + 1);
+        assert!(TextFilters::compile(&[regex_filter(&long, "")]).is_err());
+        let many: Vec<TextFilter> = (0..=MAX_REGEX_FILTERS)
+            .map(|_| regex_filter("x", "y"))
+            .collect();
+        assert!(TextFilters::compile(&many).is_err());
+    }
+
+    #[test]
+    fn apply_all_borrows_without_filters() {
+        let filters = TextFilters::compile(&[]).unwrap();
+        let messages: Vec<Message> = Vec::new();
+        assert!(matches!(filters.apply_all(&messages), Cow::Borrowed(_)));
+    }
+}
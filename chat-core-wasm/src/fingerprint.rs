@@ -0,0 +1,185 @@
+use crate::input::{InputMessage, InputTimestamp};
+use crate::options::AnalyzeOptions;
+use crate::types::AnalysisMeta;
+
+/// The crate version stamped on every summary and mixed into every fingerprint.
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const FNV_OFFSET: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+const FNV_PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+/// 128-bit FNV-1a: fast, not cryptographic, and the same on every platform and build,
+/// unlike `std`'s `DefaultHasher`.
+struct Fnv128(u128);
+
+impl Fnv128 {
+    fn new() -> Self {
+        Fnv128(FNV_OFFSET)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u128;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    /// Writes `bytes` and a separator, so ("ab", "c") and ("a", "bc") differ.
+    fn field(&mut self, bytes: &[u8]) {
+        self.write(bytes);
+        self.write(&[0xff]);
+    }
+
+    fn finish(&self) -> String {
+        format!("{:032x}", self.0)
+    }
+}
+
+/// Hash of every option, read from their `Debug` form so new options are covered
+/// without listing them here.
+pub(crate) fn options_hash(options: &AnalyzeOptions) -> String {
+    let mut h = Fnv128::new();
+    h.write(format!("{options:?}").as_bytes());
+    h.finish()
+}
+
+/// A 32-hex-digit key for caching the analysis of `raw` under `options`, e.g. in
+/// IndexedDB. It covers the raw bytes, the top-word and top-emoji limits, the options
+/// and the crate version, so a new version never reuses an older version's summary.
+///
+/// ```
+/// use chat_core_wasm::{content_fingerprint, summarize_with_options, AnalyzeOptions};
+///
+/// let raw = "[1/1/24, 1:00:00 PM] A: hi\n[1/1/24, 1:01:00 PM] B: hey";
+/// let options = AnalyzeOptions::default();
+/// let summary = summarize_with_options(raw, 10, 5, &options).unwrap();
+/// assert_eq!(summary.analysis_meta.fingerprint, content_fingerprint(raw, 10, 5, &options));
+/// ```
+pub fn content_fingerprint(
+    raw: &str,
+    top_words_n: usize,
+    top_emojis_n: usize,
+    options: &AnalyzeOptions,
+) -> String {
+    let mut h = fingerprint_header(top_words_n, top_emojis_n, options);
+    h.write(raw.as_bytes());
+    h.finish()
+}
+
+/// `content_fingerprint` for structured input to `summarize_messages`.
+pub(crate) fn messages_fingerprint(
+    input: &[InputMessage],
+    top_words_n: usize,
+    top_emojis_n: usize,
+    options: &AnalyzeOptions,
+) -> String {
+    let mut h = fingerprint_header(top_words_n, top_emojis_n, options);
+    for m in input {
+        match &m.timestamp {
+            InputTimestamp::EpochMillis(ms) => h.field(&ms.to_le_bytes()),
+            InputTimestamp::Iso(s) => h.field(s.as_bytes()),
+        }
+        h.field(m.sender.as_bytes());
+        h.field(m.text.as_bytes());
+    }
+    h.finish()
+}
+
+fn fingerprint_header(top_words_n: usize, top_emojis_n: usize, options: &AnalyzeOptions) -> Fnv128 {
+    let mut h = Fnv128::new();
+    h.field(VERSION.as_bytes());
+    h.field(&(top_words_n as u64).to_le_bytes());
+    h.field(&(top_emojis_n as u64).to_le_bytes());
+    h.field(options_hash(options).as_bytes());
+    h
+}
+
+pub(crate) fn analysis_meta(fingerprint: String, options: &AnalyzeOptions) -> AnalysisMeta {
+    AnalysisMeta {
+        fingerprint,
+        version: VERSION.to_string(),
+        options_hash: options_hash(options),
+    }
+}
+
+/// Meta for `Summary::merge`: the fingerprint covers both parts whichever order they
+/// are merged in, and the options hash is kept only if both parts agree on it.
+pub(crate) fn merge_analysis_meta(a: &AnalysisMeta, b: &AnalysisMeta) -> AnalysisMeta {
+    let combine = |x: &str, y: &str| {
+        let (first, second) = if x <= y { (x, y) } else { (y, x) };
+        let mut h = Fnv128::new();
+        h.field(first.as_bytes());
+        h.field(second.as_bytes());
+        h.finish()
+    };
+    AnalysisMeta {
+        fingerprint: combine(&a.fingerprint, &b.fingerprint),
+        version: VERSION.to_string(),
+        options_hash: if a.options_hash == b.options_hash {
+            a.options_hash.clone()
+        } else {
+            combine(&a.options_hash, &b.options_hash)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{summarize, summarize_messages};
+    use crate::types::Summary;
+
+    const RAW: &str = "[1/1/24, 1:00:00 PM] A: hi\n[1/1/24, 1:01:00 PM] B: hey";
+
+    #[test]
+    fn fingerprint_is_stable_and_tracks_content_and_options() {
+        let options = AnalyzeOptions::default();
+        let fp = content_fingerprint(RAW, 10, 5, &options);
+        assert_eq!(fp.len(), 32);
+        assert!(fp.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(
+            fp,
+            content_fingerprint(RAW, 10, 5, &AnalyzeOptions::default())
+        );
+
+        let anonymized = AnalyzeOptions::builder().anonymize(true).build().unwrap();
+        assert_ne!(fp, content_fingerprint(RAW, 10, 5, &anonymized));
+        assert_ne!(options_hash(&options), options_hash(&anonymized));
+        assert_ne!(fp, content_fingerprint(&format!("{RAW}!"), 10, 5, &options));
+        assert_ne!(fp, content_fingerprint(RAW, 20, 5, &options));
+        assert_ne!(fp, content_fingerprint(RAW, 10, 10, &options));
+        assert_ne!(fp, content_fingerprint(RAW, 5, 10, &options));
+        // The published FNV-1a test vector: a change to the hash would orphan every
+        // cached summary, so it has to be deliberate.
+        let mut h = Fnv128::new();
+        h.write(b"a");
+        assert_eq!(h.finish(), "d228cb696f1a8caf78912b704e4a8964");
+    }
+
+    #[test]
+    fn summaries_carry_their_meta() {
+        let summary = summarize(RAW, 10, 5).unwrap();
+        let meta = &summary.analysis_meta;
+        let options = AnalyzeOptions::default();
+        assert_eq!(meta.fingerprint, content_fingerprint(RAW, 10, 5, &options));
+        assert_eq!(meta.options_hash, options_hash(&options));
+        assert_eq!(meta.version, env!("CARGO_PKG_VERSION"));
+
+        let input = [InputMessage {
+            timestamp: InputTimestamp::Iso("2024-01-01T13:00:00".into()),
+            sender: "A".into(),
+            text: "hi".into(),
+        }];
+        let structured = summarize_messages(&input, 10, 5, &options).unwrap();
+        assert_eq!(
+            structured.analysis_meta.fingerprint,
+            messages_fingerprint(&input, 10, 5, &options)
+        );
+
+        let other = || summarize("[2/1/24, 1:00:00 PM] A: again", 10, 5).unwrap();
+        let ab = Summary::merge(summarize(RAW, 10, 5).unwrap(), other()).analysis_meta;
+        let ba = Summary::merge(other(), summary).analysis_meta;
+        assert_eq!(ab.fingerprint, ba.fingerprint);
+        assert_eq!(ab.options_hash, options_hash(&options));
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+
+use crate::parsing::Message;
+use crate::text::{extract_emojis, is_media_omitted_message};
+use crate::types::{Count, HeadToHead, HeadToHeadRow, Summary};
+
+/// Rows where the smaller value wins; every other row goes to the larger value.
+const LOWER_WINS: [&str; 1] = ["reply_minutes"];
+
+/// Lines the two people of a DM up side by side across the per-person metrics already
+/// in `summary`. Group chats (or single-sender files) get `None`.
+pub(crate) fn build_head_to_head(summary: &Summary, messages: &[Message]) -> Option<HeadToHead> {
+    let mut emojis: HashMap<&str, u32> = HashMap::new();
+    let mut media: HashMap<&str, u32> = HashMap::new();
+    for m in messages {
+        *emojis.entry(m.sender.as_str()).or_insert(0) += extract_emojis(&m.text).len() as u32;
+        if is_media_omitted_message(&m.text) {
+            *media.entry(m.sender.as_str()).or_insert(0) += 1;
+        }
+    }
+    assemble(summary, &emojis, &media)
+}
+
+/// Rebuilds the block for a merged summary. Emoji and media totals are not kept
+/// elsewhere in `Summary`, so they are summed from the parts' own head-to-head rows.
+pub(crate) fn merge_head_to_head(
+    merged: &Summary,
+    parts: [Option<&HeadToHead>; 2],
+) -> Option<HeadToHead> {
+    let mut emojis: HashMap<&str, u32> = HashMap::new();
+    let mut media: HashMap<&str, u32> = HashMap::new();
+    for h2h in parts.into_iter().flatten() {
+        for row in &h2h.rows {
+            let target = match row.metric.as_str() {
+                "emojis" => &mut emojis,
+                "media" => &mut media,
+                _ => continue,
+            };
+            *target.entry(h2h.left.as_str()).or_insert(0) += row.left as u32;
+            *target.entry(h2h.right.as_str()).or_insert(0) += row.right as u32;
+        }
+    }
+    assemble(merged, &emojis, &media)
+}
+
+fn assemble(
+    summary: &Summary,
+    emojis: &HashMap<&str, u32>,
+    media: &HashMap<&str, u32>,
+) -> Option<HeadToHead> {
+    if summary.by_sender.len() != 2 {
+        return None;
+    }
+    let mut names: Vec<&str> = summary.by_sender.iter().map(|c| c.label.as_str()).collect();
+    names.sort();
+    let (left, right) = (names[0], names[1]);
+
+    let count_of = |list: &[Count], name: &str| {
+        list.iter()
+            .find(|c| c.label == name)
+            .map_or(0.0, |c| c.value as f32)
+    };
+    // One (metric, value) table per person, so each value sits next to its label.
+    let metrics = |name: &str| -> [(&'static str, f32); 9] {
+        [
+            ("messages", count_of(&summary.by_sender, name)),
+            (
+                "words",
+                summary
+                    .person_stats
+                    .iter()
+                    .find(|p| p.name == name)
+                    .map_or(0.0, |p| p.total_words as f32),
+            ),
+            ("emojis", emojis.get(name).copied().unwrap_or(0) as f32),
+            (
+                "conversations_started",
+                count_of(&summary.conversation_starters, name),
+            ),
+            (
+                "reply_minutes",
+                summary
+                    .response_latency
+                    .iter()
+                    .find(|r| r.name == name)
+                    .map_or(0.0, |r| r.median_minutes),
+            ),
+            (
+                "replies",
+                summary
+                    .response_latency
+                    .iter()
+                    .find(|r| r.name == name)
+                    .map_or(0.0, |r| r.replies as f32),
+            ),
+            (
+                "sentiment",
+                summary
+                    .sentiment_overall
+                    .iter()
+                    .find(|s| s.name == name)
+                    .map_or(0.0, |s| s.mean),
+            ),
+            (
+                "longest_streak_days",
+                summary
+                    .per_person_daily
+                    .iter()
+                    .find(|p| p.name == name)
+                    .map_or(0.0, |p| p.longest_streak as f32),
+            ),
+            ("media", media.get(name).copied().unwrap_or(0) as f32),
+        ]
+    };
+    let (l, r) = (metrics(left), metrics(right));
+    let value_of = |table: &[(&str, f32)], metric: &str| {
+        table
+            .iter()
+            .find(|(m, _)| *m == metric)
+            .map_or(0.0, |(_, v)| *v)
+    };
+    // reply_minutes only means something if both people actually replied.
+    let both_replied = value_of(&l, "replies") > 0.0 && value_of(&r, "replies") > 0.0;
+
+    let rows = l
+        .iter()
+        .zip(&r)
+        .map(|(&(metric, left_value), &(_, right_value))| {
+            let contested = metric != "reply_minutes" || both_replied;
+            HeadToHeadRow {
+                metric: metric.to_string(),
+                left: left_value,
+                right: right_value,
+                winner: contested
+                    .then(|| row_winner(metric, left_value, right_value, left, right))
+                    .flatten(),
+            }
+        })
+        .collect();
+
+    Some(HeadToHead {
+        left: left.to_string(),
+        right: right.to_string(),
+        rows,
+    })
+}
+
+fn row_winner(
+    metric: &str,
+    left: f32,
+    right: f32,
+    left_name: &str,
+    right_name: &str,
+) -> Option<String> {
+    if left == right {
+        return None;
+    }
+    let left_wins = (left > right) != LOWER_WINS.contains(&metric);
+    Some(if left_wins { left_name } else { right_name }.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::summarize;
+    use crate::parsing::parse_messages;
+
+    fn head_to_head(raw: &str) -> Option<HeadToHead> {
+        let summary = summarize(raw, 10, 5).unwrap();
+        build_head_to_head(&summary, &parse_messages(raw))
+    }
+
+    fn row<'a>(h2h: &'a HeadToHead, metric: &str) -> &'a HeadToHeadRow {
+        h2h.rows.iter().find(|r| r.metric == metric).unwrap()
+    }
+
+    #[test]
+    fn row_winner_prefers_higher_unless_lower_wins() {
+        assert_eq!(
+            row_winner("messages", 3.0, 1.0, "A", "B").as_deref(),
+            Some("A")
+        );
+        assert_eq!(
+            row_winner("messages", 1.0, 3.0, "A", "B").as_deref(),
+            Some("B")
+        );
+        assert_eq!(
+            row_winner("reply_minutes", 1.0, 3.0, "A", "B").as_deref(),
+            Some("A")
+        );
+        assert_eq!(row_winner("messages", 2.0, 2.0, "A", "B"), None);
+    }
+
+    #[test]
+    fn dm_rows_pair_both_people() {
+        let raw = "[1/1/24, 10:00:00 AM] Bob: hey 😀😀\n[1/1/24, 10:01:00 AM] Alice: hi\n[1/1/24, 10:11:00 AM] Bob: <Media omitted>\n[1/2/24, 9:00:00 AM] Bob: morning";
+        let h2h = head_to_head(raw).expect("DM has a head-to-head");
+        assert_eq!((h2h.left.as_str(), h2h.right.as_str()), ("Alice", "Bob"));
+
+        let messages = row(&h2h, "messages");
+        assert_eq!((messages.left, messages.right), (1.0, 3.0));
+        assert_eq!(messages.winner.as_deref(), Some("Bob"));
+        assert_eq!(row(&h2h, "emojis").winner.as_deref(), Some("Bob"));
+        assert_eq!(row(&h2h, "media").winner.as_deref(), Some("Bob"));
+        assert_eq!(
+            row(&h2h, "longest_streak_days").winner.as_deref(),
+            Some("Bob")
+        );
+        // Alice replied after 1 minute, Bob after 10: the faster replier wins.
+        assert_eq!(row(&h2h, "reply_minutes").winner.as_deref(), Some("Alice"));
+    }
+
+    #[test]
+    fn ties_and_one_sided_replies_have_no_winner() {
+        let raw = "[1/1/24, 10:00:00 AM] Alice: hello\n[1/1/24, 10:00:30 AM] Bob: hello";
+        let h2h = head_to_head(raw).unwrap();
+        assert_eq!(row(&h2h, "messages").winner, None);
+        assert_eq!(row(&h2h, "words").winner, None);
+        // Only Bob replied, so the reply-time row is not contested.
+        assert_eq!(row(&h2h, "reply_minutes").winner, None);
+    }
+
+    #[test]
+    fn group_chats_have_no_head_to_head() {
+        let raw =
+            "[1/1/24, 10:00:00 AM] A: hi\n[1/1/24, 10:01:00 AM] B: hi\n[1/1/24, 10:02:00 AM] C: hi";
+        assert!(head_to_head(raw).is_none());
+    }
+}
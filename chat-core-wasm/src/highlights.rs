@@ -0,0 +1,405 @@
+use chrono::NaiveDate;
+
+use crate::types::{Highlight, HighlightKind, Summary};
+
+/// Highlights kept, in template order.
+const MAX_HIGHLIGHTS: usize = 6;
+/// Replies each person needs before their reply speed is compared.
+const MIN_REPLIES: u32 = 5;
+/// The slower replier must take at least this many times as long.
+const MIN_REPLY_RATIO: f32 = 1.5;
+/// Emojis in the chat before anyone's share of them is worth mentioning.
+const MIN_EMOJIS: f32 = 20.0;
+/// Share of messages or emojis (percent) that makes one person stand out.
+const DOMINANT_PERCENT: f32 = 60.0;
+/// Days from first to last message before gaps between them say anything.
+const MIN_SPAN_DAYS: i64 = 30;
+/// Longest silence (days) still worth bragging about.
+const MAX_SILENCE_DAYS: i64 = 7;
+/// Share of messages (percent) sent from midnight to 5am that makes a night-owl chat.
+const NIGHT_PERCENT: f32 = 10.0;
+const MIN_BUSIEST_DAY: u32 = 50;
+const MIN_CONVERSATION: u32 = 50;
+const MIN_STREAK_WEEKS: u32 = 4;
+const MIN_REVIVALS: u32 = 3;
+const MIN_VOICE_MINUTES: f32 = 10.0;
+
+/// Short claims about the chat with the number behind each, e.g. "Alice replies 3.2×
+/// faster than Bob". Every template whose condition holds contributes one, in library
+/// order, and the first `MAX_HIGHLIGHTS` are kept. Only reads unsampled fields.
+pub(crate) fn build_highlights(summary: &Summary) -> Vec<Highlight> {
+    let templates: [fn(&Summary) -> Option<Highlight>; 11] = [
+        reply_speed,
+        emoji_share,
+        every_day,
+        never_apart,
+        top_talker,
+        night_owls,
+        busiest_day,
+        longest_conversation,
+        weekly_streak,
+        silence_breaker,
+        voice_notes,
+    ];
+    templates
+        .iter()
+        .filter_map(|t| t(summary))
+        .take(MAX_HIGHLIGHTS)
+        .collect()
+}
+
+fn highlight(kind: HighlightKind, value: f32, text: String) -> Highlight {
+    Highlight { text, kind, value }
+}
+
+fn reply_speed(summary: &Summary) -> Option<Highlight> {
+    let mut repliers: Vec<_> = summary
+        .response_latency
+        .iter()
+        .filter(|r| r.replies >= MIN_REPLIES && r.median_minutes > 0.0)
+        .collect();
+    repliers.sort_by(|a, b| {
+        a.median_minutes
+            .total_cmp(&b.median_minutes)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    let (fast, slow) = (repliers.first()?, repliers.last()?);
+    let ratio = slow.median_minutes / fast.median_minutes;
+    (ratio >= MIN_REPLY_RATIO).then(|| {
+        highlight(
+            HighlightKind::ReplySpeed,
+            ratio,
+            format!(
+                "{} replies {ratio:.1}× faster than {}",
+                fast.name, slow.name
+            ),
+        )
+    })
+}
+
+/// Needs the head-to-head's emoji row, so DMs only.
+fn emoji_share(summary: &Summary) -> Option<Highlight> {
+    let h2h = summary.head_to_head.as_ref()?;
+    let row = h2h.rows.iter().find(|r| r.metric == "emojis")?;
+    let total = row.left + row.right;
+    let (name, count) = if row.left >= row.right {
+        (&h2h.left, row.left)
+    } else {
+        (&h2h.right, row.right)
+    };
+    let percent = count * 100.0 / total;
+    (total >= MIN_EMOJIS && percent >= DOMINANT_PERCENT).then(|| {
+        highlight(
+            HighlightKind::EmojiShare,
+            percent,
+            format!("{name} has sent {percent:.0}% of all emojis"),
+        )
+    })
+}
+
+/// Active days in order, and the days from first to last.
+fn active_days(summary: &Summary) -> Option<(Vec<NaiveDate>, i64)> {
+    let days: Vec<NaiveDate> = summary
+        .daily
+        .iter()
+        .filter_map(|c| NaiveDate::parse_from_str(&c.label, "%Y-%m-%d").ok())
+        .collect();
+    let span = (*days.last()? - *days.first()?).num_days();
+    (span >= MIN_SPAN_DAYS).then_some((days, span))
+}
+
+/// Most silent days between two active days. Gaps inside the chat's longest streak
+/// don't count: the streak only spans them because they fall in ignored ranges.
+fn longest_silence(summary: &Summary, days: &[NaiveDate]) -> i64 {
+    let parse = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok();
+    let bridged = summary
+        .longest_streak
+        .as_ref()
+        .and_then(|s| Some((parse(&s.start)?, parse(&s.end)?)));
+    days.windows(2)
+        .filter(|w| !bridged.is_some_and(|(start, end)| start <= w[0] && w[1] <= end))
+        .map(|w| (w[1] - w[0]).num_days() - 1)
+        .max()
+        .unwrap_or(0)
+}
+
+fn every_day(summary: &Summary) -> Option<Highlight> {
+    let (days, _) = active_days(summary)?;
+    let days_talked = days.len();
+    (longest_silence(summary, &days) == 0).then(|| {
+        highlight(
+            HighlightKind::EveryDay,
+            days_talked as f32,
+            format!("You've talked every single day for {days_talked} days"),
+        )
+    })
+}
+
+fn never_apart(summary: &Summary) -> Option<Highlight> {
+    let (days, _) = active_days(summary)?;
+    let silence = longest_silence(summary, &days);
+    (1..=MAX_SILENCE_DAYS).contains(&silence).then(|| {
+        let unit = if silence == 1 { "day" } else { "days" };
+        highlight(
+            HighlightKind::NeverApart,
+            silence as f32,
+            format!("You've never gone more than {silence} {unit} without talking"),
+        )
+    })
+}
+
+fn top_talker(summary: &Summary) -> Option<Highlight> {
+    let top = summary.share_of_speech.first()?;
+    (summary.share_of_speech.len() >= 2 && top.value >= DOMINANT_PERCENT).then(|| {
+        highlight(
+            HighlightKind::TopTalker,
+            top.value,
+            format!("{} sends {:.0}% of the messages", top.label, top.value),
+        )
+    })
+}
+
+fn night_owls(summary: &Summary) -> Option<Highlight> {
+    let total: u32 = summary.hourly.iter().map(|h| h.value).sum();
+    let night: u32 = summary
+        .hourly
+        .iter()
+        .filter(|h| h.hour < 5)
+        .map(|h| h.value)
+        .sum();
+    if total == 0 {
+        return None;
+    }
+    let percent = night as f32 * 100.0 / total as f32;
+    (percent >= NIGHT_PERCENT).then(|| {
+        highlight(
+            HighlightKind::NightOwls,
+            percent,
+            format!("{percent:.0}% of messages are sent between midnight and 5am"),
+        )
+    })
+}
+
+fn busiest_day(summary: &Summary) -> Option<Highlight> {
+    // The earliest day wins a tie.
+    let day = summary
+        .daily
+        .iter()
+        .rev()
+        .max_by_key(|c| c.value)
+        .filter(|c| c.value >= MIN_BUSIEST_DAY)?;
+    Some(highlight(
+        HighlightKind::BusiestDay,
+        day.value as f32,
+        format!(
+            "The busiest day was {}, with {} messages",
+            day.label, day.value
+        ),
+    ))
+}
+
+fn longest_conversation(summary: &Summary) -> Option<Highlight> {
+    let convo = summary
+        .biggest_conversation
+        .as_ref()
+        .filter(|c| c.message_count >= MIN_CONVERSATION)?;
+    Some(highlight(
+        HighlightKind::LongestConversation,
+        convo.message_count as f32,
+        format!(
+            "The longest conversation ran to {} messages on {}",
+            convo.message_count, convo.date
+        ),
+    ))
+}
+
+fn weekly_streak(summary: &Summary) -> Option<Highlight> {
+    let streak = summary
+        .weekly_streak
+        .as_ref()
+        .filter(|s| s.weeks >= MIN_STREAK_WEEKS)?;
+    Some(highlight(
+        HighlightKind::WeeklyStreak,
+        streak.weeks as f32,
+        format!(
+            "{} weeks in a row with at least {} messages each",
+            streak.weeks, streak.threshold
+        ),
+    ))
+}
+
+fn silence_breaker(summary: &Summary) -> Option<Highlight> {
+    let top = summary
+        .revivals
+        .first()
+        .filter(|r| r.count >= MIN_REVIVALS)?;
+    Some(highlight(
+        HighlightKind::SilenceBreaker,
+        top.count as f32,
+        format!("{} broke the silence {} times", top.name, top.count),
+    ))
+}
+
+fn voice_notes(summary: &Summary) -> Option<Highlight> {
+    let minutes = summary.voice_notes.total_minutes;
+    (minutes >= MIN_VOICE_MINUTES).then(|| {
+        highlight(
+            HighlightKind::VoiceNotes,
+            minutes,
+            format!("{minutes:.0} minutes of voice notes and counting"),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{summarize, summarize_with_options};
+    use crate::options::AnalyzeOptions;
+    use chrono::Datelike;
+
+    fn highlights(raw: &str) -> Vec<(HighlightKind, String, f32)> {
+        summarize(raw, 10, 5)
+            .unwrap()
+            .highlights
+            .into_iter()
+            .map(|h| (h.kind, h.text, h.value))
+            .collect()
+    }
+
+    /// Forty days of Alice writing first with emojis, Bob answering half an hour later
+    /// and Alice following up two minutes after that.
+    fn daily_dm() -> String {
+        let mut lines = Vec::new();
+        for day in 1..=40 {
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Days::new(day);
+            let d = date.format("%-m/%-d/%y");
+            lines.push(format!("[{d}, 9:00:00 AM] Alice: morning 😀😀"));
+            lines.push(format!("[{d}, 9:30:00 AM] Bob: hey"));
+            lines.push(format!("[{d}, 9:32:00 AM] Alice: how are you 😀"));
+        }
+        lines.join("\n")
+    }
+
+    #[test]
+    fn templates_fire_on_a_daily_dm() {
+        let found = highlights(&daily_dm());
+        let texts: Vec<(HighlightKind, &str)> =
+            found.iter().map(|(k, t, _)| (*k, t.as_str())).collect();
+        assert_eq!(
+            texts,
+            vec![
+                (
+                    HighlightKind::ReplySpeed,
+                    "Alice replies 15.0× faster than Bob"
+                ),
+                (
+                    HighlightKind::EmojiShare,
+                    "Alice has sent 100% of all emojis"
+                ),
+                (
+                    HighlightKind::EveryDay,
+                    "You've talked every single day for 40 days"
+                ),
+                (HighlightKind::TopTalker, "Alice sends 67% of the messages"),
+                (
+                    HighlightKind::WeeklyStreak,
+                    "6 weeks in a row with at least 10 messages each"
+                ),
+            ]
+        );
+        assert_eq!(found[0].2, 15.0);
+        assert!((found[3].2 - 200.0 / 3.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn night_group_is_capped_in_template_order() {
+        // A 61-message night on Jan 1, then Carol revives the chat every fourth day with
+        // Dan answering at once and Erin twenty minutes later.
+        let mut lines = Vec::new();
+        for i in 0..60 {
+            let sender = if i % 6 == 5 { "Dan" } else { "Carol" };
+            lines.push(format!("[1/1/24, 1:{i:02}:00 AM] {sender}: message {i}"));
+        }
+        lines.push("[1/1/24, 2:19:00 AM] Erin: catching up".to_string());
+        for offset in (4..=36).step_by(4) {
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Days::new(offset);
+            let d = date.format("%-m/%-d/%y");
+            lines.push(format!("[{d}, 1:00:00 AM] Carol: anyone up?"));
+            lines.push(format!("[{d}, 1:01:00 AM] Dan: yes"));
+            lines.push(format!("[{d}, 1:21:00 AM] Erin: now I am"));
+        }
+        let found = highlights(&lines.join("\n"));
+
+        let kinds: Vec<HighlightKind> = found.iter().map(|(k, _, _)| *k).collect();
+        // The silence breaker fires too but falls past the cap.
+        assert_eq!(
+            kinds,
+            vec![
+                HighlightKind::ReplySpeed,
+                HighlightKind::NeverApart,
+                HighlightKind::TopTalker,
+                HighlightKind::NightOwls,
+                HighlightKind::BusiestDay,
+                HighlightKind::LongestConversation,
+            ]
+        );
+        let texts: Vec<&str> = found.iter().map(|(_, t, _)| t.as_str()).collect();
+        assert_eq!(
+            texts[1],
+            "You've never gone more than 3 days without talking"
+        );
+        assert_eq!(
+            texts[3],
+            "100% of messages are sent between midnight and 5am"
+        );
+        assert_eq!(texts[4], "The busiest day was 2024-01-01, with 61 messages");
+    }
+
+    #[test]
+    fn ignored_ranges_keep_the_every_day_streak() {
+        // Daily for 40 days except a three-day trip from Jan 12 to Jan 14.
+        let mut lines = Vec::new();
+        for day in 1..=40 {
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Days::new(day);
+            if (12..=14).contains(&date.day()) && date.month() == 1 {
+                continue;
+            }
+            let d = date.format("%-m/%-d/%y");
+            lines.push(format!("[{d}, 9:00:00 AM] Alice: morning"));
+            lines.push(format!("[{d}, 9:05:00 AM] Bob: morning"));
+        }
+        let raw = lines.join("\n");
+        let kinds = |options: &AnalyzeOptions| -> Vec<(HighlightKind, String)> {
+            summarize_with_options(&raw, 10, 5, options)
+                .unwrap()
+                .highlights
+                .into_iter()
+                .map(|h| (h.kind, h.text))
+                .collect()
+        };
+
+        let plain = kinds(&AnalyzeOptions::default());
+        assert!(plain.contains(&(
+            HighlightKind::NeverApart,
+            "You've never gone more than 3 days without talking".to_string()
+        )));
+
+        let trip = AnalyzeOptions::builder()
+            .streak_ignore_range("2024-01-12", "2024-01-14")
+            .build()
+            .unwrap();
+        let bridged = kinds(&trip);
+        assert!(bridged.contains(&(
+            HighlightKind::EveryDay,
+            "You've talked every single day for 37 days".to_string()
+        )));
+        assert!(bridged.iter().all(|(k, _)| *k != HighlightKind::NeverApart));
+    }
+
+    #[test]
+    fn short_chats_have_no_highlights() {
+        let raw = "[1/1/24, 10:00:00 AM] A: hi\n[1/1/24, 10:01:00 AM] B: hello";
+        assert!(highlights(raw).is_empty());
+    }
+}
@@ -0,0 +1,253 @@
+use chrono::{DateTime, Datelike, NaiveDateTime};
+use serde::Deserialize;
+use tsify_next::Tsify;
+
+use crate::parsing::{
+    filter_system_messages, merge_sender_variants, truncate_line, Message, ParseReport,
+    MAX_PLAUSIBLE_YEAR,
+};
+
+/// A message supplied already structured, e.g. from another app's backup database, for
+/// `summarize_messages`. The mirror image of `ParsedMessage`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Tsify)]
+#[tsify(from_wasm_abi)]
+pub struct InputMessage {
+    pub timestamp: InputTimestamp,
+    pub sender: String,
+    pub text: String,
+}
+
+/// Milliseconds since the Unix epoch (read as UTC) or an ISO-8601 string. Strings with
+/// an offset keep their local wall-clock time, like the times in a txt export.
+#[derive(Debug, Clone, PartialEq, Deserialize, Tsify)]
+#[serde(untagged)]
+pub enum InputTimestamp {
+    EpochMillis(f64),
+    Iso(String),
+}
+
+impl InputTimestamp {
+    /// `None` for unreadable timestamps and for years past `MAX_PLAUSIBLE_YEAR`, the
+    /// same bound the txt parser applies.
+    fn to_datetime(&self) -> Option<NaiveDateTime> {
+        let dt = match self {
+            InputTimestamp::EpochMillis(ms) if ms.is_finite() => {
+                DateTime::from_timestamp_millis(*ms as i64).map(|dt| dt.naive_utc())
+            }
+            InputTimestamp::EpochMillis(_) => None,
+            InputTimestamp::Iso(s) => {
+                let s = s.trim();
+                DateTime::parse_from_rfc3339(s)
+                    .map(|dt| dt.naive_local())
+                    .ok()
+                    .or_else(|| {
+                        [
+                            "%Y-%m-%dT%H:%M:%S%.f",
+                            "%Y-%m-%d %H:%M:%S%.f",
+                            "%Y-%m-%dT%H:%M",
+                        ]
+                        .iter()
+                        .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+                    })
+            }
+        };
+        dt.filter(|dt| dt.year() <= MAX_PLAUSIBLE_YEAR)
+    }
+}
+
+/// Converts structured messages into the parser's output, time-ordered. Entries with an
+/// unreadable timestamp or a blank sender are skipped and tallied in
+/// `ParseReport::skipped_entries`; system notices are dropped as for txt input.
+/// Text lines longer than `max_line_length` bytes are cut as in `parse_messages_with`.
+pub(crate) fn messages_from_input(
+    input: &[InputMessage],
+    max_line_length: usize,
+) -> (Vec<Message>, ParseReport) {
+    let mut report = ParseReport {
+        lines: input.len(),
+        ..ParseReport::default()
+    };
+    let mut messages: Vec<Message> = input
+        .iter()
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            let dt = entry.timestamp.to_datetime();
+            let sender = entry.sender.trim();
+            if dt.is_none() || sender.is_empty() {
+                report.skipped_entries += 1;
+            }
+            let dt = dt?;
+            let sender = Some(sender).filter(|s| !s.is_empty())?.to_string();
+            let text = if entry.text.lines().any(|l| l.len() > max_line_length) {
+                entry
+                    .text
+                    .lines()
+                    .map(|l| {
+                        let cut = truncate_line(l, max_line_length);
+                        report.truncated_lines += (cut.len() < l.len()) as usize;
+                        cut
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            } else {
+                entry.text.clone()
+            };
+            Some(Message {
+                dt,
+                sender,
+                text,
+                line: index + 1,
+            })
+        })
+        .collect();
+    messages.sort_by_key(Message::order_key);
+    let mut messages = filter_system_messages(messages);
+    report.merged_senders = merge_sender_variants(&mut messages);
+    (messages, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::summarize;
+    use crate::summarize_messages;
+    use crate::AnalyzeOptions;
+    use chrono::Timelike;
+
+    fn entry(timestamp: InputTimestamp, sender: &str, text: &str) -> InputMessage {
+        InputMessage {
+            timestamp,
+            sender: sender.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn timestamps_accept_epoch_millis_and_iso() {
+        let millis = InputTimestamp::EpochMillis(1_704_103_200_000.0);
+        assert_eq!(
+            millis.to_datetime().unwrap().to_string(),
+            "2024-01-01 10:00:00"
+        );
+        let offset = InputTimestamp::Iso("2024-01-01T10:00:00+05:30".into());
+        assert_eq!(offset.to_datetime().unwrap().hour(), 10);
+        let naive = InputTimestamp::Iso("2024-01-01T10:00:00".into());
+        assert_eq!(naive.to_datetime(), millis.to_datetime());
+        assert!(InputTimestamp::Iso("yesterday".into())
+            .to_datetime()
+            .is_none());
+        assert!(InputTimestamp::EpochMillis(f64::NAN)
+            .to_datetime()
+            .is_none());
+    }
+
+    #[test]
+    fn far_future_timestamps_are_skipped_and_counted() {
+        let input = vec![
+            entry(
+                InputTimestamp::Iso("2024-01-01T10:00:00".into()),
+                "Alice",
+                "hi",
+            ),
+            entry(
+                InputTimestamp::Iso("2100-12-31T23:59:00".into()),
+                "Bob",
+                "ok",
+            ),
+            entry(
+                InputTimestamp::Iso("2101-01-01T00:00:00".into()),
+                "Bob",
+                "lost",
+            ),
+            entry(InputTimestamp::EpochMillis(1e15), "Bob", "also lost"),
+        ];
+        let (messages, report) = messages_from_input(&input, usize::MAX);
+        let texts: Vec<&str> = messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, vec!["hi", "ok"]);
+        assert_eq!(report.skipped_entries, 2);
+    }
+
+    #[test]
+    fn invalid_entries_are_skipped_and_counted() {
+        let input = vec![
+            entry(
+                InputTimestamp::Iso("2024-01-01T10:01:00".into()),
+                "Bob",
+                "second",
+            ),
+            entry(InputTimestamp::Iso("not a date".into()), "Bob", "lost"),
+            entry(
+                InputTimestamp::Iso("2024-01-01T10:00:00".into()),
+                "Alice",
+                "first",
+            ),
+            entry(InputTimestamp::EpochMillis(0.0), "  ", "no sender"),
+        ];
+        let (messages, report) = messages_from_input(&input, usize::MAX);
+        let texts: Vec<&str> = messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, vec!["first", "second"]);
+        assert_eq!((report.lines, report.skipped_entries), (4, 2));
+    }
+
+    #[test]
+    fn structured_input_matches_the_txt_path() {
+        let raw = "[1/1/24, 10:00:00 AM] Alice: hello there 😀\n[1/1/24, 10:01:00 AM] Bob: hi Alice\n[1/2/24, 9:00:00 PM] Alice: good night";
+        let input = vec![
+            entry(
+                InputTimestamp::Iso("2024-01-01T10:00:00".into()),
+                "Alice",
+                "hello there 😀",
+            ),
+            entry(
+                InputTimestamp::EpochMillis(1_704_103_260_000.0),
+                "Bob",
+                "hi Alice",
+            ),
+            entry(
+                InputTimestamp::Iso("2024-01-02 21:00:00".into()),
+                "Alice",
+                "good night",
+            ),
+        ];
+        let from_txt = summarize(raw, 10, 5).unwrap();
+        let from_input = summarize_messages(&input, 10, 5, &AnalyzeOptions::default()).unwrap();
+
+        assert_eq!(from_input.total_messages, from_txt.total_messages);
+        let json = |v: &[crate::Count]| serde_json::to_value(v).unwrap();
+        assert_eq!(json(&from_input.daily), json(&from_txt.daily));
+        assert_eq!(
+            serde_json::to_value(&from_input.hourly).unwrap(),
+            serde_json::to_value(&from_txt.hourly).unwrap()
+        );
+        assert_eq!(from_input.conversation_count, from_txt.conversation_count);
+        let totals = |s: &crate::Summary| {
+            let mut by: Vec<(String, u32)> = s
+                .by_sender
+                .iter()
+                .map(|c| (c.label.clone(), c.value))
+                .collect();
+            by.sort();
+            by
+        };
+        assert_eq!(totals(&from_input), totals(&from_txt));
+        assert!(from_input.warnings.is_empty());
+    }
+
+    #[test]
+    fn skipped_entries_surface_as_a_warning() {
+        let input = vec![
+            entry(
+                InputTimestamp::Iso("2024-01-01T10:00:00".into()),
+                "Alice",
+                "hi",
+            ),
+            entry(InputTimestamp::Iso("??".into()), "Bob", "lost"),
+        ];
+        let summary = summarize_messages(&input, 10, 5, &AnalyzeOptions::default()).unwrap();
+        assert_eq!(summary.total_messages, 1);
+        assert_eq!(
+            summary.warnings,
+            vec!["1 message was skipped for a missing timestamp or sender"]
+        );
+    }
+}
@@ -1,14 +1,18 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::parsing::Message;
-use crate::sentiment::sentiment_score;
-use crate::text::CONVERSATION_GAP_MINUTES;
-use crate::types::{Journey, JourneyMessage, JourneyMoment};
+use crate::options::SelfStrategy;
+use crate::parsing::{sorted_view, Message};
+use crate::sentiment::{sentiment_score, EmojiWeights};
+use crate::text::{truncate_chars, SNIPPET_MAX_CHARS};
+use crate::types::{Courtship, Journey, JourneyMessage, JourneyMoment};
+
+/// Default ceiling on the length bonus in moment scoring.
+pub(crate) const JOURNEY_LENGTH_BONUS_CAP: f32 = 3.5;
 
 fn to_journey_message(msg: &Message, likely_you: &str) -> JourneyMessage {
     JourneyMessage {
         sender: msg.sender.clone(),
-        text: msg.text.clone(),
+        text: truncate_chars(&msg.text, SNIPPET_MAX_CHARS),
         timestamp: msg.dt.format("%Y-%m-%dT%H:%M:%S").to_string(),
         is_you: msg.sender == likely_you,
     }
@@ -18,6 +22,9 @@ fn find_interesting_moments(
     messages: &[Message],
     likely_you: &str,
     max_moments: usize,
+    long_message_tokens: usize,
+    length_bonus_cap: f32,
+    emoji_weights: &EmojiWeights,
 ) -> Vec<JourneyMoment> {
     if messages.len() < 10 {
         return Vec::new();
@@ -30,9 +37,13 @@ fn find_interesting_moments(
         if text.len() < 6 || text.contains("omitted") || text.contains("deleted") {
             continue;
         }
+        // Moments need six words. Most chat messages are shorter, so reject them before
+        // the feature pass and, above all, `sentiment_score`, which dominates the cost.
+        if text.split_whitespace().nth(5).is_none() {
+            continue;
+        }
 
         let features = text_features(text);
-        let (sentiment, _) = sentiment_score(text);
 
         // Skip clearly spammy/technical drops.
         if features.url_count > 2 {
@@ -42,8 +53,13 @@ fn find_interesting_moments(
             continue;
         }
 
-        let length_score = if features.word_count > 8 {
-            (features.word_count as f32).ln().min(3.5)
+        let (sentiment, _) = sentiment_score(text, emoji_weights);
+
+        // Pasted walls of text would max out the bonus every time; they get none.
+        let length_score = if features.word_count > long_message_tokens {
+            0.0
+        } else if features.word_count > 8 {
+            (features.word_count as f32).ln().min(length_bonus_cap)
         } else {
             0.0
         };
@@ -71,8 +87,8 @@ fn find_interesting_moments(
         let interest =
             sentiment_score_abs + length_score + diversity_score + expression_score - penalty;
 
-        // Require a minimum meaningful threshold and some words.
-        if features.word_count < 6 || interest < 1.0 {
+        // Require a minimum meaningful threshold.
+        if interest < 1.0 {
             continue;
         }
 
@@ -305,13 +321,49 @@ fn text_features(text: &str) -> TextFeatures {
     }
 }
 
-pub(crate) fn build_journey(messages: &[Message]) -> Option<Journey> {
+/// The sender `strategy` treats as "you"; ties go to the alphabetically first name.
+fn pick_self<'a>(messages: &'a [Message], strategy: &'a SelfStrategy) -> &'a str {
+    let mut sender_counts: HashMap<&str, usize> = HashMap::new();
+    for msg in messages {
+        *sender_counts.entry(&msg.sender).or_insert(0) += 1;
+    }
+    let least_active = || {
+        sender_counts
+            .iter()
+            .min_by_key(|(sender, count)| (**count, **sender))
+            .map_or("", |(sender, _)| *sender)
+    };
+
+    match strategy {
+        SelfStrategy::DeletedHeuristic => messages
+            .iter()
+            .find(|m| m.text.contains("You deleted this message"))
+            .map_or_else(least_active, |m| m.sender.as_str()),
+        SelfStrategy::LeastActive => least_active(),
+        SelfStrategy::MostActive => sender_counts
+            .iter()
+            .min_by_key(|(sender, count)| (std::cmp::Reverse(**count), **sender))
+            .map_or("", |(sender, _)| *sender),
+        SelfStrategy::Explicit { name } => name,
+    }
+}
+
+/// The chat's journey. `gap_minutes` is the silence that ends a conversation, which
+/// bounds the first and last exchanges.
+pub(crate) fn build_journey(
+    messages: &[Message],
+    gap_minutes: i64,
+    long_message_tokens: usize,
+    length_bonus_cap: f32,
+    self_strategy: &SelfStrategy,
+    emoji_weights: &EmojiWeights,
+    courtship: Option<Courtship>,
+) -> Option<Journey> {
     if messages.is_empty() {
         return None;
     }
 
-    let mut sorted_messages = messages.to_vec();
-    sorted_messages.sort_by_key(|m| m.dt);
+    let sorted_messages = sorted_view(messages);
 
     let first_msg = sorted_messages.first()?;
     let last_msg = sorted_messages.last()?;
@@ -320,23 +372,7 @@ pub(crate) fn build_journey(messages: &[Message]) -> Option<Journey> {
     let last_day = last_msg.dt.date();
     let total_days = (last_day - first_day).num_days().max(1) as u32;
 
-    let mut sender_counts: HashMap<&str, usize> = HashMap::new();
-    let mut deleted_you_sender: Option<&str> = None;
-
-    for msg in &sorted_messages {
-        *sender_counts.entry(&msg.sender).or_insert(0) += 1;
-        if msg.text.contains("You deleted this message") && deleted_you_sender.is_none() {
-            deleted_you_sender = Some(&msg.sender);
-        }
-    }
-
-    let likely_you = deleted_you_sender.unwrap_or_else(|| {
-        sender_counts
-            .iter()
-            .min_by_key(|(_, count)| *count)
-            .map(|(sender, _)| *sender)
-            .unwrap_or("")
-    });
+    let likely_you = pick_self(&sorted_messages, self_strategy);
 
     let mut first_messages: Vec<JourneyMessage> = Vec::new();
     for (i, msg) in sorted_messages.iter().enumerate() {
@@ -346,7 +382,7 @@ pub(crate) fn build_journey(messages: &[Message]) -> Option<Journey> {
         }
         if let Some(next_msg) = sorted_messages.get(i + 1) {
             let gap = next_msg.dt.signed_duration_since(msg.dt).num_minutes();
-            if gap > CONVERSATION_GAP_MINUTES {
+            if gap > gap_minutes {
                 break;
             }
         }
@@ -362,14 +398,21 @@ pub(crate) fn build_journey(messages: &[Message]) -> Option<Journey> {
         if i > 0 {
             let prev_msg = &sorted_messages[i - 1];
             let gap = msg.dt.signed_duration_since(prev_msg.dt).num_minutes();
-            if gap > CONVERSATION_GAP_MINUTES {
+            if gap > gap_minutes {
                 break;
             }
         }
     }
     last_messages.reverse();
 
-    let interesting_moments = find_interesting_moments(&sorted_messages, likely_you, 4);
+    let interesting_moments = find_interesting_moments(
+        &sorted_messages,
+        likely_you,
+        4,
+        long_message_tokens,
+        length_bonus_cap,
+        emoji_weights,
+    );
 
     Some(Journey {
         first_day: first_day.format("%B %d, %Y").to_string(),
@@ -379,26 +422,158 @@ pub(crate) fn build_journey(messages: &[Message]) -> Option<Journey> {
         first_messages,
         last_messages,
         interesting_moments,
+        courtship,
+    })
+}
+
+/// The opening monologue of a two-person chat: how many messages the first sender wrote,
+/// and over how long, before the other person first answered. `None` for group chats,
+/// chats nobody answered, and chats where the reply came straight away. Takes every
+/// message rather than the journey's spam-collapsed input, since a run of unanswered
+/// openers is exactly what it measures.
+pub(crate) fn courtship(messages: &[Message]) -> Option<Courtship> {
+    let senders = messages
+        .iter()
+        .map(|m| m.sender.as_str())
+        .collect::<HashSet<_>>();
+    if senders.len() != 2 {
+        return None;
+    }
+    let sorted = sorted_view(messages);
+    let first = sorted.first()?;
+    let reply_at = sorted.iter().position(|m| m.sender != first.sender)?;
+    if reply_at < 2 {
+        return None;
+    }
+    let waited = sorted[reply_at].dt - first.dt;
+    Some(Courtship {
+        initiator: first.sender.clone(),
+        messages_before_reply: reply_at as u32,
+        hours_before_reply: waited.num_minutes() as f32 / 60.0,
     })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::text::{CONVERSATION_GAP_MINUTES, LONG_MESSAGE_TOKENS};
     use chrono::NaiveDateTime;
 
+    fn journey_with_defaults(messages: &[Message]) -> Option<Journey> {
+        build_journey(
+            messages,
+            CONVERSATION_GAP_MINUTES,
+            LONG_MESSAGE_TOKENS,
+            JOURNEY_LENGTH_BONUS_CAP,
+            &SelfStrategy::default(),
+            &EmojiWeights::new(),
+            courtship(messages),
+        )
+    }
+
+    fn moments_with_defaults(messages: &[Message], max_moments: usize) -> Vec<JourneyMoment> {
+        find_interesting_moments(
+            messages,
+            "Alice",
+            max_moments,
+            LONG_MESSAGE_TOKENS,
+            JOURNEY_LENGTH_BONUS_CAP,
+            &EmojiWeights::new(),
+        )
+    }
+
     fn msg(sender: &str, text: &str, dt_str: &str) -> Message {
         let dt = NaiveDateTime::parse_from_str(dt_str, "%Y-%m-%d %H:%M:%S").unwrap();
         Message {
             sender: sender.to_string(),
             text: text.to_string(),
             dt,
+            line: 0,
         }
     }
 
+    #[test]
+    fn test_moments_on_a_fixed_chat_are_stable() {
+        const LINES: [&str; 12] = [
+            "ok",
+            "lol",
+            "see you at 7",
+            "I love this so much, best day ever with you all! 😍",
+            "This is terrible, I hate waiting and the train is late again 😡",
+            "check https://example.com and https://example.org and www.example.net now",
+            "we should plan the trip for next summer maybe in the mountains",
+            "haha yes",
+            "What a wonderful surprise, thank you so much for the amazing gift!!",
+            "I feel awful and sad today, everything went wrong at work",
+            "#### $$$ %%% &&& *** @@@ !!! ??? ~~~ ^^^",
+            "sounds good, let me know when you leave home tonight",
+        ];
+        let messages: Vec<Message> = (0..600)
+            .map(|i| Message {
+                line: i,
+                ..msg(
+                    if i % 3 == 0 { "Bob" } else { "Alice" },
+                    LINES[(i * 7 + i / 12) % LINES.len()],
+                    &format!("2024-01-{:02} {:02}:{:02}:00", 1 + i / 24, i % 24, i % 60),
+                )
+            })
+            .collect();
+
+        let moments: Vec<(String, String, String)> = moments_with_defaults(&messages, 4)
+            .into_iter()
+            .map(|m| {
+                (
+                    m.messages[2].timestamp.clone(),
+                    m.title,
+                    m.messages[2].text.clone(),
+                )
+            })
+            .collect();
+        let joyful = "What a wonderful surprise, thank you so much for the amazing gift!!";
+        let late = "This is terrible, I hate waiting and the train is late again 😡";
+        let expected = [
+            ("2024-01-06T15:15:00", "A joyful moment", joyful),
+            ("2024-01-13T04:52:00", "A heartfelt exchange", late),
+            ("2024-01-19T13:25:00", "A joyful moment", joyful),
+            ("2024-01-25T21:57:00", "A heartfelt exchange", late),
+        ];
+        let found: Vec<(&str, &str, &str)> = moments
+            .iter()
+            .map(|(at, title, text)| (at.as_str(), title.as_str(), text.as_str()))
+            .collect();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_courtship_counts_the_opening_monologue() {
+        let messages = vec![
+            msg("Bob", "hey", "2024-01-01 20:00:00"),
+            msg("Bob", "saw you at the concert", "2024-01-01 20:05:00"),
+            msg("Bob", "anyway", "2024-01-02 09:00:00"),
+            msg("Bob", "hello?", "2024-01-03 08:00:00"),
+            msg("Alice", "haha hi Bob", "2024-01-04 02:00:00"),
+            msg("Bob", "finally", "2024-01-04 02:01:00"),
+        ];
+        let courtship = journey_with_defaults(&messages)
+            .unwrap()
+            .courtship
+            .expect("four unanswered messages");
+        assert_eq!(courtship.initiator, "Bob");
+        assert_eq!(courtship.messages_before_reply, 4);
+        assert_eq!(courtship.hours_before_reply, 54.0);
+
+        // Answered straight away: nothing to tell.
+        let quick = &messages[3..];
+        assert!(journey_with_defaults(quick).unwrap().courtship.is_none());
+
+        let mut group = messages.clone();
+        group.push(msg("Carol", "hi all", "2024-01-05 10:00:00"));
+        assert!(journey_with_defaults(&group).unwrap().courtship.is_none());
+    }
+
     #[test]
     fn test_build_journey_empty() {
-        let journey = build_journey(&[]);
+        let journey = journey_with_defaults(&[]);
         assert!(journey.is_none());
     }
 
@@ -410,7 +585,7 @@ mod tests {
             msg("Alice", "How are you?", "2023-01-01 10:02:00"),
         ];
 
-        let journey = build_journey(&messages).unwrap();
+        let journey = journey_with_defaults(&messages).unwrap();
 
         assert_eq!(journey.total_messages, 3);
         assert_eq!(journey.first_messages.len(), 3);
@@ -428,7 +603,7 @@ mod tests {
             msg("Alice", "Another one", "2023-01-01 10:02:00"),
         ];
 
-        let journey = build_journey(&messages).unwrap();
+        let journey = journey_with_defaults(&messages).unwrap();
 
         // Bob should be identified as "you" because of the deleted message
         assert!(journey
@@ -450,7 +625,7 @@ mod tests {
             msg("Bob", "Hi", "2023-01-01 10:03:00"),
         ];
 
-        let journey = build_journey(&messages).unwrap();
+        let journey = journey_with_defaults(&messages).unwrap();
 
         // Bob has fewer messages, so should be identified as "you"
         assert!(journey
@@ -459,6 +634,46 @@ mod tests {
             .any(|m| m.sender == "Bob" && m.is_you));
     }
 
+    #[test]
+    fn test_self_strategies_pick_expected_sender() {
+        let messages = vec![
+            msg("Alice", "one", "2023-01-01 10:00:00"),
+            msg("Alice", "two", "2023-01-01 10:01:00"),
+            msg("Carol", "You deleted this message", "2023-01-01 10:02:00"),
+            msg("Alice", "three", "2023-01-01 10:03:00"),
+            msg("Bob", "hi", "2023-01-01 10:04:00"),
+            msg("Carol", "sorry", "2023-01-01 10:05:00"),
+            msg("Dave", "hey", "2023-01-01 10:06:00"),
+            msg("Dave", "all", "2023-01-01 10:07:00"),
+        ];
+        let dave = SelfStrategy::Explicit {
+            name: "Dave".into(),
+        };
+
+        assert_eq!(
+            pick_self(&messages, &SelfStrategy::DeletedHeuristic),
+            "Carol"
+        );
+        assert_eq!(pick_self(&messages, &SelfStrategy::LeastActive), "Bob");
+        assert_eq!(pick_self(&messages, &SelfStrategy::MostActive), "Alice");
+        assert_eq!(pick_self(&messages, &dave), "Dave");
+
+        let journey = build_journey(
+            &messages,
+            CONVERSATION_GAP_MINUTES,
+            LONG_MESSAGE_TOKENS,
+            JOURNEY_LENGTH_BONUS_CAP,
+            &dave,
+            &EmojiWeights::new(),
+            None,
+        )
+        .unwrap();
+        assert!(journey
+            .last_messages
+            .iter()
+            .all(|m| m.is_you == (m.sender == "Dave")));
+    }
+
     #[test]
     fn test_build_journey_respects_conversation_gap() {
         let messages = vec![
@@ -469,7 +684,7 @@ mod tests {
             msg("Bob", "Evening reply", "2023-01-01 20:01:00"),
         ];
 
-        let journey = build_journey(&messages).unwrap();
+        let journey = journey_with_defaults(&messages).unwrap();
 
         // First messages should stop at the gap
         assert!(journey.first_messages.len() < 4);
@@ -479,6 +694,28 @@ mod tests {
         }));
     }
 
+    #[test]
+    fn test_build_journey_uses_the_given_gap() {
+        let messages = vec![
+            msg("Alice", "Morning message", "2023-01-01 08:00:00"),
+            msg("Bob", "Morning reply", "2023-01-01 08:01:00"),
+            msg("Alice", "Evening message", "2023-01-01 20:00:00"),
+            msg("Bob", "Evening reply", "2023-01-01 20:01:00"),
+        ];
+        let journey = build_journey(
+            &messages,
+            24 * 60,
+            LONG_MESSAGE_TOKENS,
+            JOURNEY_LENGTH_BONUS_CAP,
+            &SelfStrategy::default(),
+            &EmojiWeights::new(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(journey.first_messages.len(), 4);
+        assert_eq!(journey.last_messages.len(), 4);
+    }
+
     #[test]
     fn test_build_journey_last_messages_respects_gap() {
         let messages = vec![
@@ -490,7 +727,7 @@ mod tests {
             msg("Alice", "Evening follow-up", "2023-01-01 20:02:00"),
         ];
 
-        let journey = build_journey(&messages).unwrap();
+        let journey = journey_with_defaults(&messages).unwrap();
 
         // Last messages should only include evening messages
         assert!(journey.last_messages.iter().all(|m| {
@@ -510,7 +747,7 @@ mod tests {
             ));
         }
 
-        let journey = build_journey(&messages).unwrap();
+        let journey = journey_with_defaults(&messages).unwrap();
 
         assert!(journey.first_messages.len() <= 5);
     }
@@ -526,14 +763,14 @@ mod tests {
             ));
         }
 
-        let journey = build_journey(&messages).unwrap();
+        let journey = journey_with_defaults(&messages).unwrap();
 
         assert!(journey.last_messages.len() <= 5);
     }
 
     #[test]
     fn test_find_interesting_moments_empty() {
-        let moments = find_interesting_moments(&[], "Alice", 4);
+        let moments = moments_with_defaults(&[], 4);
         assert!(moments.is_empty());
     }
 
@@ -544,7 +781,7 @@ mod tests {
             msg("Bob", "Hello", "2023-01-01 10:01:00"),
         ];
 
-        let moments = find_interesting_moments(&messages, "Alice", 4);
+        let moments = moments_with_defaults(&messages, 4);
         assert!(moments.is_empty());
     }
 
@@ -560,13 +797,49 @@ mod tests {
             messages.push(msg("Alice", text, &format!("2023-01-01 10:{:02}:00", i)));
         }
 
-        let moments = find_interesting_moments(&messages, "Alice", 4);
+        let moments = moments_with_defaults(&messages, 4);
 
         assert!(!moments.is_empty());
         let has_positive = moments.iter().any(|m| m.sentiment_score > 0.0);
         assert!(has_positive);
     }
 
+    #[test]
+    fn test_find_interesting_moments_long_paste_loses_length_bonus() {
+        let paste = "What a wonderful happy day we had at the beach with friends, \
+            laughing about old stories, sharing snacks, watching sunset colours \
+            across calm water before driving home singing loudly together";
+        let short = "So happy you came today, lovely surprise!";
+        let mut messages = vec![];
+        for i in 0..50 {
+            let text = match i {
+                24 => paste,
+                28 => short,
+                _ => "Regular message",
+            };
+            messages.push(msg("Alice", text, &format!("2023-01-01 10:{:02}:00", i)));
+        }
+        let picked = |long_message_tokens| {
+            find_interesting_moments(
+                &messages,
+                "Alice",
+                1,
+                long_message_tokens,
+                JOURNEY_LENGTH_BONUS_CAP,
+                &EmojiWeights::new(),
+            )
+            .iter()
+            // The picked message sits after two lines of context.
+            .flat_map(|m| m.messages.get(2))
+            .map(|m| m.text.clone())
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(picked(LONG_MESSAGE_TOKENS), vec![paste]);
+        // Once the paste counts as long it loses its length bonus to the short message.
+        assert_eq!(picked(20), vec![short]);
+    }
+
     #[test]
     fn test_find_interesting_moments_negative_sentiment() {
         let mut messages = vec![];
@@ -579,7 +852,7 @@ mod tests {
             messages.push(msg("Alice", text, &format!("2023-01-01 10:{:02}:00", i)));
         }
 
-        let moments = find_interesting_moments(&messages, "Alice", 4);
+        let moments = moments_with_defaults(&messages, 4);
 
         assert!(!moments.is_empty());
         let has_negative = moments.iter().any(|m| m.sentiment_score < 0.0);
@@ -598,7 +871,7 @@ mod tests {
             messages.push(msg("Alice", text, &format!("2023-01-01 10:{:02}:00", i)));
         }
 
-        let moments = find_interesting_moments(&messages, "Alice", 4);
+        let moments = moments_with_defaults(&messages, 4);
 
         // Spammy message should be filtered out
         if !moments.is_empty() {
@@ -621,7 +894,7 @@ mod tests {
             messages.push(msg("Alice", text, &format!("2023-01-01 10:{:02}:00", i)));
         }
 
-        let moments = find_interesting_moments(&messages, "Alice", 4);
+        let moments = moments_with_defaults(&messages, 4);
 
         // Deleted message should be filtered
         let has_deleted = moments
@@ -647,7 +920,7 @@ mod tests {
             "2023-01-01 10:25:00",
         );
 
-        let moments = find_interesting_moments(&messages, "Alice", 4);
+        let moments = moments_with_defaults(&messages, 4);
 
         if !moments.is_empty() {
             // Should include context messages around the interesting one
@@ -668,7 +941,7 @@ mod tests {
             ));
         }
 
-        let moments = find_interesting_moments(&messages, "Alice", 3);
+        let moments = moments_with_defaults(&messages, 3);
 
         assert!(moments.len() <= 3);
     }
@@ -779,7 +1052,7 @@ mod tests {
             "2023-01-01 12:25:00",
         ));
 
-        let moments = find_interesting_moments(&messages, "Alice", 10);
+        let moments = moments_with_defaults(&messages, 10);
 
         // Check that different types of moments get different titles
         let titles: Vec<&str> = moments.iter().map(|m| m.title.as_str()).collect();
@@ -799,7 +1072,7 @@ mod tests {
             msg("Alice", "Last day", "2023-01-31 10:00:00"),
         ];
 
-        let journey = build_journey(&messages).unwrap();
+        let journey = journey_with_defaults(&messages).unwrap();
 
         assert_eq!(journey.first_day, "January 01, 2023");
         assert_eq!(journey.last_day, "January 31, 2023");
@@ -814,7 +1087,7 @@ mod tests {
             msg("Alice", "Second", "2023-01-01 11:00:00"),
         ];
 
-        let journey = build_journey(&messages).unwrap();
+        let journey = journey_with_defaults(&messages).unwrap();
 
         // First message should be the earliest one
         assert_eq!(journey.first_messages[0].text, "First");
@@ -833,7 +1106,7 @@ mod tests {
             messages.push(msg("Alice", text, &format!("2023-01-01 10:{:02}:00", i)));
         }
 
-        let moments = find_interesting_moments(&messages, "Alice", 10);
+        let moments = moments_with_defaults(&messages, 10);
 
         // Moments should be temporally spaced apart
         assert!(
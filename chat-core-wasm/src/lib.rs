@@ -1,19 +1,43 @@
 use wasm_bindgen::prelude::*;
 
 mod analysis;
+mod chat;
+mod diff;
+mod error;
+mod filters;
+mod fingerprint;
+mod head_to_head;
+mod highlights;
+mod input;
 mod journey;
+mod merge;
 mod metrics;
+mod options;
 mod parsing;
 mod phrases;
+mod quantiles;
+mod sampling;
 mod sentiment;
 mod text;
 mod types;
+mod warnings;
 
 #[cfg(not(target_arch = "wasm32"))]
-pub use analysis::analyze_chat_native;
-pub use analysis::summarize;
+pub use analysis::{analyze_chat_native, summarize_to_writer, summarize_to_writer_pretty};
+pub use analysis::{summarize, summarize_messages, summarize_with_options};
+pub use chat::ParsedChat;
+pub use diff::diff;
+pub use error::AnalyzeError;
+pub use fingerprint::content_fingerprint;
+pub use input::{InputMessage, InputTimestamp};
 pub use metrics::{longest_streak, longest_streak_from_raw};
-pub use types::{Count, Summary};
+pub use options::{
+    AnalyzeOptions, AnalyzeOptionsBuilder, Hemisphere, Limits, SelfStrategy, SpecialDate,
+    TextFilter, TimelineGranularity,
+};
+pub use parsing::parse_only;
+pub use text::effective_stopwords;
+pub use types::{AnalysisMeta, Count, CountDelta, FloatDelta, ParsedMessage, Summary, SummaryDiff};
 
 #[wasm_bindgen]
 pub fn init_panic_hook() {
@@ -23,8 +47,71 @@ pub fn init_panic_hook() {
 
 #[wasm_bindgen]
 pub fn analyze_chat(raw: &str, top_words_n: u32, top_emojis_n: u32) -> Result<JsValue, JsValue> {
-    let summary = summarize(raw, top_words_n as usize, top_emojis_n as usize)
-        .map_err(|e| JsValue::from_str(&e))?;
+    let summary = summarize(raw, top_words_n as usize, top_emojis_n as usize)?;
 
     serde_wasm_bindgen::to_value(&summary).map_err(|e| JsValue::from_str(&e.to_string()))
 }
+
+#[wasm_bindgen]
+pub fn analyze_chat_with_options(
+    raw: &str,
+    top_words_n: u32,
+    top_emojis_n: u32,
+    options: AnalyzeOptions,
+) -> Result<JsValue, JsValue> {
+    let summary =
+        summarize_with_options(raw, top_words_n as usize, top_emojis_n as usize, &options)?;
+
+    serde_wasm_bindgen::to_value(&summary).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Runs the full analysis on messages that are already structured: an array of
+/// `{ timestamp, sender, text }` where `timestamp` is epoch milliseconds or ISO-8601.
+#[wasm_bindgen]
+pub fn analyze_messages(
+    messages: JsValue,
+    top_words_n: u32,
+    top_emojis_n: u32,
+    options: AnalyzeOptions,
+) -> Result<JsValue, JsValue> {
+    let messages: Vec<InputMessage> =
+        serde_wasm_bindgen::from_value(messages).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let summary = summarize_messages(
+        &messages,
+        top_words_n as usize,
+        top_emojis_n as usize,
+        &options,
+    )?;
+
+    serde_wasm_bindgen::to_value(&summary).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Cache key for the analysis of `raw` with these limits and `options`; matches the
+/// summary's `analysis_meta.fingerprint`.
+#[wasm_bindgen(js_name = content_fingerprint)]
+pub fn content_fingerprint_js(
+    raw: &str,
+    top_words_n: u32,
+    top_emojis_n: u32,
+    options: AnalyzeOptions,
+) -> String {
+    content_fingerprint(raw, top_words_n as usize, top_emojis_n as usize, &options)
+}
+
+#[wasm_bindgen(js_name = parse_only)]
+pub fn parse_only_js(raw: &str) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(&parse_only(raw)).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen(js_name = stopwords)]
+pub fn stopwords_js(language: &str) -> Result<Vec<String>, JsValue> {
+    effective_stopwords(language).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Checks an options object without running an analysis, e.g. to validate a settings
+/// form before the user uploads a chat.
+#[wasm_bindgen(js_name = validate_options)]
+pub fn validate_options_js(options: JsValue) -> Result<(), JsValue> {
+    AnalyzeOptions::from_js_value(options)?;
+    Ok(())
+}
@@ -0,0 +1,1120 @@
+use chrono::NaiveDate;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::fingerprint;
+use crate::head_to_head;
+use crate::highlights;
+use crate::metrics;
+use crate::options::AnalyzeOptions;
+use crate::phrases;
+use crate::sentiment;
+use crate::types::{
+    Count, DailyStreak, FloatCount, FunFact, HourCount, LanguageMonth, MonthHour, PersonBuckets,
+    PersonDaily, PersonEmojiMonthly, PersonEmojiTrend, PersonPhrases, PersonPunctuation,
+    PersonReplyHist, PersonStat, PersonVoiceNotes, PhraseMinCount, PhraseWithExample, PollStats,
+    PunctuationMonth, RelationshipScore, ResponseLatency, Revival, Routine, SampleInfo, SpecialDay,
+    Summary, TimelineAnnotation, VoiceNotes, WordOrigin,
+};
+
+impl Summary {
+    /// Combines summaries of two parts of the same chat (e.g. per-year exports or worker
+    /// chunks). Senders are matched by exact name.
+    ///
+    /// Message, day, hour, month, bucket, deletion, voice-note and sentiment counts
+    /// combine exactly, as do the derived timeline, quarters, seasons, streaks and
+    /// sentiment means. Ranked lists that were truncated (words, emojis, phrases), reply
+    /// medians, routines, first-message hours, vocabulary sizes and drift, emoji trends,
+    /// word firsts and the language of a month split between the parts are best-effort
+    /// approximations, and replies or conversations spanning the seam between the parts
+    /// are missed. The journey is dropped. The timeline is bucketed at the coarser of the
+    /// two parts' units. The result has `merged` set.
+    ///
+    /// ```
+    /// use chat_core_wasm::{summarize, Summary};
+    ///
+    /// let jan = summarize("[1/1/24, 1:00:00 PM] A: hi\n[1/1/24, 1:01:00 PM] B: hey", 10, 5).unwrap();
+    /// let feb = summarize("[2/1/24, 1:00:00 PM] A: again", 10, 5).unwrap();
+    /// let both = Summary::merge(jan, feb);
+    /// assert!(both.merged);
+    /// assert_eq!(both.total_messages, 3);
+    /// assert_eq!(both.total_for_sender("A"), 2);
+    /// ```
+    pub fn merge(a: Summary, b: Summary) -> Summary {
+        let recent_emojis = merge_recent_emojis([
+            (&a.recent_emojis, a.daily.last()),
+            (&b.recent_emojis, b.daily.last()),
+        ]);
+        let by_sender = sorted_desc(sum_counts(a.by_sender, b.by_sender));
+        let top_phrases = truncated_sum(a.top_phrases, b.top_phrases);
+        let top_words = truncated_sum(a.top_words, b.top_words);
+        let top_word = top_words.first().map(|c| c.label.clone());
+        let top_phrase_examples =
+            merge_phrase_examples(&top_phrases, a.top_phrase_examples, b.top_phrase_examples);
+        let participants = metrics::participants(&by_sender);
+        let share_of_speech = metrics::share_of_speech(&by_sender);
+        let imbalance = metrics::imbalance(&share_of_speech);
+        let a_first = a.daily.first().map(|c| c.label.as_str());
+        let emoji_trend = if a_first <= b.daily.first().map(|c| c.label.as_str()) {
+            merge_emoji_trend(a.emoji_trend, b.emoji_trend)
+        } else {
+            merge_emoji_trend(b.emoji_trend, a.emoji_trend)
+        };
+        let daily = sorted_by_label(sum_counts(a.daily, b.daily));
+        let longest_streak = merge_longest_streak(&daily, a.longest_streak, b.longest_streak);
+        let iso_weekly = metrics::iso_weekly_from_daily(&daily);
+        let daily_timeline = metrics::timeline_from_daily(&daily);
+        let volume_trend = metrics::volume_trend_from_timeline(&daily_timeline);
+        let timeline_unit = a.timeline_unit.max(b.timeline_unit);
+        let timeline = metrics::timeline_at(daily_timeline, timeline_unit);
+        let monthly = sorted_by_label(sum_counts(a.monthly, b.monthly));
+        let (busiest_month, quietest_month) = metrics::busiest_and_quietest_month(&monthly);
+        let sentiment_by_day =
+            sentiment::merge_sentiment_by_day(a.sentiment_by_day, b.sentiment_by_day);
+        let swings = metrics::month_swings(&monthly, &sentiment_by_day);
+        let per_person_daily = merge_per_person_daily(a.per_person_daily, b.per_person_daily);
+        let buckets_by_person = merge_buckets(a.buckets_by_person, b.buckets_by_person);
+        let (weekday_leader, weekday_share) = metrics::weekday_leaders(&buckets_by_person);
+        let presence_calendar = metrics::presence_calendar(&per_person_daily, &by_sender);
+        let threshold = a
+            .weekly_streak
+            .as_ref()
+            .or(b.weekly_streak.as_ref())
+            .map_or(AnalyzeOptions::default().weekly_streak_threshold, |s| {
+                s.threshold
+            });
+
+        let total_replies = |r: &[ResponseLatency]| r.iter().map(|r| r.replies).sum::<u32>();
+        let avg_response_minutes = weighted_mean(
+            a.avg_response_minutes,
+            total_replies(&a.response_latency),
+            b.avg_response_minutes,
+            total_replies(&b.response_latency),
+        );
+        let total_messages = a.total_messages + b.total_messages;
+        let total_conversation_minutes =
+            a.total_conversation_minutes + b.total_conversation_minutes;
+        let starter_success = merge_starter_success([
+            (&a.starter_success, &a.conversation_starters),
+            (&b.starter_success, &b.conversation_starters),
+        ]);
+        // Carry each part's emoji polarity over, so custom emoji weights survive the merge.
+        let emoji_polarities: sentiment::EmojiWeights = a
+            .emoji_cloud_sentiment
+            .iter()
+            .chain(&b.emoji_cloud_sentiment)
+            .map(|item| (item.label.clone(), item.sentiment as f32))
+            .collect();
+        let emoji_cloud = truncated_sum(a.emoji_cloud, b.emoji_cloud);
+        let (sentiment_overall, sentiment_exchange) =
+            sentiment::merge_sentiment_overall(a.sentiment_overall, b.sentiment_overall);
+
+        let mut merged = Summary {
+            total_messages,
+            by_sender,
+            participants,
+            hourly: merge_hourly(a.hourly, b.hourly),
+            routines: merge_routines(a.routines, b.routines, daily.len() as u32),
+            first_message_hour_by_month: merge_month_hours(
+                a.first_message_hour_by_month,
+                b.first_message_hour_by_month,
+            ),
+            first_message_hour_by_person: merge_by_name(
+                a.first_message_hour_by_person,
+                b.first_message_hour_by_person,
+                |p| p.name.as_str(),
+                |x, y| x.monthly = merge_month_hours(std::mem::take(&mut x.monthly), y.monthly),
+            ),
+            top_emojis: truncated_sum(a.top_emojis, b.top_emojis),
+            recent_emojis,
+            top_words,
+            top_words_no_stop: truncated_sum(a.top_words_no_stop, b.top_words_no_stop),
+            top_word,
+            stopword_config: a.stopword_config,
+            language_timeline: merge_language_timeline(a.language_timeline, b.language_timeline),
+            deleted_you: a.deleted_you + b.deleted_you,
+            deleted_others: a.deleted_others + b.deleted_others,
+            volume_trend,
+            weekly_streak: metrics::weekly_streak_over(&iso_weekly, threshold),
+            daily,
+            longest_streak,
+            timeline,
+            timeline_unit,
+            annotations: merge_annotations(a.annotations, b.annotations),
+            special_days: merge_special_days(a.special_days, b.special_days),
+            weekly: sum_counts(a.weekly, b.weekly),
+            iso_weekly,
+            quarterly: metrics::quarterly_counts(&monthly),
+            vocab_drift: {
+                // A quarter split across the seam keeps the earlier part's lists.
+                let mut drift =
+                    merge_by_name(a.vocab_drift, b.vocab_drift, |d| &d.period, |_, _| {});
+                drift.sort_by(|x, y| x.period.cmp(&y.period));
+                drift
+            },
+            word_origins: merge_word_origins(a.word_origins, b.word_origins),
+            seasonal: std::array::from_fn(|i| a.seasonal[i] + b.seasonal[i]),
+            monthly,
+            busiest_month,
+            quietest_month,
+            swings,
+            share_of_speech,
+            imbalance,
+            buckets_by_person,
+            weekday_leader,
+            weekday_share,
+            word_cloud: truncated_sum(a.word_cloud, b.word_cloud),
+            keyword_counts: sum_counts(a.keyword_counts, b.keyword_counts),
+            theme_counts: sum_counts(a.theme_counts, b.theme_counts),
+            word_cloud_no_stop: truncated_sum(a.word_cloud_no_stop, b.word_cloud_no_stop),
+            emoji_cloud_sentiment: sentiment::emoji_cloud_sentiment(
+                &emoji_cloud,
+                &emoji_polarities,
+            ),
+            emoji_cloud,
+            emoji_spam_by_sender: sorted_desc(sum_counts(
+                a.emoji_spam_by_sender,
+                b.emoji_spam_by_sender,
+            )),
+            per_person_emoji_monthly: merge_emoji_monthly(
+                a.per_person_emoji_monthly,
+                b.per_person_emoji_monthly,
+            ),
+            emoji_categories: sorted_desc(sum_counts(a.emoji_categories, b.emoji_categories)),
+            emoji_pairs: truncated_sum(a.emoji_pairs, b.emoji_pairs),
+            emoji_trend,
+            punctuation_energy: merge_punctuation_months(
+                a.punctuation_energy,
+                b.punctuation_energy,
+            ),
+            punctuation_energy_by_person: merge_punctuation_by_person(
+                a.punctuation_energy_by_person,
+                b.punctuation_energy_by_person,
+            ),
+            salient_phrases: truncated_sum(a.salient_phrases, b.salient_phrases),
+            top_phrases,
+            top_phrases_no_stop: truncated_sum(a.top_phrases_no_stop, b.top_phrases_no_stop),
+            top_phrase_examples,
+            top_question_phrases: truncated_sum(a.top_question_phrases, b.top_question_phrases),
+            phrase_min_count: PhraseMinCount {
+                top_phrases: a
+                    .phrase_min_count
+                    .top_phrases
+                    .max(b.phrase_min_count.top_phrases),
+                salient_phrases: a
+                    .phrase_min_count
+                    .salient_phrases
+                    .max(b.phrase_min_count.salient_phrases),
+            },
+            per_person_phrases: merge_person_phrases(a.per_person_phrases, b.per_person_phrases),
+            per_person_phrases_no_stop: merge_person_phrases(
+                a.per_person_phrases_no_stop,
+                b.per_person_phrases_no_stop,
+            ),
+            fun_facts: merge_fun_facts(a.fun_facts, b.fun_facts),
+            highlights: Vec::new(),
+            spam_runs: {
+                let mut runs = a.spam_runs;
+                runs.extend(b.spam_runs);
+                runs.sort_by(|x, y| x.date.cmp(&y.date));
+                runs
+            },
+            long_messages: {
+                let mut long = a.long_messages;
+                long.extend(b.long_messages);
+                long.sort_by(|x, y| x.date.cmp(&y.date));
+                long
+            },
+            poll_stats: merge_poll_stats(a.poll_stats, b.poll_stats),
+            person_stats: merge_person_stats(a.person_stats, b.person_stats),
+            word_firsts: sorted_desc(sum_counts(a.word_firsts, b.word_firsts)),
+            per_person_daily,
+            presence_calendar,
+            sentiment_by_day,
+            sentiment_overall,
+            sentiment_series: {
+                let mut series = a.sentiment_series;
+                series.extend(b.sentiment_series);
+                series.sort_by(|x, y| x.timestamp.cmp(&y.timestamp));
+                series
+            },
+            overall_sentiment: weighted_mean(
+                a.overall_sentiment,
+                a.total_messages as u32,
+                b.overall_sentiment,
+                b.total_messages as u32,
+            ),
+            sentiment_exchange,
+            conversation_starters: sorted_desc(sum_counts(
+                a.conversation_starters,
+                b.conversation_starters,
+            )),
+            starter_success,
+            presence_share: merge_presence_share([
+                (&a.presence_share, a.conversation_count),
+                (&b.presence_share, b.conversation_count),
+            ]),
+            revivals: merge_revivals(a.revivals, b.revivals),
+            conversation_count: a.conversation_count + b.conversation_count,
+            total_conversation_minutes,
+            message_density: metrics::message_density(total_messages, total_conversation_minutes),
+            conversations_per_month: sorted_by_label(sum_counts(
+                a.conversations_per_month,
+                b.conversations_per_month,
+            )),
+            conversation_start_hours: std::array::from_fn(|i| {
+                a.conversation_start_hours[i] + b.conversation_start_hours[i]
+            }),
+            conversation_start_weekdays: std::array::from_fn(|i| {
+                a.conversation_start_weekdays[i] + b.conversation_start_weekdays[i]
+            }),
+            edits_per_conversation: sorted_desc(sum_counts(
+                a.edits_per_conversation,
+                b.edits_per_conversation,
+            )),
+            response_latency: merge_response_latency(a.response_latency, b.response_latency),
+            avg_response_minutes,
+            reply_time_histogram: merge_reply_histograms(
+                a.reply_time_histogram,
+                b.reply_time_histogram,
+            ),
+            biggest_conversation: match (a.biggest_conversation, b.biggest_conversation) {
+                (Some(x), Some(y)) => {
+                    let y_wins = y.message_count > x.message_count
+                        || (y.message_count == x.message_count && y.start < x.start);
+                    Some(if y_wins { y } else { x })
+                }
+                (x, y) => x.or(y),
+            },
+            voice_notes: merge_voice_notes(a.voice_notes, b.voice_notes),
+            head_to_head: None,
+            relationship_score: RelationshipScore::default(),
+            journey: None,
+            warnings: {
+                let mut warnings = a.warnings;
+                for w in b.warnings {
+                    if !warnings.contains(&w) {
+                        warnings.push(w);
+                    }
+                }
+                warnings
+            },
+            merged: true,
+            sampled: merge_sampled(a.sampled, b.sampled),
+            analysis_meta: fingerprint::merge_analysis_meta(&a.analysis_meta, &b.analysis_meta),
+        };
+        merged.head_to_head = head_to_head::merge_head_to_head(
+            &merged,
+            [a.head_to_head.as_ref(), b.head_to_head.as_ref()],
+        );
+        merged.relationship_score = metrics::relationship_score(&merged);
+        merged.highlights = highlights::build_highlights(&merged);
+        merged
+    }
+}
+
+/// Adds counts with matching labels, keeping first-seen label order.
+fn sum_counts(a: Vec<Count>, b: Vec<Count>) -> Vec<Count> {
+    let mut out: Vec<Count> = a;
+    let mut index: HashMap<String, usize> = out
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (c.label.clone(), i))
+        .collect();
+    for c in b {
+        match index.get(&c.label) {
+            Some(&i) => out[i].value += c.value,
+            None => {
+                index.insert(c.label.clone(), out.len());
+                out.push(c);
+            }
+        }
+    }
+    out
+}
+
+fn sorted_desc(mut counts: Vec<Count>) -> Vec<Count> {
+    counts.sort_by(|x, y| y.value.cmp(&x.value).then_with(|| x.label.cmp(&y.label)));
+    counts
+}
+
+fn sorted_by_label(mut counts: Vec<Count>) -> Vec<Count> {
+    counts.sort_by(|x, y| x.label.cmp(&y.label));
+    counts
+}
+
+/// Sums two top-N lists and keeps the longer input's length. Items that fell just
+/// outside either part's cut are missing, so the ranking is approximate.
+fn truncated_sum(a: Vec<Count>, b: Vec<Count>) -> Vec<Count> {
+    let take = a.len().max(b.len());
+    let mut out = sorted_desc(sum_counts(a, b));
+    out.truncate(take);
+    out
+}
+
+/// Pairs the earlier part's early rate with the later part's late rate for people in
+/// both, which stands in for the thirds of the combined chat.
+fn merge_emoji_trend(
+    earlier: Vec<PersonEmojiTrend>,
+    later: Vec<PersonEmojiTrend>,
+) -> Vec<PersonEmojiTrend> {
+    let mut out = merge_by_name(
+        earlier,
+        later,
+        |t| &t.name,
+        |x, y| {
+            x.late_rate = y.late_rate;
+            x.direction = metrics::emoji_trend_direction(x.early_rate, x.late_rate);
+        },
+    );
+    out.sort_by(|x, y| x.name.cmp(&y.name));
+    out
+}
+
+fn merge_punctuation_months(
+    a: Vec<PunctuationMonth>,
+    b: Vec<PunctuationMonth>,
+) -> Vec<PunctuationMonth> {
+    let mut by_month: BTreeMap<String, (u32, u32)> = BTreeMap::new();
+    for entry in a.into_iter().chain(b) {
+        let tally = by_month.entry(entry.month).or_default();
+        tally.0 += entry.questions;
+        tally.1 += entry.exclamations;
+    }
+    by_month
+        .into_iter()
+        .map(|(month, (questions, exclamations))| {
+            metrics::punctuation_month(month, questions, exclamations)
+        })
+        .collect()
+}
+
+fn merge_punctuation_by_person(
+    a: Vec<PersonPunctuation>,
+    b: Vec<PersonPunctuation>,
+) -> Vec<PersonPunctuation> {
+    let mut out = merge_by_name(
+        a,
+        b,
+        |p| &p.name,
+        |x, y| x.monthly = merge_punctuation_months(std::mem::take(&mut x.monthly), y.monthly),
+    );
+    out.sort_by(|x, y| x.name.cmp(&y.name));
+    out
+}
+
+/// A word listed by both parts keeps the earlier origin and adds up the adoptions; the
+/// later part's first use of it was really an adoption too, so counts run slightly low.
+fn merge_word_origins(a: Vec<WordOrigin>, b: Vec<WordOrigin>) -> Vec<WordOrigin> {
+    let take = a.len().max(b.len());
+    let mut out = merge_by_name(
+        a,
+        b,
+        |o| &o.word,
+        |x, y| {
+            if y.first_date < x.first_date {
+                x.coined_by = y.coined_by;
+                x.first_date = y.first_date;
+            }
+            x.adopted_count += y.adopted_count;
+        },
+    );
+    out.sort_by(|x, y| {
+        y.adopted_count
+            .cmp(&x.adopted_count)
+            .then_with(|| x.word.cmp(&y.word))
+    });
+    out.truncate(take);
+    out
+}
+
+/// Each part's `(recent_emojis, last daily entry)`. Scores are re-anchored to the later
+/// part's last day before adding, which is exact for emojis both parts kept.
+fn merge_recent_emojis(parts: [(&[FloatCount], Option<&Count>); 2]) -> Vec<FloatCount> {
+    let last_day =
+        |c: Option<&Count>| c.and_then(|c| NaiveDate::parse_from_str(&c.label, "%Y-%m-%d").ok());
+    let Some(anchor) = parts.iter().filter_map(|(_, last)| last_day(*last)).max() else {
+        return Vec::new();
+    };
+    let take = parts
+        .iter()
+        .map(|(emojis, _)| emojis.len())
+        .max()
+        .unwrap_or(0);
+    let mut totals: HashMap<&str, f32> = HashMap::new();
+    for (emojis, last) in parts {
+        let age = last_day(last).map_or(0, |day| (anchor - day).num_days()) as f32;
+        let decay = 0.5f32.powf(age / phrases::RECENT_EMOJI_HALF_LIFE_DAYS);
+        for e in emojis {
+            *totals.entry(e.label.as_str()).or_insert(0.0) += e.value * decay;
+        }
+    }
+    let mut out: Vec<FloatCount> = totals
+        .into_iter()
+        .map(|(label, value)| FloatCount {
+            label: label.to_string(),
+            value,
+        })
+        .collect();
+    out.sort_by(|x, y| {
+        y.value
+            .total_cmp(&x.value)
+            .then_with(|| x.label.cmp(&y.label))
+    });
+    out.truncate(take);
+    out
+}
+
+fn weighted_mean(a: f32, a_weight: u32, b: f32, b_weight: u32) -> f32 {
+    let total = a_weight + b_weight;
+    if total == 0 {
+        0.0
+    } else {
+        (a * a_weight as f32 + b * b_weight as f32) / total as f32
+    }
+}
+
+/// Merges per-person records by name: `combine` folds a duplicate into the first.
+fn merge_by_name<T>(
+    a: Vec<T>,
+    b: Vec<T>,
+    name: impl Fn(&T) -> &str,
+    mut combine: impl FnMut(&mut T, T),
+) -> Vec<T> {
+    let mut out = a;
+    for item in b {
+        match out.iter().position(|x| name(x) == name(&item)) {
+            Some(i) => combine(&mut out[i], item),
+            None => out.push(item),
+        }
+    }
+    out
+}
+
+/// Parts only keep their routine hours, so an hour that fell short of the threshold in
+/// one part is counted as unused there.
+fn merge_routines(a: Vec<Routine>, b: Vec<Routine>, active_days: u32) -> Vec<Routine> {
+    let mut hour_days = [0u32; 24];
+    for r in a.into_iter().chain(b) {
+        if let Some(slot) = hour_days.get_mut(r.hour as usize) {
+            *slot += r.days;
+        }
+    }
+    metrics::routines_from_counts(&hour_days, active_days)
+}
+
+/// A month split across the parts gets the day-weighted mean of the two medians.
+fn merge_month_hours(a: Vec<MonthHour>, b: Vec<MonthHour>) -> Vec<MonthHour> {
+    let mut by_month: BTreeMap<String, MonthHour> = BTreeMap::new();
+    for entry in a.into_iter().chain(b) {
+        match by_month.get_mut(&entry.month) {
+            Some(kept) => {
+                let days = kept.days + entry.days;
+                kept.median_hour = (kept.median_hour * kept.days as f32
+                    + entry.median_hour * entry.days as f32)
+                    / days.max(1) as f32;
+                kept.days = days;
+            }
+            None => {
+                by_month.insert(entry.month.clone(), entry);
+            }
+        }
+    }
+    by_month.into_values().collect()
+}
+
+/// A silence spanning the seam between the parts isn't seen, so its revival is missed.
+fn merge_revivals(a: Vec<Revival>, b: Vec<Revival>) -> Vec<Revival> {
+    let mut out = merge_by_name(
+        a,
+        b,
+        |x| x.name.as_str(),
+        |x, y| {
+            x.avg_followup_messages = weighted_mean(
+                x.avg_followup_messages,
+                x.count,
+                y.avg_followup_messages,
+                y.count,
+            );
+            x.count += y.count;
+        },
+    );
+    out.sort_by(|x, y| y.count.cmp(&x.count).then_with(|| x.name.cmp(&y.name)));
+    out
+}
+
+/// Each part's `(presence_share, conversation_count)`; shares are weighted by the
+/// part's conversations, and someone missing from a part was present in none of them.
+fn merge_presence_share(parts: [(&[FloatCount], usize); 2]) -> Vec<FloatCount> {
+    let total: usize = parts.iter().map(|(_, count)| count).sum();
+    let mut present: HashMap<&str, f32> = HashMap::new();
+    for (shares, count) in parts {
+        for share in shares {
+            *present.entry(share.label.as_str()).or_insert(0.0) += share.value * count as f32;
+        }
+    }
+    let mut shares: Vec<FloatCount> = present
+        .into_iter()
+        .map(|(label, conversations)| FloatCount {
+            label: label.to_string(),
+            value: conversations / total.max(1) as f32,
+        })
+        .collect();
+    shares.sort_by(|x, y| {
+        y.value
+            .total_cmp(&x.value)
+            .then_with(|| x.label.cmp(&y.label))
+    });
+    shares
+}
+
+/// Each part's `(starter_success, conversation_starters)`; rates are weighted by how
+/// many conversations the person started in that part.
+fn merge_starter_success(parts: [(&[FloatCount], &[Count]); 2]) -> Vec<FloatCount> {
+    let mut totals: HashMap<&str, (f32, u32)> = HashMap::new();
+    for (rates, starters) in parts {
+        for rate in rates {
+            let started = starters
+                .iter()
+                .find(|c| c.label == rate.label)
+                .map_or(0, |c| c.value);
+            let entry = totals.entry(rate.label.as_str()).or_insert((0.0, 0));
+            entry.0 += rate.value * started as f32;
+            entry.1 += started;
+        }
+    }
+    let mut rates: Vec<FloatCount> = totals
+        .into_iter()
+        .map(|(label, (weighted, started))| FloatCount {
+            label: label.to_string(),
+            value: if started == 0 {
+                0.0
+            } else {
+                weighted / started as f32
+            },
+        })
+        .collect();
+    rates.sort_by(|x, y| {
+        y.value
+            .total_cmp(&x.value)
+            .then_with(|| x.label.cmp(&y.label))
+    });
+    rates
+}
+
+fn merge_hourly(a: Vec<HourCount>, b: Vec<HourCount>) -> Vec<HourCount> {
+    let mut hours = [0u32; 24];
+    for h in a.into_iter().chain(b) {
+        if let Some(slot) = hours.get_mut(h.hour as usize) {
+            *slot += h.value;
+        }
+    }
+    hours
+        .iter()
+        .enumerate()
+        .map(|(hour, value)| HourCount {
+            hour: hour as u32,
+            value: *value,
+        })
+        .collect()
+}
+
+/// Message counts add up; for a date both parts saw, the baseline comparison and top
+/// phrase come from the busier part and sentiment is message-weighted.
+fn merge_special_days(a: Vec<SpecialDay>, b: Vec<SpecialDay>) -> Vec<SpecialDay> {
+    let mut out = a;
+    for day in b {
+        match out
+            .iter_mut()
+            .find(|d| d.date == day.date && d.label == day.label)
+        {
+            Some(kept) => {
+                kept.sentiment_mean = weighted_mean(
+                    kept.sentiment_mean,
+                    kept.messages,
+                    day.sentiment_mean,
+                    day.messages,
+                );
+                if day.messages > kept.messages {
+                    kept.vs_average_pct = day.vs_average_pct;
+                    kept.top_phrase = day.top_phrase;
+                }
+                kept.messages += day.messages;
+            }
+            None => out.push(day),
+        }
+    }
+    out
+}
+
+fn merge_annotations(
+    a: Vec<TimelineAnnotation>,
+    b: Vec<TimelineAnnotation>,
+) -> Vec<TimelineAnnotation> {
+    let mut out = merge_by_name(
+        a,
+        b,
+        |x| x.date.as_str(),
+        |x, y| {
+            if x.kind == y.kind {
+                x.evidence_count += y.evidence_count;
+            } else if y.evidence_count > x.evidence_count {
+                *x = y;
+            }
+        },
+    );
+    out.sort_by(|x, y| x.date.cmp(&y.date));
+    out
+}
+
+fn merge_buckets(a: Vec<PersonBuckets>, b: Vec<PersonBuckets>) -> Vec<PersonBuckets> {
+    let mut out = merge_by_name(
+        a,
+        b,
+        |x| x.name.as_str(),
+        |x, y| {
+            x.messages += y.messages;
+            x.hourly.iter_mut().zip(y.hourly).for_each(|(v, w)| *v += w);
+            x.daily.iter_mut().zip(y.daily).for_each(|(v, w)| *v += w);
+            x.monthly
+                .iter_mut()
+                .zip(y.monthly)
+                .for_each(|(v, w)| *v += w);
+        },
+    );
+    out.sort_by(|x, y| {
+        y.messages
+            .cmp(&x.messages)
+            .then_with(|| x.name.cmp(&y.name))
+    });
+    out
+}
+
+fn merge_person_phrases(a: Vec<PersonPhrases>, b: Vec<PersonPhrases>) -> Vec<PersonPhrases> {
+    let mut out = merge_by_name(
+        a,
+        b,
+        |x| x.name.as_str(),
+        |x, y| x.phrases = truncated_sum(std::mem::take(&mut x.phrases), y.phrases),
+    );
+    out.sort_by(|x, y| x.name.cmp(&y.name));
+    out
+}
+
+/// Months only one part saw are kept as-is. A month split across the seam keeps the
+/// part that could tell the language, preferring the more confident one.
+fn merge_language_timeline(a: Vec<LanguageMonth>, b: Vec<LanguageMonth>) -> Vec<LanguageMonth> {
+    let mut by_month: BTreeMap<String, LanguageMonth> = BTreeMap::new();
+    for entry in a.into_iter().chain(b) {
+        match by_month.get(&entry.month) {
+            Some(kept) if kept.share >= entry.share => {}
+            _ => {
+                by_month.insert(entry.month.clone(), entry);
+            }
+        }
+    }
+    by_month.into_values().collect()
+}
+
+fn merge_poll_stats(a: PollStats, b: PollStats) -> PollStats {
+    let mut polls = a.polls;
+    polls.extend(b.polls);
+    polls.sort_by(|x, y| x.date.cmp(&y.date));
+    PollStats {
+        total_polls: a.total_polls + b.total_polls,
+        total_votes: a.total_votes + b.total_votes,
+        by_sender: sorted_desc(sum_counts(a.by_sender, b.by_sender)),
+        polls,
+    }
+}
+
+fn merge_fun_facts(a: Vec<FunFact>, b: Vec<FunFact>) -> Vec<FunFact> {
+    let mut out = merge_by_name(
+        a,
+        b,
+        |x| x.name.as_str(),
+        |x, y| {
+            let counted = |f: &FunFact| {
+                if f.average_message_length == 0 {
+                    0.0
+                } else {
+                    f.total_words as f64 / f.average_message_length as f64
+                }
+            };
+            let messages = counted(x) + counted(&y);
+            x.total_words += y.total_words;
+            x.longest_message_words = x.longest_message_words.max(y.longest_message_words);
+            x.unique_words = x.unique_words.max(y.unique_words);
+            x.average_message_length = if messages == 0.0 {
+                0
+            } else {
+                (x.total_words as f64 / messages).round() as u32
+            };
+            for emoji in y.top_emojis {
+                if x.top_emojis.len() < 3 && !x.top_emojis.contains(&emoji) {
+                    x.top_emojis.push(emoji);
+                }
+            }
+        },
+    );
+    out.sort_by(|x, y| {
+        y.total_words
+            .cmp(&x.total_words)
+            .then_with(|| x.name.cmp(&y.name))
+    });
+    out
+}
+
+fn merge_person_stats(a: Vec<PersonStat>, b: Vec<PersonStat>) -> Vec<PersonStat> {
+    let mut out = merge_by_name(
+        a,
+        b,
+        |x| x.name.as_str(),
+        |x, y| {
+            let counted = |p: &PersonStat| {
+                if p.average_words_per_message == 0.0 {
+                    0.0
+                } else {
+                    p.total_words as f32 / p.average_words_per_message
+                }
+            };
+            let (x_messages, y_messages) = (counted(x), counted(&y));
+            let messages = x_messages + y_messages;
+            // Percentiles don't combine; a message-weighted mean is the estimate.
+            let (xw, yw) = (x_messages.round() as u32, y_messages.round() as u32);
+            let mean = |a: f32, b: f32| weighted_mean(a, xw, b, yw);
+            x.p25_words = mean(x.p25_words, y.p25_words);
+            x.p75_words = mean(x.p75_words, y.p75_words);
+            x.p95_words = mean(x.p95_words, y.p95_words);
+            // A run straddling the seam counts as two here.
+            x.avg_burst_size = mean(x.avg_burst_size, y.avg_burst_size);
+            x.lowercase_i_ratio = mean(x.lowercase_i_ratio, y.lowercase_i_ratio);
+            if y.total_words > x.total_words {
+                x.dominant_color = y.dominant_color.or(x.dominant_color.take());
+            } else if x.dominant_color.is_none() {
+                x.dominant_color = y.dominant_color;
+            }
+            x.total_words += y.total_words;
+            x.unique_words = x.unique_words.max(y.unique_words);
+            x.longest_message_words = x.longest_message_words.max(y.longest_message_words);
+            x.average_words_per_message = if messages == 0.0 {
+                0.0
+            } else {
+                x.total_words as f32 / messages
+            };
+            x.top_emojis = truncated_sum(std::mem::take(&mut x.top_emojis), y.top_emojis);
+            x.emoji_pairs = truncated_sum(std::mem::take(&mut x.emoji_pairs), y.emoji_pairs);
+        },
+    );
+    out.sort_by(|x, y| {
+        y.total_words
+            .cmp(&x.total_words)
+            .then_with(|| x.name.cmp(&y.name))
+    });
+    out
+}
+
+/// The merged chat's streak, or the longer part's when ignored ranges (which aren't
+/// kept) made that one longer.
+fn merge_longest_streak(
+    daily: &[Count],
+    a: Option<DailyStreak>,
+    b: Option<DailyStreak>,
+) -> Option<DailyStreak> {
+    [metrics::daily_streak(daily, &[]), a, b]
+        .into_iter()
+        .flatten()
+        .reduce(|best, s| if s.days > best.days { s } else { best })
+}
+
+fn merge_per_person_daily(a: Vec<PersonDaily>, b: Vec<PersonDaily>) -> Vec<PersonDaily> {
+    let mut out = merge_by_name(
+        a,
+        b,
+        |x| x.name.as_str(),
+        |x, y| {
+            x.daily = sorted_by_label(sum_counts(std::mem::take(&mut x.daily), y.daily));
+            // Ignored streak ranges aren't kept, so never drop below either part's streak.
+            let parts_best = x.longest_streak.max(y.longest_streak);
+            x.longest_streak = metrics::longest_streak(&x.daily)
+                .map_or(0, |(days, _, _)| days)
+                .max(parts_best);
+        },
+    );
+    out.sort_by(|x, y| x.name.cmp(&y.name));
+    out
+}
+
+/// Re-pairs the merged top phrases with an example, preferring the earlier part's. Stays
+/// empty when neither part collected examples.
+fn merge_phrase_examples(
+    top_phrases: &[Count],
+    a: Vec<PhraseWithExample>,
+    b: Vec<PhraseWithExample>,
+) -> Vec<PhraseWithExample> {
+    if a.is_empty() && b.is_empty() {
+        return Vec::new();
+    }
+    let mut examples: HashMap<String, String> = HashMap::new();
+    for p in b.into_iter().chain(a) {
+        if let Some(example) = p.example {
+            examples.insert(p.label, example);
+        }
+    }
+    top_phrases
+        .iter()
+        .map(|c| PhraseWithExample {
+            label: c.label.clone(),
+            value: c.value,
+            example: examples.get(&c.label).cloned(),
+        })
+        .collect()
+}
+
+/// A merge with any sampled part is marked sampled; the sample sizes add up.
+fn merge_sampled(a: Option<SampleInfo>, b: Option<SampleInfo>) -> Option<SampleInfo> {
+    match (a, b) {
+        (Some(mut x), Some(y)) => {
+            x.messages += y.messages;
+            Some(x)
+        }
+        (x, y) => x.or(y),
+    }
+}
+
+fn merge_emoji_monthly(
+    a: Vec<PersonEmojiMonthly>,
+    b: Vec<PersonEmojiMonthly>,
+) -> Vec<PersonEmojiMonthly> {
+    let mut out = merge_by_name(
+        a,
+        b,
+        |x| x.name.as_str(),
+        |x, y| {
+            // Only a month split across the seam shows up in both parts.
+            let mut monthly = merge_by_name(
+                std::mem::take(&mut x.monthly),
+                y.monthly,
+                |m| m.month.as_str(),
+                |m, n| m.top = truncated_sum(std::mem::take(&mut m.top), n.top),
+            );
+            monthly.sort_by(|m, n| m.month.cmp(&n.month));
+            x.monthly = monthly;
+        },
+    );
+    out.sort_by(|x, y| x.name.cmp(&y.name));
+    out
+}
+
+fn merge_response_latency(
+    a: Vec<ResponseLatency>,
+    b: Vec<ResponseLatency>,
+) -> Vec<ResponseLatency> {
+    let mut out = merge_by_name(
+        a,
+        b,
+        |x| x.name.as_str(),
+        |x, y| {
+            // Medians and percentiles don't combine; a reply-weighted mean is the estimate.
+            let (xr, yr) = (x.replies, y.replies);
+            let mean = |a: f32, b: f32| weighted_mean(a, xr, b, yr);
+            x.median_minutes = mean(x.median_minutes, y.median_minutes);
+            x.p25_minutes = mean(x.p25_minutes, y.p25_minutes);
+            x.p75_minutes = mean(x.p75_minutes, y.p75_minutes);
+            x.p95_minutes = mean(x.p95_minutes, y.p95_minutes);
+            x.replies += y.replies;
+        },
+    );
+    out.sort_by(|x, y| x.name.cmp(&y.name));
+    out
+}
+
+fn merge_reply_histograms(
+    a: Vec<PersonReplyHist>,
+    b: Vec<PersonReplyHist>,
+) -> Vec<PersonReplyHist> {
+    let mut out = merge_by_name(
+        a,
+        b,
+        |x| x.name.as_str(),
+        |x, y| x.buckets = sum_counts(std::mem::take(&mut x.buckets), y.buckets),
+    );
+    out.sort_by(|x, y| x.name.cmp(&y.name));
+    out
+}
+
+fn merge_voice_notes(a: VoiceNotes, b: VoiceNotes) -> VoiceNotes {
+    let mut per_person = merge_by_name(
+        a.per_person,
+        b.per_person,
+        |x: &PersonVoiceNotes| x.name.as_str(),
+        |x, y| {
+            x.count += y.count;
+            x.minutes += y.minutes;
+        },
+    );
+    per_person.sort_by(|x, y| y.count.cmp(&x.count).then_with(|| x.name.cmp(&y.name)));
+
+    let longest = match (a.longest, b.longest) {
+        (Some(x), Some(y)) => {
+            let y_wins = y.seconds > x.seconds || (y.seconds == x.seconds && y.date < x.date);
+            Some(if y_wins { y } else { x })
+        }
+        (x, y) => x.or(y),
+    };
+
+    VoiceNotes {
+        count: a.count + b.count,
+        unknown_duration: a.unknown_duration + b.unknown_duration,
+        total_minutes: a.total_minutes + b.total_minutes,
+        per_person,
+        longest,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::summarize;
+    use serde::Serialize;
+
+    fn json<T: Serialize>(value: &T) -> serde_json::Value {
+        serde_json::to_value(value).unwrap()
+    }
+
+    fn by_label(counts: &[Count]) -> Vec<(String, u32)> {
+        let mut pairs: Vec<(String, u32)> =
+            counts.iter().map(|c| (c.label.clone(), c.value)).collect();
+        pairs.sort();
+        pairs
+    }
+
+    const PART1: &str = "[12/30/23, 9:00:00 AM] Alice: Happy new year soon! 😂😂😂
+[12/30/23, 9:05:00 AM] Bob: can't wait, love it
+[12/31/23, 11:00:00 PM] Alice: This message was deleted
+[12/31/23, 11:30:00 PM] Bob: PTT-20231231-WA0001.opus (0:42)";
+
+    const PART2: &str = "[1/1/24, 12:05:00 AM] Alice: happy new year!!
+[1/1/24, 12:06:00 AM] Bob: awful fireworks though 😢
+[1/2/24, 8:00:00 AM] Alice: <Media omitted>
+[2/14/24, 7:00:00 PM] Bob: love you";
+
+    #[test]
+    fn merge_matches_whole_chat_for_exact_fields() {
+        let whole = summarize(&format!("{PART1}\n{PART2}"), 50, 20).unwrap();
+        let merged = Summary::merge(
+            summarize(PART1, 50, 20).unwrap(),
+            summarize(PART2, 50, 20).unwrap(),
+        );
+
+        assert!(merged.merged);
+        assert!(!whole.merged);
+        assert_eq!(merged.total_messages, whole.total_messages);
+        assert_eq!(by_label(&merged.by_sender), by_label(&whole.by_sender));
+        assert_eq!(merged.participants, whole.participants);
+        assert_eq!(json(&merged.daily), json(&whole.daily));
+        assert_eq!(json(&merged.hourly), json(&whole.hourly));
+        assert_eq!(merged.deleted_you, whole.deleted_you);
+        assert_eq!(merged.deleted_others, whole.deleted_others);
+        assert_eq!(json(&merged.timeline), json(&whole.timeline));
+        assert_eq!(json(&merged.volume_trend), json(&whole.volume_trend));
+        assert_eq!(json(&merged.weekly), json(&whole.weekly));
+        assert_eq!(json(&merged.weekly_streak), json(&whole.weekly_streak));
+        assert_eq!(json(&merged.monthly), json(&whole.monthly));
+        assert_eq!(json(&merged.quarterly), json(&whole.quarterly));
+        assert_eq!(merged.seasonal, whole.seasonal);
+        assert_eq!(
+            json(&merged.per_person_daily),
+            json(&whole.per_person_daily)
+        );
+        assert_eq!(
+            by_label(&merged.emoji_spam_by_sender),
+            by_label(&whole.emoji_spam_by_sender)
+        );
+        assert_eq!(
+            json(&merged.sentiment_by_day),
+            json(&whole.sentiment_by_day)
+        );
+        assert_eq!(json(&merged.voice_notes), json(&whole.voice_notes));
+
+        let mut merged_buckets = json(&merged.buckets_by_person);
+        let mut whole_buckets = json(&whole.buckets_by_person);
+        for v in [&mut merged_buckets, &mut whole_buckets] {
+            v.as_array_mut()
+                .unwrap()
+                .sort_by_key(|b| b["name"].as_str().unwrap().to_string());
+        }
+        assert_eq!(merged_buckets, whole_buckets);
+
+        for (m, w) in merged
+            .sentiment_overall
+            .iter()
+            .zip(&whole.sentiment_overall)
+        {
+            assert_eq!(
+                (&m.name, m.pos, m.neu, m.neg),
+                (&w.name, w.pos, w.neu, w.neg)
+            );
+            assert!((m.mean - w.mean).abs() < 1e-5);
+        }
+        assert!((merged.overall_sentiment - whole.overall_sentiment).abs() < 1e-5);
+        let exchange = merged.sentiment_exchange.as_ref().unwrap();
+        assert_eq!(exchange.len(), 2);
+
+        assert!(merged.journey.is_none());
+        let h2h = merged.head_to_head.as_ref().unwrap();
+        let whole_h2h = whole.head_to_head.as_ref().unwrap();
+        for metric in ["messages", "emojis", "media", "longest_streak_days"] {
+            let row = |h: &crate::types::HeadToHead| {
+                let r = h.rows.iter().find(|r| r.metric == metric).unwrap();
+                (r.left, r.right, r.winner.clone())
+            };
+            assert_eq!(row(h2h), row(whole_h2h), "{metric}");
+        }
+    }
+
+    #[test]
+    fn merge_approximates_ranked_lists() {
+        let merged = Summary::merge(
+            summarize(PART1, 50, 20).unwrap(),
+            summarize(PART2, 50, 20).unwrap(),
+        );
+        let happy = merged
+            .word_cloud
+            .iter()
+            .find(|c| c.label == "happy")
+            .unwrap();
+        assert_eq!(happy.value, 2);
+        let laugh = merged
+            .emoji_cloud_sentiment
+            .iter()
+            .find(|e| e.label == "😂");
+        assert_eq!(laugh.map(|e| e.sentiment), Some(1));
+    }
+
+    #[test]
+    fn sum_counts_keeps_first_seen_order() {
+        let a = vec![
+            Count {
+                label: "x".into(),
+                value: 1,
+            },
+            Count {
+                label: "y".into(),
+                value: 2,
+            },
+        ];
+        let b = vec![
+            Count {
+                label: "z".into(),
+                value: 5,
+            },
+            Count {
+                label: "x".into(),
+                value: 3,
+            },
+        ];
+        let merged = sum_counts(a, b);
+        let pairs: Vec<(&str, u32)> = merged.iter().map(|c| (c.label.as_str(), c.value)).collect();
+        assert_eq!(pairs, vec![("x", 4), ("y", 2), ("z", 5)]);
+    }
+}
@@ -1,48 +1,67 @@
-use chrono::{Datelike, NaiveDate, Timelike};
-use std::collections::{BTreeMap, HashMap};
+use chrono::{Datelike, Days, NaiveDate, NaiveTime, Timelike};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use unicode_segmentation::UnicodeSegmentation;
 
+use crate::options::{Hemisphere, SpecialDate, TimelineGranularity};
 use crate::parsing::{
-    parse_timestamp, re_bracket_pattern, re_hyphen_pattern, weekday_index, weekday_label, Message,
+    parse_timestamp, re_bracket_pattern, re_hyphen_pattern, re_senderless_pattern, sorted_view,
+    weekday_index, weekday_label, Message,
 };
+use crate::phrases::{emoji_pairs, keyword_counts, top_phrases};
+use crate::quantiles::{Percentiles, QuantileSketch};
+use crate::sentiment::{sentiment_score, EmojiWeights};
 use crate::text::{
-    color_hex_for_word, extract_emojis, is_media_omitted_message, pick_dominant_color,
+    celebration_category, color_hex_for_word, detect_language, extract_emojis, is_edited_message,
+    is_emoji_spam, is_media_omitted_message, is_placeholder_message, is_voice_note, parse_poll,
+    pick_dominant_color, punctuation_counts, stopwords_set, tokenize, truncate_chars,
+    voice_note_seconds, SNIPPET_MAX_CHARS,
+};
+use crate::types::{
+    ConvHighlight, Count, DailyStreak, FloatCount, FunFact, HourCount, ImbalanceInfo,
+    LanguageMonth, LongMessage, LongestVoiceNote, MonthHour, MonthSwing, PersonBuckets,
+    PersonDaily, PersonEmojiTrend, PersonMonthHours, PersonPresence, PersonPunctuation,
+    PersonReplyHist, PersonStat, PersonVoiceNotes, PersonWeekdayShare, Poll, PollStats,
+    PresenceCalendar, PunctuationMonth, RelationshipScore, ResponseLatency, Revival, Routine,
+    SentimentDay, SpamRun, SpecialDay, Summary, SwingKind, TimelineAnnotation, TimelineUnit, Trend,
+    TrendDirection, VoiceNotes, WeeklyStreak,
 };
-use crate::types::{Count, FunFact, HourCount, PersonBuckets, PersonDaily, PersonStat};
 
+/// Who opened each conversation, and how many there were. With `skip_placeholders` the
+/// credit goes to the first substantive message rather than a photo, deleted message or
+/// call record; placeholders still count as activity for the gap rule.
 pub(crate) fn conversation_initiations(
     messages: &[Message],
     gap_minutes: i64,
+    skip_placeholders: bool,
 ) -> (Vec<Count>, usize) {
-    conversation_initiations_with_gap(messages, gap_minutes)
+    conversation_initiations_with_gap(messages, gap_minutes, skip_placeholders)
 }
 
 pub(crate) fn conversation_initiations_with_gap(
     messages: &[Message],
     gap_minutes: i64,
+    skip_placeholders: bool,
 ) -> (Vec<Count>, usize) {
     if messages.is_empty() {
         return (Vec::new(), 0);
     }
 
-    let mut sorted = messages.to_vec();
-    sorted.sort_by_key(|m| m.dt);
+    let sorted = sorted_view(messages);
 
     let mut initiations: HashMap<String, u32> = HashMap::new();
     let mut conversation_count = 1usize;
     let mut prev_dt = sorted[0].dt;
-    let mut current_initiator_recorded = true;
-
-    *initiations.entry(sorted[0].sender.clone()).or_insert(0) += 1;
+    let mut current_initiator_recorded = false;
 
-    for m in sorted.iter().skip(1) {
+    for m in sorted.iter() {
         let gap = (m.dt - prev_dt).num_minutes();
         if gap > gap_minutes {
             conversation_count += 1;
             current_initiator_recorded = false;
         }
 
-        if !current_initiator_recorded {
+        let credited = !skip_placeholders || !is_placeholder_message(&m.text);
+        if !current_initiator_recorded && credited {
             *initiations.entry(m.sender.clone()).or_insert(0) += 1;
             current_initiator_recorded = true;
         }
@@ -54,10 +73,655 @@ pub(crate) fn conversation_initiations_with_gap(
         .into_iter()
         .map(|(label, value)| Count { label, value })
         .collect();
-    items.sort_by_key(|c| std::cmp::Reverse(c.value));
+    items.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
     (items, conversation_count)
 }
 
+/// For each person, the fraction of the conversations they started that got a reply
+/// from someone else rather than dying as a monologue. Starters are credited as in
+/// `conversation_initiations`; highest rate first.
+pub(crate) fn starter_success(
+    messages: &[Message],
+    gap_minutes: i64,
+    skip_placeholders: bool,
+) -> Vec<FloatCount> {
+    let sorted = sorted_view(messages);
+
+    // (conversations started, of which answered)
+    let mut tally: HashMap<&str, (u32, u32)> = HashMap::new();
+    for segment in segment_conversations(&sorted, gap_minutes) {
+        let conversation = segment.messages(&sorted);
+        let Some(first) = conversation
+            .iter()
+            .position(|m| !skip_placeholders || !is_placeholder_message(&m.text))
+        else {
+            continue;
+        };
+        let starter = conversation[first].sender.as_str();
+        let answered = conversation[first + 1..]
+            .iter()
+            .any(|m| m.sender != starter);
+        let entry = tally.entry(starter).or_insert((0, 0));
+        entry.0 += 1;
+        entry.1 += answered as u32;
+    }
+
+    let mut rates: Vec<FloatCount> = tally
+        .into_iter()
+        .map(|(name, (started, answered))| FloatCount {
+            label: name.to_string(),
+            value: answered as f32 / started as f32,
+        })
+        .collect();
+    rates.sort_by(|a, b| {
+        b.value
+            .total_cmp(&a.value)
+            .then_with(|| a.label.cmp(&b.label))
+    });
+    rates
+}
+
+/// Who messaged first after each silence longer than `gap_hours`, and how many
+/// messages followed before the chat went quiet again. The chat's opening message is
+/// not a revival.
+pub(crate) fn revivals(messages: &[Message], gap_hours: u32) -> Vec<Revival> {
+    let sorted = sorted_view(messages);
+
+    // (revivals, follow-up messages)
+    let mut tally: HashMap<&str, (u32, u32)> = HashMap::new();
+    for segment in segment_conversations(&sorted, gap_hours as i64 * 60)
+        .iter()
+        .skip(1)
+    {
+        let entry = tally
+            .entry(sorted[segment.start].sender.as_str())
+            .or_insert((0, 0));
+        entry.0 += 1;
+        entry.1 += segment.len() as u32 - 1;
+    }
+
+    let mut items: Vec<Revival> = tally
+        .into_iter()
+        .map(|(name, (count, followups))| Revival {
+            name: name.to_string(),
+            count,
+            avg_followup_messages: followups as f32 / count as f32,
+        })
+        .collect();
+    items.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    items
+}
+
+/// Conversations (split as in `segment_conversations`) per month of their first
+/// message, chronological.
+pub(crate) fn conversations_per_month(messages: &[Message], gap_minutes: i64) -> Vec<Count> {
+    let sorted = sorted_view(messages);
+
+    let mut map: BTreeMap<String, u32> = BTreeMap::new();
+    for segment in segment_conversations(&sorted, gap_minutes) {
+        let first = sorted[segment.start].dt;
+        *map.entry(format!("{:04}-{:02}", first.year(), first.month()))
+            .or_insert(0) += 1;
+    }
+    map.into_iter()
+        .map(|(label, value)| Count { label, value })
+        .collect()
+}
+
+/// Hour-of-day and weekday histograms of when conversations (as split by
+/// `segment_conversations`) begin.
+pub(crate) fn conversation_start_histograms(
+    messages: &[Message],
+    gap_minutes: i64,
+) -> ([u32; 24], [u32; 7]) {
+    let sorted = sorted_view(messages);
+
+    let (mut hours, mut weekdays) = ([0u32; 24], [0u32; 7]);
+    for segment in segment_conversations(&sorted, gap_minutes) {
+        let first = sorted[segment.start].dt;
+        hours[first.hour() as usize] += 1;
+        weekdays[weekday_index(first.weekday())] += 1;
+    }
+    (hours, weekdays)
+}
+
+/// Fraction (0-1) of all conversations (split as in `segment_conversations`) each person
+/// wrote in at all, however little; highest first, ties by name.
+pub(crate) fn presence_share(messages: &[Message], gap_minutes: i64) -> Vec<FloatCount> {
+    let sorted = sorted_view(messages);
+
+    let segments = segment_conversations(&sorted, gap_minutes);
+    let mut present: HashMap<&str, u32> = HashMap::new();
+    for segment in &segments {
+        let senders: HashSet<&str> = segment
+            .messages(&sorted)
+            .iter()
+            .map(|m| m.sender.as_str())
+            .collect();
+        for sender in senders {
+            *present.entry(sender).or_insert(0) += 1;
+        }
+    }
+
+    let mut shares: Vec<FloatCount> = present
+        .into_iter()
+        .map(|(name, count)| FloatCount {
+            label: name.to_string(),
+            value: count as f32 / segments.len() as f32,
+        })
+        .collect();
+    shares.sort_by(|a, b| {
+        b.value
+            .total_cmp(&a.value)
+            .then_with(|| a.label.cmp(&b.label))
+    });
+    shares
+}
+
+/// Minutes spent in conversation: first to last message of each conversation (split
+/// as in `segment_conversations`), with a one-minute floor so a lone message counts.
+pub(crate) fn conversation_minutes(messages: &[Message], gap_minutes: i64) -> u32 {
+    let sorted = sorted_view(messages);
+
+    segment_conversations(&sorted, gap_minutes)
+        .iter()
+        .map(|segment| {
+            let span = sorted[segment.end - 1].dt - sorted[segment.start].dt;
+            span.num_minutes().max(1) as u32
+        })
+        .sum()
+}
+
+/// Messages per hour of active conversation; 0 for an empty chat.
+pub(crate) fn message_density(total_messages: usize, conversation_minutes: u32) -> f32 {
+    if conversation_minutes == 0 {
+        return 0.0;
+    }
+    total_messages as f32 / conversation_minutes as f32 * 60.0
+}
+
+/// Edited messages per conversation (split as in `segment_conversations`), labelled by
+/// the conversation's first timestamp (`"YYYY-MM-DD HH:MM"`). Conversations without
+/// edits are left out; the most edit-heavy come first.
+pub(crate) fn edits_per_conversation(messages: &[Message], gap_minutes: i64) -> Vec<Count> {
+    let sorted = sorted_view(messages);
+
+    let mut out: Vec<Count> = segment_conversations(&sorted, gap_minutes)
+        .into_iter()
+        .filter_map(|segment| {
+            let edits = segment
+                .messages(&sorted)
+                .iter()
+                .filter(|m| is_edited_message(&m.text))
+                .count() as u32;
+            (edits > 0).then(|| Count {
+                label: sorted[segment.start]
+                    .dt
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string(),
+                value: edits,
+            })
+        })
+        .collect();
+    out.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
+    out
+}
+
+/// A run of messages with no silence longer than the conversation gap, stored as an
+/// index range into the time-sorted slice it was segmented from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ConversationSegment {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+impl ConversationSegment {
+    pub(crate) fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub(crate) fn messages<'a>(&self, sorted: &'a [Message]) -> &'a [Message] {
+        &sorted[self.start..self.end]
+    }
+}
+
+/// Splits time-sorted messages into conversations using the same gap rule as
+/// `conversation_initiations`.
+pub(crate) fn segment_conversations(
+    sorted: &[Message],
+    gap_minutes: i64,
+) -> Vec<ConversationSegment> {
+    let mut segments = Vec::new();
+    if sorted.is_empty() {
+        return segments;
+    }
+
+    let mut start = 0usize;
+    for i in 1..sorted.len() {
+        let gap = (sorted[i].dt - sorted[i - 1].dt).num_minutes();
+        if gap > gap_minutes {
+            segments.push(ConversationSegment { start, end: i });
+            start = i;
+        }
+    }
+    segments.push(ConversationSegment {
+        start,
+        end: sorted.len(),
+    });
+    segments
+}
+
+/// Minutes from each message to the reply that followed it, paired with the replier.
+/// A reply is the next message from a different sender within `window_minutes`.
+pub(crate) fn reply_gaps(messages: &[Message], window_minutes: i64) -> Vec<(String, f32)> {
+    let sorted = sorted_view(messages);
+
+    sorted
+        .windows(2)
+        .filter_map(|pair| {
+            let (prev, m) = (&pair[0], &pair[1]);
+            let gap = m.dt - prev.dt;
+            if m.sender == prev.sender || gap.num_minutes() > window_minutes {
+                return None;
+            }
+            Some((m.sender.clone(), gap.num_seconds() as f32 / 60.0))
+        })
+        .collect()
+}
+
+/// Median reply latency per person, sorted by name. The median and percentiles all
+/// come from the same sorted gaps.
+pub(crate) fn response_latency(gaps: &[(String, f32)]) -> Vec<ResponseLatency> {
+    let mut by_person: BTreeMap<&str, Vec<f32>> = BTreeMap::new();
+    for (name, minutes) in gaps {
+        by_person.entry(name).or_default().push(*minutes);
+    }
+    by_person
+        .into_iter()
+        .map(|(name, mut minutes)| {
+            minutes.sort_by(|a, b| a.total_cmp(b));
+            let Percentiles { p25, p75, p95 } = Percentiles::of_sorted(&minutes);
+            ResponseLatency {
+                name: name.to_string(),
+                replies: minutes.len() as u32,
+                median_minutes: median(&mut minutes),
+                p25_minutes: p25,
+                p75_minutes: p75,
+                p95_minutes: p95,
+            }
+        })
+        .collect()
+}
+
+/// Median reply latency across every sender, or 0 when there were no replies.
+pub(crate) fn avg_response_minutes(gaps: &[(String, f32)]) -> f32 {
+    let mut minutes: Vec<f32> = gaps.iter().map(|(_, m)| *m).collect();
+    median(&mut minutes)
+}
+
+/// Upper bounds (exclusive, in minutes) and labels for the reply-time histogram.
+const REPLY_BUCKETS: [(f32, &str); 5] = [
+    (1.0, "<1m"),
+    (5.0, "1-5m"),
+    (30.0, "5-30m"),
+    (120.0, "30m-2h"),
+    (f32::INFINITY, ">2h"),
+];
+
+/// Buckets each person's reply latencies (from `reply_gaps`) into fixed ranges,
+/// sorted by name.
+pub(crate) fn reply_time_histogram(gaps: &[(String, f32)]) -> Vec<PersonReplyHist> {
+    let mut by_person: BTreeMap<&str, [u32; REPLY_BUCKETS.len()]> = BTreeMap::new();
+    for (name, minutes) in gaps {
+        let idx = REPLY_BUCKETS
+            .iter()
+            .position(|(upper, _)| *minutes < *upper)
+            .unwrap_or(REPLY_BUCKETS.len() - 1);
+        by_person.entry(name).or_default()[idx] += 1;
+    }
+
+    by_person
+        .into_iter()
+        .map(|(name, counts)| PersonReplyHist {
+            name: name.to_string(),
+            buckets: REPLY_BUCKETS
+                .iter()
+                .zip(counts)
+                .map(|((_, label), value)| Count {
+                    label: label.to_string(),
+                    value,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn median(values: &mut [f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+const CONVERSATION_PREVIEW_LEN: usize = 3;
+
+/// The conversation with the most messages; the earliest one wins ties.
+pub(crate) fn biggest_conversation(
+    messages: &[Message],
+    gap_minutes: i64,
+) -> Option<ConvHighlight> {
+    let sorted = sorted_view(messages);
+
+    let segments = segment_conversations(&sorted, gap_minutes);
+    let best = segments
+        .iter()
+        .reduce(|best, seg| if seg.len() > best.len() { seg } else { best })?;
+    let convo = best.messages(&sorted);
+    let (first, last) = (convo.first()?, convo.last()?);
+
+    let mut participants: Vec<String> = convo.iter().map(|m| m.sender.clone()).collect();
+    participants.sort();
+    participants.dedup();
+
+    Some(ConvHighlight {
+        date: first.dt.format("%Y-%m-%d").to_string(),
+        start: first.dt.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        end: last.dt.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        message_count: convo.len() as u32,
+        participants,
+        preview: convo
+            .iter()
+            .take(CONVERSATION_PREVIEW_LEN)
+            .map(|m| truncate_chars(&m.text, SNIPPET_MAX_CHARS))
+            .collect(),
+    })
+}
+
+/// Messages per sender that are a single emoji repeated, most prolific first.
+pub(crate) fn emoji_spam_by_sender(messages: &[Message]) -> Vec<Count> {
+    let spam: Vec<Message> = messages
+        .iter()
+        .filter(|m| is_emoji_spam(&m.text))
+        .cloned()
+        .collect();
+    count_by_sender(&spam)
+}
+
+/// Messages a person needs in both the first and the last third of the chat before their
+/// emoji trend is reported.
+const EMOJI_TREND_MIN_MESSAGES: u32 = 10;
+
+/// Change in emojis per message between the first and last third beyond which a person's
+/// emoji use counts as growing or declining.
+const EMOJI_TREND_MIN_CHANGE: f32 = 0.1;
+
+pub(crate) fn emoji_trend_direction(early_rate: f32, late_rate: f32) -> TrendDirection {
+    let change = late_rate - early_rate;
+    if change >= EMOJI_TREND_MIN_CHANGE {
+        TrendDirection::Growing
+    } else if change <= -EMOJI_TREND_MIN_CHANGE {
+        TrendDirection::Declining
+    } else {
+        TrendDirection::Stable
+    }
+}
+
+/// Each person's emojis per message in the first versus the last third of the chat's
+/// time span, for people with `EMOJI_TREND_MIN_MESSAGES` messages in both; by name.
+pub(crate) fn emoji_trend_by_person(messages: &[Message]) -> Vec<PersonEmojiTrend> {
+    let (Some(start), Some(end)) = (
+        messages.iter().map(|m| m.dt).min(),
+        messages.iter().map(|m| m.dt).max(),
+    ) else {
+        return Vec::new();
+    };
+    let third = (end - start) / 3;
+    let (early_end, late_start) = (start + third, end - third);
+    if early_end >= late_start {
+        return Vec::new();
+    }
+
+    // (messages, emojis) in the first and the last third.
+    let mut tallies: BTreeMap<&str, [(u32, u32); 2]> = BTreeMap::new();
+    for m in messages {
+        let slot = if m.dt < early_end {
+            0
+        } else if m.dt > late_start {
+            1
+        } else {
+            continue;
+        };
+        let tally = &mut tallies.entry(&m.sender).or_default()[slot];
+        tally.0 += 1;
+        tally.1 += extract_emojis(&m.text).len() as u32;
+    }
+
+    tallies
+        .into_iter()
+        .filter(|(_, t)| t.iter().all(|(n, _)| *n >= EMOJI_TREND_MIN_MESSAGES))
+        .map(|(name, [early, late])| {
+            let early_rate = early.1 as f32 / early.0 as f32;
+            let late_rate = late.1 as f32 / late.0 as f32;
+            PersonEmojiTrend {
+                name: name.to_string(),
+                early_rate,
+                late_rate,
+                direction: emoji_trend_direction(early_rate, late_rate),
+            }
+        })
+        .collect()
+}
+
+/// Question and exclamation marks per month for the whole chat and per person (by
+/// name). Months without either mark are left out.
+pub(crate) fn punctuation_energy(
+    messages: &[Message],
+) -> (Vec<PunctuationMonth>, Vec<PersonPunctuation>) {
+    let mut chat: BTreeMap<String, (u32, u32)> = BTreeMap::new();
+    let mut people: BTreeMap<&str, BTreeMap<String, (u32, u32)>> = BTreeMap::new();
+    for m in messages {
+        let (questions, exclamations) = punctuation_counts(&m.text);
+        if questions + exclamations == 0 {
+            continue;
+        }
+        let month = format!("{:04}-{:02}", m.dt.year(), m.dt.month());
+        let person = people.entry(&m.sender).or_default();
+        for tally in [
+            chat.entry(month.clone()).or_default(),
+            person.entry(month).or_default(),
+        ] {
+            tally.0 += questions;
+            tally.1 += exclamations;
+        }
+    }
+
+    let months = |tallies: BTreeMap<String, (u32, u32)>| {
+        tallies
+            .into_iter()
+            .map(|(month, (questions, exclamations))| {
+                punctuation_month(month, questions, exclamations)
+            })
+            .collect()
+    };
+    let by_person = people
+        .into_iter()
+        .map(|(name, tallies)| PersonPunctuation {
+            name: name.to_string(),
+            monthly: months(tallies),
+        })
+        .collect();
+    (months(chat), by_person)
+}
+
+pub(crate) fn punctuation_month(
+    month: String,
+    questions: u32,
+    exclamations: u32,
+) -> PunctuationMonth {
+    PunctuationMonth {
+        month,
+        questions,
+        exclamations,
+        exclamations_per_question: (questions > 0).then(|| exclamations as f32 / questions as f32),
+    }
+}
+
+/// Total hits per `(theme, words)` cluster, in the given order. Words match like tracked
+/// keywords: whole words or phrases, case-insensitive, stopwords included.
+pub(crate) fn theme_counts(messages: &[Message], clusters: &[(String, Vec<String>)]) -> Vec<Count> {
+    if clusters.is_empty() {
+        return Vec::new();
+    }
+    // One pass over the messages for every word of every theme.
+    let words: Vec<String> = clusters
+        .iter()
+        .flat_map(|(_, w)| w.iter().cloned())
+        .collect();
+    let hits: HashMap<String, u32> = keyword_counts(messages, &words)
+        .into_iter()
+        .map(|c| (c.label, c.value))
+        .collect();
+
+    clusters
+        .iter()
+        .map(|(theme, words)| {
+            let unique: HashSet<&str> = words.iter().map(|w| w.trim()).collect();
+            Count {
+                label: theme.clone(),
+                value: unique.iter().filter_map(|w| hits.get(*w)).sum(),
+            }
+        })
+        .collect()
+}
+
+/// Runs of at least this many identical consecutive messages from one sender are spam.
+pub(crate) const SPAM_RUN_MIN: usize = 20;
+const SPAM_PREVIEW_CHARS: usize = 40;
+
+/// Finds runs of identical consecutive messages from the same sender and returns the
+/// time-sorted messages with each run collapsed to its first message, plus the runs
+/// themselves. Only phrase and journey inputs use the collapsed view; plain counts
+/// keep every message.
+pub(crate) fn collapse_spam_runs(messages: &[Message]) -> (Vec<Message>, Vec<SpamRun>) {
+    let sorted = sorted_view(messages);
+
+    let mut collapsed = Vec::with_capacity(sorted.len());
+    let mut runs = Vec::new();
+    let mut i = 0usize;
+    while i < sorted.len() {
+        let head = &sorted[i];
+        let text = head.text.trim();
+        let mut j = i + 1;
+        while j < sorted.len() && sorted[j].sender == head.sender && sorted[j].text.trim() == text {
+            j += 1;
+        }
+
+        let run_len = j - i;
+        if run_len >= SPAM_RUN_MIN {
+            runs.push(SpamRun {
+                sender: head.sender.clone(),
+                text_preview: truncate_chars(text, SPAM_PREVIEW_CHARS),
+                count: run_len as u32,
+                date: head.dt.format("%Y-%m-%d").to_string(),
+            });
+            collapsed.push(head.clone());
+        } else {
+            collapsed.extend_from_slice(&sorted[i..j]);
+        }
+        i = j;
+    }
+    (collapsed, runs)
+}
+
+/// Time-sorted messages with consecutive parts from the same sender and the same second
+/// joined (newline-separated) into one message: how very long messages sometimes export.
+/// Only exports whose timestamps carry seconds are joined, judged by any message having
+/// a non-zero second; in minute-precision exports (Android) a shared timestamp is just a
+/// burst of separate messages.
+pub(crate) fn join_split_parts(messages: &[Message]) -> Vec<Message> {
+    let sorted = sorted_view(messages).into_owned();
+    if sorted.iter().all(|m| m.dt.second() == 0) {
+        return sorted;
+    }
+
+    let mut joined: Vec<Message> = Vec::with_capacity(sorted.len());
+    for m in sorted {
+        match joined.last_mut() {
+            Some(prev) if prev.sender == m.sender && prev.dt == m.dt => {
+                prev.text.push('\n');
+                prev.text.push_str(&m.text);
+            }
+            _ => joined.push(m),
+        }
+    }
+    joined
+}
+
+const LONG_MESSAGE_PREVIEW_CHARS: usize = 80;
+
+/// Messages over `max_tokens` tokens, in chat order. These are the pastes whose
+/// phrase contribution is capped.
+pub(crate) fn long_messages(messages: &[Message], max_tokens: usize) -> Vec<LongMessage> {
+    let stop = stopwords_set();
+    messages
+        .iter()
+        .filter(|m| !is_media_omitted_message(&m.text))
+        .filter_map(|m| {
+            let token_count = tokenize(&m.text, false, stop).len();
+            (token_count > max_tokens).then(|| LongMessage {
+                sender: m.sender.clone(),
+                date: m.dt.format("%Y-%m-%d").to_string(),
+                token_count: token_count as u32,
+                text_preview: truncate_chars(&m.text, LONG_MESSAGE_PREVIEW_CHARS),
+            })
+        })
+        .collect()
+}
+
+/// Tallies poll messages (see `is_poll_message` for the format). Vote counts are the
+/// final ones in the export, so `total_votes` is votes cast rather than messages.
+pub(crate) fn poll_stats(messages: &[Message]) -> PollStats {
+    let polls: Vec<Poll> = messages
+        .iter()
+        .filter_map(|m| {
+            let (question, options) = parse_poll(&m.text)?;
+            Some(Poll {
+                sender: m.sender.clone(),
+                date: m.dt.format("%Y-%m-%d").to_string(),
+                question,
+                options: options
+                    .into_iter()
+                    .map(|(label, value)| Count { label, value })
+                    .collect(),
+            })
+        })
+        .collect();
+    let mut by_sender: HashMap<String, u32> = HashMap::new();
+    for poll in &polls {
+        *by_sender.entry(poll.sender.clone()).or_insert(0) += 1;
+    }
+    let mut by_sender: Vec<Count> = by_sender
+        .into_iter()
+        .map(|(label, value)| Count { label, value })
+        .collect();
+    by_sender.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
+    PollStats {
+        total_polls: polls.len() as u32,
+        total_votes: polls
+            .iter()
+            .flat_map(|p| p.options.iter().map(|o| o.value))
+            .sum(),
+        by_sender,
+        polls,
+    }
+}
+
 pub(crate) fn count_by_sender(messages: &[Message]) -> Vec<Count> {
     let mut map = HashMap::new();
     for m in messages {
@@ -67,7 +731,7 @@ pub(crate) fn count_by_sender(messages: &[Message]) -> Vec<Count> {
         .into_iter()
         .map(|(label, value)| Count { label, value })
         .collect();
-    items.sort_by_key(|c| std::cmp::Reverse(c.value));
+    items.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
     items
 }
 
@@ -86,6 +750,15 @@ pub(crate) fn daily_counts(messages: &[Message]) -> Vec<Count> {
 }
 
 pub fn longest_streak(daily: &[Count]) -> Option<(u32, String, String)> {
+    longest_streak_ignoring(daily, &[])
+}
+
+/// `longest_streak` where a run of silent days falling entirely inside one of the
+/// inclusive `ignore` ranges doesn't end the streak. Only active days are counted.
+pub(crate) fn longest_streak_ignoring(
+    daily: &[Count],
+    ignore: &[(NaiveDate, NaiveDate)],
+) -> Option<(u32, String, String)> {
     if daily.is_empty() {
         return None;
     }
@@ -101,7 +774,7 @@ pub fn longest_streak(daily: &[Count]) -> Option<(u32, String, String)> {
         let prev = parse_day(&sorted[i - 1].label);
         let curr = parse_day(&sorted[i].label);
         if let (Some(p), Some(c)) = (prev, curr) {
-            if c - p == chrono::Duration::days(1) {
+            if c - p == chrono::Duration::days(1) || gap_is_ignored(p, c, ignore) {
                 current_streak += 1;
                 if current_streak > max_streak {
                     max_streak = current_streak;
@@ -123,12 +796,35 @@ pub fn longest_streak(daily: &[Count]) -> Option<(u32, String, String)> {
     Some((max_streak, start, end))
 }
 
+/// The chat-wide `longest_streak_ignoring` as a `DailyStreak`.
+pub(crate) fn daily_streak(
+    daily: &[Count],
+    ignore: &[(NaiveDate, NaiveDate)],
+) -> Option<DailyStreak> {
+    longest_streak_ignoring(daily, ignore).map(|(days, start, end)| DailyStreak {
+        days,
+        start,
+        end,
+    })
+}
+
+/// Whether every day strictly between `prev` and `next` is in an ignored range.
+fn gap_is_ignored(prev: NaiveDate, next: NaiveDate, ignore: &[(NaiveDate, NaiveDate)]) -> bool {
+    !ignore.is_empty()
+        && prev.iter_days().skip(1).take_while(|d| *d < next).all(|d| {
+            ignore
+                .iter()
+                .any(|(start, end)| (*start..=*end).contains(&d))
+        })
+}
+
 pub fn longest_streak_from_raw(raw: &str) -> Option<(u32, String, String)> {
     let mut map: BTreeMap<NaiveDate, u32> = BTreeMap::new();
-    for line in raw.lines() {
+    for line in raw.lines().map(|l| l.trim_end_matches('\r')) {
         if let Some(caps) = re_bracket_pattern()
             .captures(line)
             .or_else(|| re_hyphen_pattern().captures(line))
+            .or_else(|| re_senderless_pattern().captures(line))
         {
             let date = caps.name("date").map(|m| m.as_str()).unwrap_or("");
             let time = caps.name("time").map(|m| m.as_str()).unwrap_or("");
@@ -152,6 +848,47 @@ pub fn longest_streak_from_raw(raw: &str) -> Option<(u32, String, String)> {
     longest_streak(&daily)
 }
 
+/// The sender names of `by_sender`, sorted by name.
+pub(crate) fn participants(by_sender: &[Count]) -> Vec<String> {
+    let mut names: Vec<String> = by_sender.iter().map(|c| c.label.clone()).collect();
+    names.sort();
+    names
+}
+
+/// Percent of all messages sent by each sender, keeping the order of `by_sender`.
+pub(crate) fn share_of_speech(by_sender: &[Count]) -> Vec<FloatCount> {
+    let total: u32 = by_sender.iter().map(|c| c.value).sum();
+    by_sender
+        .iter()
+        .map(|c| FloatCount {
+            label: c.label.clone(),
+            value: if total == 0 {
+                0.0
+            } else {
+                c.value as f32 * 100.0 / total as f32
+            },
+        })
+        .collect()
+}
+
+/// Share of messages above which a two-person chat counts as one-sided.
+const IMBALANCE_PERCENT: f32 = 70.0;
+
+/// Flags a DM where one person sends more than `IMBALANCE_PERCENT` of the messages.
+/// Group chats are expected to be uneven, so they never get the flag.
+pub(crate) fn imbalance(share_of_speech: &[FloatCount]) -> Option<ImbalanceInfo> {
+    if share_of_speech.len() != 2 {
+        return None;
+    }
+    share_of_speech
+        .iter()
+        .find(|s| s.value > IMBALANCE_PERCENT)
+        .map(|s| ImbalanceInfo {
+            dominant: s.label.clone(),
+            percent: s.value,
+        })
+}
+
 pub(crate) fn hourly_counts(messages: &[Message]) -> Vec<HourCount> {
     let mut map = [0u32; 24];
     for m in messages {
@@ -169,51 +906,613 @@ pub(crate) fn hourly_counts(messages: &[Message]) -> Vec<HourCount> {
         .collect()
 }
 
-pub(crate) fn weekly_counts(messages: &[Message]) -> Vec<Count> {
-    let mut map = [0u32; 7];
+/// Share of active days an hour must be used on to count as a routine.
+const ROUTINE_MIN_COVERAGE: f32 = 0.6;
+/// Fewer active days than this is too little history to call anything a routine.
+const ROUTINE_MIN_ACTIVE_DAYS: u32 = 7;
+
+/// Median time of day of each day's first message, per month. Every active day counts,
+/// including days whose only message came late at night.
+pub(crate) fn first_message_hour_by_month(messages: &[Message]) -> Vec<MonthHour> {
+    monthly_first_hours(first_message_times(messages.iter()))
+}
+
+/// `first_message_hour_by_month` per sender, from each person's own first message of
+/// the day. Sorted by name.
+pub(crate) fn first_message_hour_by_person(messages: &[Message]) -> Vec<PersonMonthHours> {
+    let mut by_sender: BTreeMap<&str, Vec<&Message>> = BTreeMap::new();
     for m in messages {
-        let idx = weekday_index(m.dt.weekday());
-        map[idx] += 1;
+        by_sender.entry(m.sender.as_str()).or_default().push(m);
     }
-    map.iter()
-        .enumerate()
-        .map(|(i, value)| Count {
-            label: weekday_label(i),
-            value: *value,
+    by_sender
+        .into_iter()
+        .map(|(name, messages)| PersonMonthHours {
+            name: name.to_string(),
+            monthly: monthly_first_hours(first_message_times(messages.into_iter())),
         })
         .collect()
 }
 
-pub(crate) fn monthly_counts(messages: &[Message]) -> Vec<Count> {
-    let mut map: BTreeMap<String, u32> = BTreeMap::new();
+/// Earliest message time per day; input order does not matter.
+fn first_message_times<'a>(
+    messages: impl Iterator<Item = &'a Message>,
+) -> BTreeMap<NaiveDate, NaiveTime> {
+    let mut firsts: BTreeMap<NaiveDate, NaiveTime> = BTreeMap::new();
     for m in messages {
-        let label = format!("{:04}-{:02}", m.dt.year(), m.dt.month());
-        *map.entry(label).or_insert(0) += 1;
+        let time = m.dt.time();
+        firsts
+            .entry(m.dt.date())
+            .and_modify(|t| *t = (*t).min(time))
+            .or_insert(time);
     }
-    map.into_iter()
-        .map(|(label, value)| Count { label, value })
+    firsts
+}
+
+fn monthly_first_hours(firsts: BTreeMap<NaiveDate, NaiveTime>) -> Vec<MonthHour> {
+    let mut months: BTreeMap<String, Vec<f32>> = BTreeMap::new();
+    for (day, time) in firsts {
+        months
+            .entry(format!("{:04}-{:02}", day.year(), day.month()))
+            .or_default()
+            .push(time.num_seconds_from_midnight() as f32 / 60.0);
+    }
+    months
+        .into_iter()
+        .map(|(month, mut minutes)| MonthHour {
+            month,
+            days: minutes.len() as u32,
+            median_hour: median(&mut minutes) / 60.0,
+        })
         .collect()
 }
 
-pub(crate) fn deleted_counts(messages: &[Message]) -> (u32, u32) {
-    let mut you = 0u32;
-    let mut others = 0u32;
-    for text in messages.iter().map(|m| m.text.as_str()) {
-        if text == "You deleted this message" {
-            you += 1;
-        } else if text == "This message was deleted" {
-            others += 1;
+/// Tokens sampled per month for language detection; enough to be confident, small enough
+/// that a busy month costs no more than a quiet one.
+const LANGUAGE_SAMPLE_TOKENS: usize = 2000;
+
+/// Months with fewer sampled tokens than this report `"unknown"`.
+const LANGUAGE_MIN_TOKENS: usize = 50;
+
+/// Dominant language per month, chronologically, judged from the first
+/// `LANGUAGE_SAMPLE_TOKENS` tokens of each month.
+pub(crate) fn language_timeline(messages: &[Message]) -> Vec<LanguageMonth> {
+    let stop = stopwords_set();
+    let mut samples: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for m in messages {
+        if is_placeholder_message(&m.text) {
+            continue;
+        }
+        let sample = samples
+            .entry(format!("{:04}-{:02}", m.dt.year(), m.dt.month()))
+            .or_default();
+        if sample.len() >= LANGUAGE_SAMPLE_TOKENS {
+            continue;
         }
+        let room = LANGUAGE_SAMPLE_TOKENS - sample.len();
+        sample.extend(
+            tokenize(&m.text, false, stop)
+                .into_iter()
+                .map(|t| t.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
+                .filter(|t| !t.is_empty())
+                .take(room),
+        );
     }
-    (you, others)
+    samples
+        .into_iter()
+        .map(|(month, sample)| {
+            let detected = (sample.len() >= LANGUAGE_MIN_TOKENS)
+                .then(|| detect_language(sample.iter().map(String::as_str)))
+                .flatten();
+            let (top_language, share) = detected.unwrap_or(("unknown", 0.0));
+            LanguageMonth {
+                month,
+                top_language: top_language.to_string(),
+                share,
+            }
+        })
+        .collect()
 }
 
-pub(crate) fn timeline(messages: &[Message]) -> Vec<Count> {
-    if messages.is_empty() {
-        return Vec::new();
+/// Hours of the day the chat reliably comes alive ("almost every day around 11 PM"):
+/// hours with a message on at least `ROUTINE_MIN_COVERAGE` of active days, most
+/// consistent first.
+pub(crate) fn routine_detection(messages: &[Message]) -> Vec<Routine> {
+    let mut active_days: HashSet<NaiveDate> = HashSet::new();
+    let mut hour_days: [HashSet<NaiveDate>; 24] = std::array::from_fn(|_| HashSet::new());
+    for m in messages {
+        let day = m.dt.date();
+        active_days.insert(day);
+        if let Some(days) = hour_days.get_mut(m.dt.hour() as usize) {
+            days.insert(day);
+        }
     }
-    let mut sorted = messages.to_vec();
-    sorted.sort_by_key(|m| m.dt);
+    let hour_day_counts: [u32; 24] = std::array::from_fn(|h| hour_days[h].len() as u32);
+    routines_from_counts(&hour_day_counts, active_days.len() as u32)
+}
+
+/// Routines from per-hour active-day counts; shared with `Summary::merge`.
+pub(crate) fn routines_from_counts(hour_days: &[u32; 24], active_days: u32) -> Vec<Routine> {
+    if active_days < ROUTINE_MIN_ACTIVE_DAYS {
+        return Vec::new();
+    }
+    let mut routines: Vec<Routine> = hour_days
+        .iter()
+        .enumerate()
+        .map(|(hour, &days)| Routine {
+            hour: hour as u32,
+            days,
+            active_days,
+            coverage: days as f32 / active_days as f32,
+        })
+        .filter(|r| r.coverage >= ROUTINE_MIN_COVERAGE)
+        .collect();
+    routines.sort_by(|a, b| {
+        b.coverage
+            .partial_cmp(&a.coverage)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.hour.cmp(&b.hour))
+    });
+    routines
+}
+
+/// Relative change across the whole chat (slope * days / mean) beyond which the volume
+/// counts as growing or declining.
+const TREND_RELATIVE_CHANGE: f32 = 0.2;
+
+/// Least-squares slope of the gap-filled daily series, in messages/day per day.
+pub(crate) fn volume_trend_from_timeline(daily: &[Count]) -> Trend {
+    let n = daily.len() as f32;
+    if daily.len() < 2 {
+        return Trend {
+            slope: 0.0,
+            direction: TrendDirection::Stable,
+        };
+    }
+
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = daily.iter().map(|c| c.value as f32).sum::<f32>() / n;
+    let (mut cov, mut var) = (0.0f32, 0.0f32);
+    for (i, c) in daily.iter().enumerate() {
+        let dx = i as f32 - mean_x;
+        cov += dx * (c.value as f32 - mean_y);
+        var += dx * dx;
+    }
+    let slope = cov / var;
+
+    let relative = slope * (n - 1.0) / mean_y;
+    let direction = if relative > TREND_RELATIVE_CHANGE {
+        TrendDirection::Growing
+    } else if relative < -TREND_RELATIVE_CHANGE {
+        TrendDirection::Declining
+    } else {
+        TrendDirection::Stable
+    };
+    Trend { slope, direction }
+}
+
+/// Messages per ISO calendar week ("2023-W05"), with empty weeks filled in as zero.
+pub(crate) fn iso_weekly_counts(messages: &[Message]) -> Vec<Count> {
+    iso_weekly_from_daily(&daily_counts(messages))
+}
+
+/// Rolls `"YYYY-MM-DD"` daily counts up into gap-filled ISO weeks.
+pub(crate) fn iso_weekly_from_daily(daily: &[Count]) -> Vec<Count> {
+    let week_start =
+        |d: NaiveDate| d - chrono::Duration::days(d.weekday().num_days_from_monday() as i64);
+    let days: Vec<(NaiveDate, u32)> = daily
+        .iter()
+        .filter_map(|c| {
+            NaiveDate::parse_from_str(&c.label, "%Y-%m-%d")
+                .ok()
+                .map(|d| (d, c.value))
+        })
+        .collect();
+    let (Some(first), Some(last)) = (
+        days.iter().map(|(d, _)| *d).min(),
+        days.iter().map(|(d, _)| *d).max(),
+    ) else {
+        return Vec::new();
+    };
+
+    let mut map: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    let mut cursor = week_start(first);
+    while cursor <= last {
+        map.insert(cursor, 0);
+        match cursor.checked_add_days(chrono::Days::new(7)) {
+            Some(next) => cursor = next,
+            None => break,
+        }
+    }
+    for (d, value) in days {
+        if let Some(v) = map.get_mut(&week_start(d)) {
+            *v += value;
+        }
+    }
+
+    map.into_iter()
+        .map(|(monday, value)| {
+            let week = monday.iso_week();
+            Count {
+                label: format!("{:04}-W{:02}", week.year(), week.week()),
+                value,
+            }
+        })
+        .collect()
+}
+
+/// Longest run of consecutive ISO weeks with at least `threshold` messages; the
+/// earliest run wins ties.
+pub(crate) fn weekly_streak(messages: &[Message], threshold: u32) -> Option<WeeklyStreak> {
+    weekly_streak_over(&iso_weekly_counts(messages), threshold)
+}
+
+pub(crate) fn weekly_streak_over(weeks: &[Count], threshold: u32) -> Option<WeeklyStreak> {
+    let mut best: Option<(usize, usize)> = None;
+    let mut run_start: Option<usize> = None;
+    for (i, week) in weeks.iter().enumerate() {
+        if week.value < threshold {
+            run_start = None;
+            continue;
+        }
+        let start = *run_start.get_or_insert(i);
+        if best.is_none_or(|(s, e)| i - start > e - s) {
+            best = Some((start, i));
+        }
+    }
+
+    best.map(|(start, end)| WeeklyStreak {
+        weeks: (end - start + 1) as u32,
+        start_week: weeks[start].label.clone(),
+        end_week: weeks[end].label.clone(),
+        threshold,
+    })
+}
+
+pub(crate) fn weekly_counts(messages: &[Message]) -> Vec<Count> {
+    let mut map = [0u32; 7];
+    for m in messages {
+        let idx = weekday_index(m.dt.weekday());
+        map[idx] += 1;
+    }
+    map.iter()
+        .enumerate()
+        .map(|(i, value)| Count {
+            label: weekday_label(i),
+            value: *value,
+        })
+        .collect()
+}
+
+pub(crate) fn monthly_counts(messages: &[Message]) -> Vec<Count> {
+    let mut map: BTreeMap<String, u32> = BTreeMap::new();
+    for m in messages {
+        let label = format!("{:04}-{:02}", m.dt.year(), m.dt.month());
+        *map.entry(label).or_insert(0) += 1;
+    }
+    map.into_iter()
+        .map(|(label, value)| Count { label, value })
+        .collect()
+}
+
+/// `(busiest, quietest)` of the chronological `monthly` counts. Months without messages
+/// are ignored, and the earlier month wins a tie either way.
+pub(crate) fn busiest_and_quietest_month(monthly: &[Count]) -> (Option<Count>, Option<Count>) {
+    let active = monthly.iter().filter(|c| c.value > 0);
+    let busiest = active
+        .clone()
+        .min_by(|a, b| b.value.cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
+    let quietest = active.min_by(|a, b| a.value.cmp(&b.value).then_with(|| a.label.cmp(&b.label)));
+    (busiest.cloned(), quietest.cloned())
+}
+
+/// Scored messages both months need before their sentiment means are compared.
+const SENTIMENT_SWING_MIN_MESSAGES: u32 = 50;
+
+/// The biggest month-over-month volume rise and drop in `monthly` (months without
+/// messages count as zero) and the biggest change in mean sentiment between
+/// neighbouring months that each have `SENTIMENT_SWING_MIN_MESSAGES` scored messages.
+/// The first month has nothing to compare with; the earlier month wins ties.
+pub(crate) fn month_swings(
+    monthly: &[Count],
+    sentiment_by_day: &[SentimentDay],
+) -> Vec<MonthSwing> {
+    let month_of = |label: &str| parse_month_label(label).map(|(y, m)| y * 12 + m as i32 - 1);
+    let label_of = |idx: i32| format!("{:04}-{:02}", idx.div_euclid(12), idx.rem_euclid(12) + 1);
+
+    let volumes: BTreeMap<i32, u32> = monthly
+        .iter()
+        .filter_map(|c| month_of(&c.label).map(|m| (m, c.value)))
+        .collect();
+    let mut sentiment: BTreeMap<i32, (f64, u32)> = BTreeMap::new();
+    for d in sentiment_by_day {
+        let Some(month) = d.day.get(..7).and_then(month_of) else {
+            continue;
+        };
+        let n = d.pos + d.neu + d.neg;
+        let entry = sentiment.entry(month).or_default();
+        entry.0 += d.mean as f64 * n as f64;
+        entry.1 += n;
+    }
+
+    let mut rise: Option<MonthSwing> = None;
+    let mut drop: Option<MonthSwing> = None;
+    if let (Some(&first), Some(&last)) = (volumes.keys().next(), volumes.keys().next_back()) {
+        for month in first + 1..=last {
+            let prev = volumes.get(&(month - 1)).copied().unwrap_or(0) as f32;
+            let curr = volumes.get(&month).copied().unwrap_or(0) as f32;
+            let swing = MonthSwing {
+                kind: SwingKind::VolumeRise,
+                month: label_of(month),
+                prev,
+                curr,
+                delta: curr - prev,
+                percent: (prev > 0.0).then(|| (curr - prev) / prev * 100.0),
+            };
+            if swing.delta > 0.0 && rise.as_ref().is_none_or(|r| swing.delta > r.delta) {
+                rise = Some(swing);
+            } else if swing.delta < 0.0 && drop.as_ref().is_none_or(|d| swing.delta < d.delta) {
+                drop = Some(MonthSwing {
+                    kind: SwingKind::VolumeDrop,
+                    ..swing
+                });
+            }
+        }
+    }
+
+    let mean_of = |month: i32| {
+        sentiment
+            .get(&month)
+            .filter(|(_, n)| *n >= SENTIMENT_SWING_MIN_MESSAGES)
+            .map(|(sum, n)| (sum / *n as f64) as f32)
+    };
+    let mut mood: Option<MonthSwing> = None;
+    for &month in sentiment.keys() {
+        let (Some(prev), Some(curr)) = (mean_of(month - 1), mean_of(month)) else {
+            continue;
+        };
+        let delta = curr - prev;
+        if mood.as_ref().is_none_or(|s| delta.abs() > s.delta.abs()) {
+            mood = Some(MonthSwing {
+                kind: SwingKind::SentimentSwing,
+                month: label_of(month),
+                prev,
+                curr,
+                delta,
+                percent: None,
+            });
+        }
+    }
+
+    [rise, drop, mood].into_iter().flatten().collect()
+}
+
+/// Rolls "YYYY-MM" monthly counts up into "YYYY-Qn" calendar quarters.
+pub(crate) fn quarterly_counts(monthly: &[Count]) -> Vec<Count> {
+    let mut map: BTreeMap<String, u32> = BTreeMap::new();
+    for c in monthly {
+        let Some((year, month)) = parse_month_label(&c.label) else {
+            continue;
+        };
+        let label = format!("{:04}-Q{}", year, (month - 1) / 3 + 1);
+        *map.entry(label).or_insert(0) += c.value;
+    }
+    map.into_iter()
+        .map(|(label, value)| Count { label, value })
+        .collect()
+}
+
+/// Messages per meteorological season summed across all years, ordered
+/// `[spring, summer, autumn, winter]`. The southern hemisphere shifts every season by
+/// six months (December is summer there).
+pub(crate) fn seasonal_profile(monthly: &[Count], hemisphere: Hemisphere) -> [u32; 4] {
+    let mut seasons = [0u32; 4];
+    for c in monthly {
+        let Some((_, month)) = parse_month_label(&c.label) else {
+            continue;
+        };
+        // Mar-May -> 0 (spring), Jun-Aug -> 1, Sep-Nov -> 2, Dec-Feb -> 3.
+        let northern = ((month + 9) % 12 / 3) as usize;
+        let idx = match hemisphere {
+            Hemisphere::Northern => northern,
+            Hemisphere::Southern => (northern + 2) % 4,
+        };
+        seasons[idx] += c.value;
+    }
+    seasons
+}
+
+fn parse_month_label(label: &str) -> Option<(i32, u32)> {
+    let (year, month) = label.split_once('-')?;
+    let month = month.parse::<u32>().ok().filter(|m| (1..=12).contains(m))?;
+    Some((year.parse().ok()?, month))
+}
+
+pub(crate) fn deleted_counts(messages: &[Message]) -> (u32, u32) {
+    let mut you = 0u32;
+    let mut others = 0u32;
+    for text in messages.iter().map(|m| m.text.as_str()) {
+        if text == "You deleted this message" {
+            you += 1;
+        } else if text == "This message was deleted" {
+            others += 1;
+        }
+    }
+    (you, others)
+}
+
+pub(crate) fn voice_notes(messages: &[Message]) -> VoiceNotes {
+    let mut summary = VoiceNotes::default();
+    let mut total_seconds = 0u64;
+    let mut per_person: HashMap<&str, (u32, u64)> = HashMap::new();
+    let mut longest: Option<(&Message, u32)> = None;
+
+    for m in messages {
+        if !is_voice_note(&m.text) {
+            continue;
+        }
+        summary.count += 1;
+        let entry = per_person.entry(m.sender.as_str()).or_insert((0, 0));
+        entry.0 += 1;
+
+        let Some(seconds) = voice_note_seconds(&m.text) else {
+            summary.unknown_duration += 1;
+            continue;
+        };
+        total_seconds += seconds as u64;
+        entry.1 += seconds as u64;
+        // Strictly greater keeps the earliest note on ties.
+        if longest.is_none_or(|(_, best)| seconds > best) {
+            longest = Some((m, seconds));
+        }
+    }
+
+    summary.total_minutes = total_seconds as f32 / 60.0;
+    let mut people: Vec<PersonVoiceNotes> = per_person
+        .into_iter()
+        .map(|(name, (count, seconds))| PersonVoiceNotes {
+            name: name.to_string(),
+            count,
+            minutes: seconds as f32 / 60.0,
+        })
+        .collect();
+    people.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    summary.per_person = people;
+    summary.longest = longest.map(|(m, seconds)| LongestVoiceNote {
+        sender: m.sender.clone(),
+        date: m.dt.format("%Y-%m-%d").to_string(),
+        seconds,
+    });
+    summary
+}
+
+/// Days need at least this many celebration messages of one kind to be annotated.
+pub(crate) const CELEBRATION_MIN_MESSAGES: u32 = 5;
+
+/// Flags days where congratulation keywords spike, tagged with the dominant category.
+pub(crate) fn timeline_annotations(messages: &[Message]) -> Vec<TimelineAnnotation> {
+    let mut per_day: BTreeMap<NaiveDate, BTreeMap<&'static str, u32>> = BTreeMap::new();
+    for m in messages {
+        if let Some(kind) = celebration_category(&m.text) {
+            *per_day
+                .entry(m.dt.date())
+                .or_default()
+                .entry(kind)
+                .or_insert(0) += 1;
+        }
+    }
+
+    per_day
+        .into_iter()
+        .filter_map(|(date, kinds)| {
+            let (kind, evidence_count) =
+                kinds
+                    .into_iter()
+                    .reduce(|best, k| if k.1 > best.1 { k } else { best })?;
+            (evidence_count >= CELEBRATION_MIN_MESSAGES).then(|| TimelineAnnotation {
+                date: date.format("%Y-%m-%d").to_string(),
+                kind: kind.to_string(),
+                evidence_count,
+            })
+        })
+        .collect()
+}
+
+/// Gap-fills sorted `"YYYY-MM-DD"` daily counts into a continuous timeline.
+pub(crate) fn timeline_from_daily(daily: &[Count]) -> Vec<Count> {
+    let mut map: BTreeMap<NaiveDate, u32> = daily
+        .iter()
+        .filter_map(|c| {
+            NaiveDate::parse_from_str(&c.label, "%Y-%m-%d")
+                .ok()
+                .map(|d| (d, c.value))
+        })
+        .collect();
+    let (Some(&start), Some(&end)) = (map.keys().next(), map.keys().next_back()) else {
+        return Vec::new();
+    };
+    let mut cursor = start;
+    while cursor < end {
+        match cursor.succ_opt() {
+            Some(next) => cursor = next,
+            None => break,
+        }
+        map.entry(cursor).or_insert(0);
+    }
+    map.into_iter()
+        .map(|(d, value)| Count {
+            label: d.format("%Y-%m-%d").to_string(),
+            value,
+        })
+        .collect()
+}
+
+/// Longest span, in days, still charted day by day under `auto` granularity.
+const AUTO_DAY_MAX_SPAN: i64 = 400;
+/// Longest span still charted week by week; anything longer goes monthly.
+const AUTO_WEEK_MAX_SPAN: i64 = 3 * 365;
+
+/// Resolves the requested granularity against a gap-filled daily timeline.
+pub(crate) fn timeline_unit(daily: &[Count], granularity: TimelineGranularity) -> TimelineUnit {
+    match granularity {
+        TimelineGranularity::Day => TimelineUnit::Day,
+        TimelineGranularity::Week => TimelineUnit::Week,
+        TimelineGranularity::Month => TimelineUnit::Month,
+        TimelineGranularity::Auto => {
+            // The daily timeline has one entry per calendar day in the span.
+            let span = daily.len() as i64;
+            if span <= AUTO_DAY_MAX_SPAN {
+                TimelineUnit::Day
+            } else if span <= AUTO_WEEK_MAX_SPAN {
+                TimelineUnit::Week
+            } else {
+                TimelineUnit::Month
+            }
+        }
+    }
+}
+
+/// Re-buckets a gap-filled daily timeline; weeks and months are gap-filled too.
+pub(crate) fn timeline_at(daily: Vec<Count>, unit: TimelineUnit) -> Vec<Count> {
+    match unit {
+        TimelineUnit::Day => daily,
+        TimelineUnit::Week => iso_weekly_from_daily(&daily),
+        TimelineUnit::Month => monthly_from_daily(&daily),
+    }
+}
+
+/// Rolls `"YYYY-MM-DD"` daily counts up into gap-filled `"YYYY-MM"` months.
+fn monthly_from_daily(daily: &[Count]) -> Vec<Count> {
+    let mut map: BTreeMap<(i32, u32), u32> = BTreeMap::new();
+    for c in daily {
+        if let Ok(d) = NaiveDate::parse_from_str(&c.label, "%Y-%m-%d") {
+            *map.entry((d.year(), d.month())).or_insert(0) += c.value;
+        }
+    }
+    let (Some(&first), Some(&last)) = (map.keys().next(), map.keys().next_back()) else {
+        return Vec::new();
+    };
+    let mut cursor = first;
+    while cursor < last {
+        cursor = if cursor.1 == 12 {
+            (cursor.0 + 1, 1)
+        } else {
+            (cursor.0, cursor.1 + 1)
+        };
+        map.entry(cursor).or_insert(0);
+    }
+    map.into_iter()
+        .map(|((year, month), value)| Count {
+            label: format!("{year:04}-{month:02}"),
+            value,
+        })
+        .collect()
+}
+
+pub(crate) fn timeline(messages: &[Message]) -> Vec<Count> {
+    if messages.is_empty() {
+        return Vec::new();
+    }
+    let sorted = sorted_view(messages);
     // Graceful handling: `sorted` is non-empty (checked above), but avoid unwrap so a
     // future refactor can never turn malformed input into a panic under panic=abort.
     let (Some(first), Some(last)) = (sorted.first(), sorted.last()) else {
@@ -233,7 +1532,7 @@ pub(crate) fn timeline(messages: &[Message]) -> Vec<Count> {
             None => break,
         }
     }
-    for m in sorted {
+    for m in sorted.iter() {
         let d = m.dt.date();
         if let Some(v) = map.get_mut(&d) {
             *v += 1;
@@ -274,11 +1573,59 @@ pub(crate) fn buckets_by_person(messages: &[Message]) -> Vec<PersonBuckets> {
         });
     }
 
-    buckets.sort_by_key(|b| std::cmp::Reverse(b.messages as u32));
+    buckets.sort_by(|a, b| {
+        b.messages
+            .cmp(&a.messages)
+            .then_with(|| a.name.cmp(&b.name))
+    });
     buckets
 }
 
-pub(crate) fn per_person_daily(messages: &[Message]) -> Vec<PersonDaily> {
+/// Per-weekday leaders and shares from the weekday buckets. A tie for first place
+/// leaves that weekday without a leader rather than picking one arbitrarily.
+pub(crate) fn weekday_leaders(
+    buckets: &[PersonBuckets],
+) -> ([Option<String>; 7], Vec<PersonWeekdayShare>) {
+    let mut totals = [0u32; 7];
+    for b in buckets {
+        for (total, n) in totals.iter_mut().zip(b.daily) {
+            *total += n;
+        }
+    }
+
+    let leaders = std::array::from_fn(|day| {
+        let mut counts: Vec<(u32, &str)> = buckets
+            .iter()
+            .map(|b| (b.daily[day], b.name.as_str()))
+            .filter(|(n, _)| *n > 0)
+            .collect();
+        counts.sort_by_key(|c| std::cmp::Reverse(c.0));
+        match counts.as_slice() {
+            [] => None,
+            [(top, _), (second, _), ..] if top == second => None,
+            [(_, name), ..] => Some(name.to_string()),
+        }
+    });
+    let shares = buckets
+        .iter()
+        .map(|b| PersonWeekdayShare {
+            name: b.name.clone(),
+            percent: std::array::from_fn(|day| {
+                if totals[day] == 0 {
+                    0.0
+                } else {
+                    b.daily[day] as f32 * 100.0 / totals[day] as f32
+                }
+            }),
+        })
+        .collect();
+    (leaders, shares)
+}
+
+pub(crate) fn per_person_daily(
+    messages: &[Message],
+    streak_ignore: &[(NaiveDate, NaiveDate)],
+) -> Vec<PersonDaily> {
     let mut grouped: HashMap<&str, BTreeMap<NaiveDate, u32>> = HashMap::new();
     for m in messages {
         grouped
@@ -291,16 +1638,19 @@ pub(crate) fn per_person_daily(messages: &[Message]) -> Vec<PersonDaily> {
 
     let mut result = Vec::with_capacity(grouped.len());
     for (name, map) in grouped.into_iter() {
-        let daily = map
+        let daily: Vec<Count> = map
             .into_iter()
             .map(|(d, value)| Count {
                 label: d.format("%Y-%m-%d").to_string(),
                 value,
             })
             .collect();
+        let longest_streak =
+            longest_streak_ignoring(&daily, streak_ignore).map_or(0, |(days, _, _)| days);
         result.push(PersonDaily {
             name: name.to_string(),
             daily,
+            longest_streak,
         });
     }
 
@@ -308,6 +1658,202 @@ pub(crate) fn per_person_daily(messages: &[Message]) -> Vec<PersonDaily> {
     result
 }
 
+/// Days either side of a special date that make up its baseline.
+const SPECIAL_DAY_WINDOW: i64 = 15;
+
+/// Per-date stats for `special_dates`: messages that day compared with the average of
+/// the `2 * SPECIAL_DAY_WINDOW` surrounding days (silent days count as 0), the day's top
+/// phrase and its mean sentiment. Dates are expected to be validated already.
+pub(crate) fn special_days(
+    messages: &[Message],
+    special_dates: &[SpecialDate],
+    max_tokens: usize,
+    emoji_weights: &EmojiWeights,
+) -> Vec<SpecialDay> {
+    if special_dates.is_empty() {
+        return Vec::new();
+    }
+    let mut per_day: HashMap<NaiveDate, u32> = HashMap::new();
+    for m in messages {
+        *per_day.entry(m.dt.date()).or_insert(0) += 1;
+    }
+
+    special_dates
+        .iter()
+        .filter_map(|special| {
+            let date = NaiveDate::parse_from_str(&special.date, "%Y-%m-%d").ok()?;
+            let that_day: Vec<Message> = messages
+                .iter()
+                .filter(|m| m.dt.date() == date)
+                .cloned()
+                .collect();
+            let baseline: u32 = (1..=SPECIAL_DAY_WINDOW)
+                .flat_map(|offset| {
+                    [
+                        date.checked_sub_days(Days::new(offset as u64)),
+                        date.checked_add_days(Days::new(offset as u64)),
+                    ]
+                })
+                .flatten()
+                .map(|d| per_day.get(&d).copied().unwrap_or(0))
+                .sum();
+            let average = baseline as f32 / (2 * SPECIAL_DAY_WINDOW) as f32;
+            let count = that_day.len() as u32;
+            let vs_average_pct = if average > 0.0 {
+                (count as f32 - average) / average * 100.0
+            } else {
+                0.0
+            };
+            let sentiment_mean = if that_day.is_empty() {
+                0.0
+            } else {
+                that_day
+                    .iter()
+                    .map(|m| sentiment_score(&m.text, emoji_weights).0)
+                    .sum::<f32>()
+                    / count as f32
+            };
+            Some(SpecialDay {
+                date: special.date.clone(),
+                label: special.label.clone(),
+                messages: count,
+                vs_average_pct,
+                top_phrase: top_phrases(&that_day, 1, true, max_tokens)
+                    .into_iter()
+                    .next()
+                    .map(|c| c.label),
+                sentiment_mean,
+            })
+        })
+        .collect()
+}
+
+/// Component weights of `relationship_score`; they sum to 1.
+const SCORE_WEIGHT_RECIPROCITY: f32 = 0.30;
+const SCORE_WEIGHT_RESPONSIVENESS: f32 = 0.25;
+const SCORE_WEIGHT_POSITIVITY: f32 = 0.25;
+const SCORE_WEIGHT_CONSISTENCY: f32 = 0.20;
+
+/// Median reply time (minutes) at which responsiveness scores 50.
+const SCORE_HALF_RESPONSE_MINUTES: f32 = 30.0;
+
+/// Combines reciprocity, responsiveness, positivity and consistency from an already
+/// computed summary into a reproducible 0-100 score. Only reads summary fields that
+/// merge exactly or near-exactly, so merged summaries recompute it the same way.
+pub(crate) fn relationship_score(summary: &Summary) -> RelationshipScore {
+    let shares: Vec<f32> = summary
+        .share_of_speech
+        .iter()
+        .map(|s| s.value / 100.0)
+        .filter(|&p| p > 0.0)
+        .collect();
+    let reciprocity = if shares.len() < 2 {
+        0.0
+    } else {
+        let entropy: f32 = shares.iter().map(|p| -p * p.ln()).sum();
+        entropy / (shares.len() as f32).ln()
+    };
+
+    let replies: u32 = summary.response_latency.iter().map(|r| r.replies).sum();
+    let responsiveness = if replies == 0 {
+        0.0
+    } else {
+        SCORE_HALF_RESPONSE_MINUTES / (SCORE_HALF_RESPONSE_MINUTES + summary.avg_response_minutes)
+    };
+
+    let positivity = ((summary.overall_sentiment + 1.0) / 2.0).clamp(0.0, 1.0);
+
+    let span_days = |daily: &[Count]| -> Option<i64> {
+        let first = NaiveDate::parse_from_str(&daily.first()?.label, "%Y-%m-%d").ok()?;
+        let last = NaiveDate::parse_from_str(&daily.last()?.label, "%Y-%m-%d").ok()?;
+        Some((last - first).num_days() + 1)
+    };
+    let consistency = span_days(&summary.daily)
+        .map_or(0.0, |span| summary.daily.len() as f32 / span.max(1) as f32);
+
+    let score = SCORE_WEIGHT_RECIPROCITY * reciprocity
+        + SCORE_WEIGHT_RESPONSIVENESS * responsiveness
+        + SCORE_WEIGHT_POSITIVITY * positivity
+        + SCORE_WEIGHT_CONSISTENCY * consistency;
+    RelationshipScore {
+        score: score * 100.0,
+        reciprocity: reciprocity * 100.0,
+        responsiveness: responsiveness * 100.0,
+        positivity: positivity * 100.0,
+        consistency: consistency * 100.0,
+    }
+}
+
+/// People included in the presence calendar, most messages first.
+const PRESENCE_TOP_PEOPLE: usize = 10;
+
+/// Per-day presence bitmaps for the `PRESENCE_TOP_PEOPLE` most active senders (ties by
+/// name), spanning the first to the last day anyone wrote. Built from
+/// `per_person_daily` so merged summaries can rebuild it exactly.
+pub(crate) fn presence_calendar(
+    per_person_daily: &[PersonDaily],
+    by_sender: &[Count],
+) -> PresenceCalendar {
+    let parse = |label: &str| NaiveDate::parse_from_str(label, "%Y-%m-%d").ok();
+    let days_of = |p: &PersonDaily| -> Vec<NaiveDate> {
+        p.daily.iter().filter_map(|c| parse(&c.label)).collect()
+    };
+    let all_days: Vec<NaiveDate> = per_person_daily.iter().flat_map(days_of).collect();
+    let (Some(&start), Some(&end)) = (all_days.iter().min(), all_days.iter().max()) else {
+        return PresenceCalendar {
+            start: String::new(),
+            days: 0,
+            people: Vec::new(),
+        };
+    };
+    let days = (end - start).num_days() as usize + 1;
+
+    let mut ranked: Vec<&Count> = by_sender.iter().collect();
+    ranked.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
+    let people = ranked
+        .into_iter()
+        .filter_map(|c| per_person_daily.iter().find(|p| p.name == c.label))
+        .take(PRESENCE_TOP_PEOPLE)
+        .map(|p| {
+            let mut bits = vec![0u8; days.div_ceil(8)];
+            for day in days_of(p) {
+                let i = (day - start).num_days() as usize;
+                bits[i / 8] |= 1 << (i % 8);
+            }
+            PersonPresence {
+                name: p.name.clone(),
+                bitmap: base64_encode(&bits),
+            }
+        })
+        .collect();
+
+    PresenceCalendar {
+        start: start.format("%Y-%m-%d").to_string(),
+        days: days as u32,
+        people,
+    }
+}
+
+/// Standard (RFC 4648, padded) base64.
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, &b)| acc | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
 pub(crate) fn fun_facts(messages: &[Message]) -> Vec<FunFact> {
     let mut grouped: HashMap<&str, Vec<&Message>> = HashMap::new();
     for m in messages {
@@ -354,7 +1900,7 @@ pub(crate) fn fun_facts(messages: &[Message]) -> Vec<FunFact> {
         };
 
         let mut top_emoji_vec: Vec<_> = emoji_freq.into_iter().collect();
-        top_emoji_vec.sort_by_key(|(_, v)| std::cmp::Reverse(*v));
+        top_emoji_vec.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         top_emoji_vec.truncate(3);
 
         facts.push(FunFact {
@@ -367,15 +1913,53 @@ pub(crate) fn fun_facts(messages: &[Message]) -> Vec<FunFact> {
         });
     }
 
-    facts.sort_by_key(|f| std::cmp::Reverse(f.total_words));
+    facts.sort_by(|a, b| {
+        b.total_words
+            .cmp(&a.total_words)
+            .then_with(|| a.name.cmp(&b.name))
+    });
     facts
 }
 
+/// Distinct words each sender was the first in the chat to use, most first. Stopwords,
+/// media placeholders and tokens without letters are ignored.
+pub(crate) fn word_firsts(messages: &[Message]) -> Vec<Count> {
+    let stop = stopwords_set();
+    let sorted = sorted_view(messages);
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut firsts: HashMap<&str, u32> = HashMap::new();
+    for m in sorted.iter() {
+        if is_media_omitted_message(&m.text) {
+            continue;
+        }
+        for token in tokenize(&m.text, true, stop) {
+            let word = token.trim_matches(|c: char| !c.is_alphanumeric());
+            if !word.chars().any(char::is_alphabetic) || seen.contains(word) {
+                continue;
+            }
+            seen.insert(word.to_string());
+            *firsts.entry(m.sender.as_str()).or_insert(0) += 1;
+        }
+    }
+
+    let mut items: Vec<Count> = firsts
+        .into_iter()
+        .map(|(name, value)| Count {
+            label: name.to_string(),
+            value,
+        })
+        .collect();
+    items.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
+    items
+}
+
 pub(crate) fn person_stats(messages: &[Message]) -> Vec<PersonStat> {
     let mut grouped: HashMap<&str, Vec<&Message>> = HashMap::new();
     for m in messages {
         grouped.entry(m.sender.as_str()).or_default().push(m);
     }
+    let bursts = burst_counts(messages);
 
     let mut stats = Vec::with_capacity(grouped.len());
     for (name, msgs) in grouped.into_iter() {
@@ -385,6 +1969,8 @@ pub(crate) fn person_stats(messages: &[Message]) -> Vec<PersonStat> {
         let mut emoji_freq: HashMap<String, u32> = HashMap::new();
         let mut color_freq: HashMap<String, u32> = HashMap::new();
         let mut counted_msgs = 0u32;
+        let mut lengths = QuantileSketch::new();
+        let (mut lowercase_i, mut uppercase_i) = (0u32, 0u32);
 
         for m in &msgs {
             if is_media_omitted_message(&m.text) {
@@ -393,6 +1979,11 @@ pub(crate) fn person_stats(messages: &[Message]) -> Vec<PersonStat> {
             counted_msgs += 1;
             let mut words_in_message = 0u32;
             for token in m.text.unicode_words() {
+                match token {
+                    "i" => lowercase_i += 1,
+                    "I" => uppercase_i += 1,
+                    _ => {}
+                }
                 let cleaned = token
                     .trim_matches(|c: char| !c.is_alphanumeric())
                     .to_lowercase();
@@ -408,6 +1999,7 @@ pub(crate) fn person_stats(messages: &[Message]) -> Vec<PersonStat> {
                 }
             }
             longest_message = longest_message.max(words_in_message);
+            lengths.push(words_in_message as f32);
 
             for hit in extract_emojis(&m.text) {
                 *emoji_freq.entry(hit).or_insert(0) += 1;
@@ -422,7 +2014,7 @@ pub(crate) fn person_stats(messages: &[Message]) -> Vec<PersonStat> {
         };
 
         let mut top_emoji_vec: Vec<_> = emoji_freq.into_iter().collect();
-        top_emoji_vec.sort_by_key(|(_, v)| std::cmp::Reverse(*v));
+        top_emoji_vec.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         top_emoji_vec.truncate(10);
         let top_emojis = top_emoji_vec
             .into_iter()
@@ -430,6 +2022,10 @@ pub(crate) fn person_stats(messages: &[Message]) -> Vec<PersonStat> {
             .collect();
 
         let dominant_color = pick_dominant_color(&color_freq);
+        let Percentiles { p25, p75, p95 } = lengths.percentiles();
+        let avg_burst_size = bursts
+            .get(name)
+            .map_or(0.0, |&runs| msgs.len() as f32 / runs as f32);
 
         stats.push(PersonStat {
             name: name.to_string(),
@@ -437,17 +2033,44 @@ pub(crate) fn person_stats(messages: &[Message]) -> Vec<PersonStat> {
             unique_words,
             longest_message_words: longest_message,
             average_words_per_message: avg,
+            p25_words: p25,
+            p75_words: p75,
+            p95_words: p95,
             top_emojis,
+            emoji_pairs: emoji_pairs(msgs.iter().map(|m| m.text.as_str()), 5),
             dominant_color,
+            avg_burst_size,
+            lowercase_i_ratio: if lowercase_i + uppercase_i == 0 {
+                0.0
+            } else {
+                lowercase_i as f32 / (lowercase_i + uppercase_i) as f32
+            },
         });
     }
 
-    stats.sort_by_key(|s| std::cmp::Reverse(s.total_words));
+    stats.sort_by(|a, b| {
+        b.total_words
+            .cmp(&a.total_words)
+            .then_with(|| a.name.cmp(&b.name))
+    });
     stats
 }
 
-#[cfg(test)]
-mod tests {
+/// Turn runs per sender: how many times each person took over the conversation.
+fn burst_counts(messages: &[Message]) -> HashMap<&str, u32> {
+    let mut runs: HashMap<&str, u32> = HashMap::new();
+    let mut previous: Option<&str> = None;
+    for m in messages {
+        if previous != Some(m.sender.as_str()) {
+            *runs.entry(m.sender.as_str()).or_insert(0) += 1;
+        }
+        previous = Some(m.sender.as_str());
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
     use super::*;
     use chrono::NaiveDateTime;
 
@@ -456,6 +2079,7 @@ mod tests {
             dt: NaiveDateTime::parse_from_str(dt_str, "%Y-%m-%d %H:%M:%S").unwrap(),
             sender: sender.to_string(),
             text: text.to_string(),
+            line: 0,
         }
     }
 
@@ -533,6 +2157,36 @@ mod tests {
         assert_eq!(len, 2);
     }
 
+    #[test]
+    fn ignored_ranges_bridge_streak_gaps() {
+        let daily: Vec<Count> = [
+            "2024-03-01",
+            "2024-03-02",
+            "2024-03-03",
+            "2024-03-06",
+            "2024-03-07",
+        ]
+        .iter()
+        .map(|d| Count {
+            label: d.to_string(),
+            value: 1,
+        })
+        .collect();
+        assert_eq!(longest_streak(&daily).unwrap().0, 3);
+
+        let day = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let trip = [(day("2024-03-04"), day("2024-03-05"))];
+        let (len, start, end) = longest_streak_ignoring(&daily, &trip).unwrap();
+        assert_eq!(
+            (len, start.as_str(), end.as_str()),
+            (5, "2024-03-01", "2024-03-07")
+        );
+
+        // A range covering only part of the gap doesn't bridge it.
+        let partial = [(day("2024-03-04"), day("2024-03-04"))];
+        assert_eq!(longest_streak_ignoring(&daily, &partial).unwrap().0, 3);
+    }
+
     #[test]
     fn longest_streak_from_raw_empty() {
         assert!(longest_streak_from_raw("").is_none());
@@ -589,6 +2243,58 @@ mod tests {
         assert_eq!(monthly[1].label, "2023-03");
     }
 
+    fn multi_year_months() -> Vec<Message> {
+        vec![
+            msg("A", "x", "2022-01-10 10:00:00"),
+            msg("A", "x", "2022-02-10 10:00:00"),
+            msg("A", "x", "2022-04-10 10:00:00"),
+            msg("A", "x", "2022-07-10 10:00:00"),
+            msg("A", "x", "2022-12-10 10:00:00"),
+            msg("A", "x", "2023-01-10 10:00:00"),
+            msg("A", "x", "2023-10-10 10:00:00"),
+        ]
+    }
+
+    #[test]
+    fn quarterly_counts_roll_up_months() {
+        let quarterly = quarterly_counts(&monthly_counts(&multi_year_months()));
+        let labels: Vec<(&str, u32)> = quarterly
+            .iter()
+            .map(|c| (c.label.as_str(), c.value))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                ("2022-Q1", 2),
+                ("2022-Q2", 1),
+                ("2022-Q3", 1),
+                ("2022-Q4", 1),
+                ("2023-Q1", 1),
+                ("2023-Q4", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn seasonal_profile_aggregates_across_years() {
+        let monthly = monthly_counts(&multi_year_months());
+        // Winter: Jan, Feb, Dec 2022 + Jan 2023.
+        assert_eq!(
+            seasonal_profile(&monthly, Hemisphere::Northern),
+            [1, 1, 1, 4]
+        );
+    }
+
+    #[test]
+    fn seasonal_profile_flips_for_southern_hemisphere() {
+        let monthly = monthly_counts(&multi_year_months());
+        // December/January are summer south of the equator; April is autumn.
+        assert_eq!(
+            seasonal_profile(&monthly, Hemisphere::Southern),
+            [1, 4, 1, 1]
+        );
+    }
+
     #[test]
     fn deleted_counts_distinguishes_you_and_others() {
         let messages = vec![
@@ -602,6 +2308,46 @@ mod tests {
         assert_eq!(others, 2);
     }
 
+    #[test]
+    fn voice_notes_aggregate_durations_and_longest() {
+        let messages = vec![
+            msg(
+                "A",
+                "PTT-20230101-WA0001.opus (0:42)",
+                "2023-01-01 10:00:00",
+            ),
+            msg(
+                "B",
+                "PTT-20230102-WA0002.opus (1:02:03)",
+                "2023-01-02 10:00:00",
+            ),
+            msg("A", "\u{200e}audio omitted", "2023-01-03 10:00:00"),
+            msg("A", "not a voice note", "2023-01-03 10:01:00"),
+        ];
+        let notes = voice_notes(&messages);
+        assert_eq!(notes.count, 3);
+        assert_eq!(notes.unknown_duration, 1);
+        assert!((notes.total_minutes - (42.0 + 3723.0) / 60.0).abs() < 1e-4);
+
+        let a = notes.per_person.iter().find(|p| p.name == "A").unwrap();
+        assert_eq!(a.count, 2);
+        assert!((a.minutes - 0.7).abs() < 1e-4);
+
+        let longest = notes.longest.expect("has a timed note");
+        assert_eq!(longest.sender, "B");
+        assert_eq!(longest.date, "2023-01-02");
+        assert_eq!(longest.seconds, 3723);
+    }
+
+    #[test]
+    fn voice_notes_empty_without_markers() {
+        let messages = vec![msg("A", "hello", "2023-01-01 10:00:00")];
+        let notes = voice_notes(&messages);
+        assert_eq!(notes.count, 0);
+        assert!(notes.per_person.is_empty());
+        assert!(notes.longest.is_none());
+    }
+
     #[test]
     fn timeline_empty_is_empty() {
         assert!(timeline(&[]).is_empty());
@@ -630,6 +2376,172 @@ mod tests {
         assert_eq!(tl[0].value, 1);
     }
 
+    fn span_of_days(days: i64) -> Vec<Count> {
+        let start = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        (0..days)
+            .map(|i| Count {
+                label: (start + chrono::Duration::days(i))
+                    .format("%Y-%m-%d")
+                    .to_string(),
+                value: 1,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn timeline_unit_auto_thresholds() {
+        let unit = |days| timeline_unit(&span_of_days(days), TimelineGranularity::Auto);
+        assert_eq!(unit(1), TimelineUnit::Day);
+        assert_eq!(unit(400), TimelineUnit::Day);
+        assert_eq!(unit(401), TimelineUnit::Week);
+        assert_eq!(unit(3 * 365), TimelineUnit::Week);
+        assert_eq!(unit(3 * 365 + 1), TimelineUnit::Month);
+        // An explicit choice wins regardless of span.
+        assert_eq!(
+            timeline_unit(&span_of_days(2000), TimelineGranularity::Day),
+            TimelineUnit::Day
+        );
+    }
+
+    #[test]
+    fn timeline_at_week_sums_days_and_fills_gaps() {
+        let messages = vec![
+            // Monday and Sunday of ISO week 2023-W01.
+            msg("A", "x", "2023-01-02 10:00:00"),
+            msg("A", "x", "2023-01-08 10:00:00"),
+            msg("A", "x", "2023-01-08 11:00:00"),
+            // Nothing in W02, one message in W03.
+            msg("A", "x", "2023-01-16 10:00:00"),
+        ];
+        let weeks = timeline_at(timeline(&messages), TimelineUnit::Week);
+        let labels: Vec<&str> = weeks.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, ["2023-W01", "2023-W02", "2023-W03"]);
+        let values: Vec<u32> = weeks.iter().map(|c| c.value).collect();
+        assert_eq!(values, [3, 0, 1]);
+    }
+
+    #[test]
+    fn timeline_at_month_fills_gaps_across_years() {
+        let messages = vec![
+            msg("A", "x", "2022-11-30 10:00:00"),
+            msg("A", "x", "2023-02-01 10:00:00"),
+        ];
+        let months = timeline_at(timeline(&messages), TimelineUnit::Month);
+        let labels: Vec<&str> = months.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, ["2022-11", "2022-12", "2023-01", "2023-02"]);
+        assert_eq!(months.iter().map(|c| c.value).sum::<u32>(), 2);
+    }
+
+    #[test]
+    fn timeline_annotations_flag_birthday_spike_only() {
+        let mut msgs: Vec<Message> = (0..6)
+            .map(|i| {
+                msg(
+                    "Friend",
+                    "Happy birthday!! 🎂",
+                    &format!("2023-05-02 0{i}:00:00"),
+                )
+            })
+            .collect();
+        msgs.push(msg("Alice", "congrats", "2023-05-02 09:00:00"));
+        // A busy day with no congratulations must stay unannotated.
+        msgs.extend((0..30).map(|i| {
+            msg(
+                "Alice",
+                "just chatting",
+                &format!("2023-05-03 10:{:02}:00", i),
+            )
+        }));
+
+        let annotations = timeline_annotations(&msgs);
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(annotations[0].date, "2023-05-02");
+        assert_eq!(annotations[0].kind, "birthday");
+        assert_eq!(annotations[0].evidence_count, 6);
+    }
+
+    fn ramp(per_day: &[usize]) -> Vec<Message> {
+        per_day
+            .iter()
+            .enumerate()
+            .flat_map(|(day, &count)| {
+                (0..count).map(move |i| {
+                    msg(
+                        "Alice",
+                        "hey",
+                        &format!("2023-03-{:02} 10:{:02}:00", day + 1, i),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn volume_trend(messages: &[Message]) -> Trend {
+        volume_trend_from_timeline(&timeline(messages))
+    }
+
+    #[test]
+    fn volume_trend_detects_growth_and_decline() {
+        let growing = volume_trend(&ramp(&[1, 2, 3, 5, 8, 10, 12]));
+        assert_eq!(growing.direction, TrendDirection::Growing);
+        assert!(growing.slope > 1.0);
+
+        let declining = volume_trend(&ramp(&[12, 10, 8, 5, 3, 2, 1]));
+        assert_eq!(declining.direction, TrendDirection::Declining);
+
+        let flat = volume_trend(&ramp(&[5, 5, 6, 5, 5, 6, 5]));
+        assert_eq!(flat.direction, TrendDirection::Stable);
+    }
+
+    #[test]
+    fn volume_trend_single_day_is_stable() {
+        let trend = volume_trend(&ramp(&[4]));
+        assert_eq!(trend.slope, 0.0);
+        assert_eq!(trend.direction, TrendDirection::Stable);
+    }
+
+    fn weekly_burst(day: &str, count: usize) -> Vec<Message> {
+        (0..count)
+            .map(|i| msg("Alice", "hey", &format!("{day} 10:{:02}:00", i)))
+            .collect()
+    }
+
+    #[test]
+    fn iso_weekly_counts_fill_gaps_across_year_boundary() {
+        let mut msgs = weekly_burst("2020-12-28", 2); // Monday of 2020-W53
+        msgs.extend(weekly_burst("2021-01-11", 1)); // 2021-W02
+        let weeks = iso_weekly_counts(&msgs);
+        let labels: Vec<&str> = weeks.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, vec!["2020-W53", "2021-W01", "2021-W02"]);
+        let values: Vec<u32> = weeks.iter().map(|c| c.value).collect();
+        assert_eq!(values, vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn weekly_streak_crosses_december_january() {
+        let mut msgs = Vec::new();
+        for day in [
+            "2023-12-11",
+            "2023-12-18",
+            "2023-12-27",
+            "2024-01-03",
+            "2024-01-10",
+        ] {
+            msgs.extend(weekly_burst(day, 10));
+        }
+        // A quiet week breaks the run, then a shorter burst follows.
+        msgs.extend(weekly_burst("2024-01-17", 3));
+        msgs.extend(weekly_burst("2024-01-24", 12));
+
+        let streak = weekly_streak(&msgs, 10).expect("streak");
+        assert_eq!(streak.weeks, 5);
+        assert_eq!(streak.start_week, "2023-W50");
+        assert_eq!(streak.end_week, "2024-W02");
+        assert_eq!(streak.threshold, 10);
+
+        assert!(weekly_streak(&msgs, 50).is_none());
+    }
+
     #[test]
     fn buckets_by_person_aggregates() {
         let messages = vec![
@@ -647,6 +2559,33 @@ mod tests {
         assert_eq!(buckets[0].name, "A");
     }
 
+    #[test]
+    fn weekday_leaders_pick_the_top_sender_and_drop_ties() {
+        let messages = vec![
+            // Friday 2024-01-05: Dave twice, Erin once.
+            msg("Dave", "x", "2024-01-05 21:00:00"),
+            msg("Dave", "x", "2024-01-05 22:00:00"),
+            msg("Erin", "x", "2024-01-05 23:00:00"),
+            // Monday 2024-01-08: Erin only.
+            msg("Erin", "x", "2024-01-08 09:00:00"),
+            // Wednesday 2024-01-10: one each.
+            msg("Dave", "x", "2024-01-10 09:00:00"),
+            msg("Erin", "x", "2024-01-10 09:01:00"),
+        ];
+        let (leaders, shares) = weekday_leaders(&buckets_by_person(&messages));
+        assert_eq!(leaders[5].as_deref(), Some("Dave"));
+        assert_eq!(leaders[1].as_deref(), Some("Erin"));
+        assert_eq!(leaders[3], None);
+        assert_eq!(leaders[0], None);
+
+        let dave = shares.iter().find(|s| s.name == "Dave").unwrap();
+        assert!((dave.percent[5] - 200.0 / 3.0).abs() < 1e-4);
+        assert_eq!(
+            (dave.percent[1], dave.percent[3], dave.percent[0]),
+            (0.0, 50.0, 0.0)
+        );
+    }
+
     #[test]
     fn buckets_by_person_empty() {
         assert!(buckets_by_person(&[]).is_empty());
@@ -659,10 +2598,12 @@ mod tests {
             msg("Alice", "y", "2023-01-01 10:00:00"),
             msg("Alice", "z", "2023-01-02 10:00:00"),
         ];
-        let pp = per_person_daily(&messages);
+        let pp = per_person_daily(&messages, &[]);
         assert_eq!(pp[0].name, "Alice");
         assert_eq!(pp[0].daily.len(), 2);
+        assert_eq!(pp[0].longest_streak, 2);
         assert_eq!(pp[1].name, "Bob");
+        assert_eq!(pp[1].longest_streak, 1);
     }
 
     #[test]
@@ -685,6 +2626,22 @@ mod tests {
         assert!(fun_facts(&[]).is_empty());
     }
 
+    #[test]
+    fn word_firsts_credit_the_first_user_only() {
+        let msgs = vec![
+            msg("Bob", "Yeet! the yeet", "2023-01-01 10:01:00"),
+            msg("Alice", "yeet yeet", "2023-01-01 10:00:00"),
+            msg("Bob", "bazinga 2023", "2023-01-01 10:02:00"),
+            msg("Alice", "<Media omitted>", "2023-01-01 10:03:00"),
+        ];
+        let firsts = word_firsts(&msgs);
+        // Alice said "yeet" first (despite input order); Bob only gets "bazinga".
+        assert_eq!(firsts.len(), 2);
+        assert!(firsts.iter().all(|c| c.value == 1));
+        assert_eq!(firsts[0].label, "Alice");
+        assert_eq!(firsts[1].label, "Bob");
+    }
+
     #[test]
     fn person_stats_average_and_unique() {
         let messages = vec![
@@ -707,9 +2664,856 @@ mod tests {
         assert_eq!(a.average_words_per_message, 0.0);
     }
 
+    #[test]
+    fn segment_conversations_splits_on_gap() {
+        let messages = vec![
+            msg("A", "a", "2023-01-01 10:00:00"),
+            msg("B", "b", "2023-01-01 10:30:00"),
+            msg("A", "c", "2023-01-01 11:01:00"),
+        ];
+        let segments = segment_conversations(&messages, 30);
+        assert_eq!(
+            segments,
+            vec![
+                ConversationSegment { start: 0, end: 2 },
+                ConversationSegment { start: 2, end: 3 },
+            ]
+        );
+        assert!(segment_conversations(&[], 30).is_empty());
+    }
+
+    #[test]
+    fn biggest_conversation_picks_longest_segment() {
+        let messages = vec![
+            msg("A", "short one", "2023-01-01 08:00:00"),
+            msg("B", "ok", "2023-01-01 08:01:00"),
+            msg("A", "the big one", "2023-01-02 21:00:00"),
+            msg("B", "keeps going", "2023-01-02 21:05:00"),
+            msg("C", "me too", "2023-01-02 21:06:00"),
+            msg("A", "and on", "2023-01-02 21:20:00"),
+            msg("B", "another short", "2023-01-03 09:00:00"),
+        ];
+        let best = biggest_conversation(&messages, 30).unwrap();
+        assert_eq!(best.date, "2023-01-02");
+        assert_eq!(best.message_count, 4);
+        assert_eq!(best.participants, vec!["A", "B", "C"]);
+        assert_eq!(best.preview, vec!["the big one", "keeps going", "me too"]);
+        assert_eq!(best.end, "2023-01-02T21:20:00");
+    }
+
+    #[test]
+    fn reply_gaps_skip_same_sender_and_stale_replies() {
+        let msgs = vec![
+            msg("Alice", "hi", "2023-01-01 10:00:00"),
+            msg("Alice", "you there?", "2023-01-01 10:01:00"),
+            msg("Bob", "yes", "2023-01-01 10:04:30"),
+            msg("Alice", "days later", "2023-01-05 10:00:00"),
+        ];
+        let gaps = reply_gaps(&msgs, 24 * 60);
+        assert_eq!(gaps, vec![("Bob".to_string(), 3.5)]);
+    }
+
+    #[test]
+    fn response_latency_takes_per_person_median() {
+        let gaps = vec![
+            ("Bob".to_string(), 10.0),
+            ("Alice".to_string(), 1.0),
+            ("Bob".to_string(), 2.0),
+            ("Bob".to_string(), 4.0),
+        ];
+        let latency = response_latency(&gaps);
+        assert_eq!(latency.len(), 2);
+        assert_eq!(latency[0].name, "Alice");
+        assert_eq!(latency[1].name, "Bob");
+        assert_eq!(latency[1].replies, 3);
+        assert!((latency[1].median_minutes - 4.0).abs() < 1e-6);
+        // Bob's sorted gaps are [2, 4, 10].
+        assert!((latency[1].p25_minutes - 3.0).abs() < 1e-6);
+        assert!((latency[1].p75_minutes - 7.0).abs() < 1e-6);
+        assert!((latency[1].p95_minutes - 9.4).abs() < 1e-5);
+        assert!((avg_response_minutes(&gaps) - 3.0).abs() < 1e-6);
+        assert_eq!(avg_response_minutes(&[]), 0.0);
+    }
+
+    #[test]
+    fn reply_time_histogram_buckets_ten_minute_reply() {
+        let msgs = vec![
+            msg("Alice", "hi", "2023-01-01 10:00:00"),
+            msg("Bob", "sorry, was busy", "2023-01-01 10:10:00"),
+            msg("Alice", "np", "2023-01-01 10:10:20"),
+        ];
+        let hist = reply_time_histogram(&reply_gaps(&msgs, 24 * 60));
+        assert_eq!(hist.len(), 2);
+        let bob = &hist[1];
+        assert_eq!(bob.name, "Bob");
+        let labels: Vec<&str> = bob.buckets.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, vec!["<1m", "1-5m", "5-30m", "30m-2h", ">2h"]);
+        let values: Vec<u32> = bob.buckets.iter().map(|c| c.value).collect();
+        assert_eq!(values, vec![0, 0, 1, 0, 0]);
+        assert_eq!(hist[0].buckets[0].value, 1);
+    }
+
+    #[test]
+    fn biggest_conversation_empty_is_none() {
+        assert!(biggest_conversation(&[], 30).is_none());
+    }
+
+    #[test]
+    fn emoji_trend_spots_rising_emoji_use() {
+        let mut msgs = Vec::new();
+        for day in 1..=10 {
+            msgs.push(msg(
+                "Alice",
+                "see you later",
+                &format!("2023-01-{day:02} 10:00:00"),
+            ));
+            msgs.push(msg("Bob", "ok 👍", &format!("2023-01-{day:02} 11:00:00")));
+            msgs.push(msg(
+                "Alice",
+                "haha 😂😂",
+                &format!("2023-06-{day:02} 10:00:00"),
+            ));
+            msgs.push(msg("Bob", "sure 👍", &format!("2023-06-{day:02} 11:00:00")));
+        }
+        // Too few messages late in the chat to say anything about Carol.
+        msgs.push(msg("Carol", "🎉", "2023-01-05 12:00:00"));
+        msgs.push(msg("Alice", "mid", "2023-03-15 10:00:00"));
+
+        let trend = emoji_trend_by_person(&msgs);
+        let summary: Vec<(&str, f32, f32, TrendDirection)> = trend
+            .iter()
+            .map(|t| (t.name.as_str(), t.early_rate, t.late_rate, t.direction))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Alice", 0.0, 2.0, TrendDirection::Growing),
+                ("Bob", 1.0, 1.0, TrendDirection::Stable),
+            ]
+        );
+        assert!(emoji_trend_by_person(&msgs[..1]).is_empty());
+    }
+
+    #[test]
+    fn theme_counts_add_up_whole_word_hits_per_theme() {
+        let msgs = vec![
+            msg("Alice", "Pizza tonight? or SUSHI", "2024-01-01 10:00:00"),
+            msg(
+                "Bob",
+                "pizza pizza! pizzas don't count",
+                "2024-01-01 10:01:00",
+            ),
+            msg("Bob", "good game", "2024-01-01 10:02:00"),
+        ];
+        let clusters = vec![
+            (
+                "food".to_string(),
+                vec!["pizza".to_string(), "sushi".to_string()],
+            ),
+            (
+                "gaming".to_string(),
+                vec!["good game".to_string(), "gg".to_string()],
+            ),
+            ("travel".to_string(), vec!["flight".to_string()]),
+        ];
+        let counts: Vec<(String, u32)> = theme_counts(&msgs, &clusters)
+            .into_iter()
+            .map(|c| (c.label, c.value))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                ("food".to_string(), 4),
+                ("gaming".to_string(), 1),
+                ("travel".to_string(), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn punctuation_energy_counts_marks_per_month_and_person() {
+        let msgs = vec![
+            msg("Alice", "really?? no way!!!!!!!", "2024-01-03 10:00:00"),
+            msg(
+                "Bob",
+                "see https://example.com/search?q=a&b=c?",
+                "2024-01-04 10:00:00",
+            ),
+            msg("Bob", "why?", "2024-01-05 10:00:00"),
+            msg("Alice", "nothing to see here", "2024-01-06 10:00:00"),
+            msg("Alice", "yes! yes! yes!", "2024-02-01 10:00:00"),
+        ];
+        let (chat, by_person) = punctuation_energy(&msgs);
+
+        let rows = |months: &[PunctuationMonth]| {
+            months
+                .iter()
+                .map(|m| {
+                    (
+                        m.month.clone(),
+                        m.questions,
+                        m.exclamations,
+                        m.exclamations_per_question,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        // The seven "!" count as three and the URL's "?"s not at all.
+        assert_eq!(
+            rows(&chat),
+            vec![
+                ("2024-01".to_string(), 3, 3, Some(1.0)),
+                ("2024-02".to_string(), 0, 3, None),
+            ]
+        );
+        assert_eq!(by_person.len(), 2);
+        assert_eq!(by_person[0].name, "Alice");
+        assert_eq!(
+            rows(&by_person[0].monthly),
+            vec![
+                ("2024-01".to_string(), 2, 3, Some(1.5)),
+                ("2024-02".to_string(), 0, 3, None),
+            ]
+        );
+        assert_eq!(by_person[1].name, "Bob");
+        assert_eq!(
+            rows(&by_person[1].monthly),
+            vec![("2024-01".to_string(), 1, 0, Some(0.0))]
+        );
+    }
+
+    #[test]
+    fn emoji_spam_by_sender_counts_repeated_single_emoji() {
+        let msgs = vec![
+            msg("Alice", "🔥🔥🔥🔥", "2023-01-01 10:00:00"),
+            msg("Alice", "🔥😂🔥", "2023-01-01 10:01:00"),
+            msg("Bob", "😂😂😂", "2023-01-01 10:02:00"),
+            msg("Alice", "💀💀💀", "2023-01-01 10:03:00"),
+        ];
+        let counts = emoji_spam_by_sender(&msgs);
+        assert_eq!(counts.len(), 2);
+        assert_eq!((counts[0].label.as_str(), counts[0].value), ("Alice", 2));
+        assert_eq!((counts[1].label.as_str(), counts[1].value), ("Bob", 1));
+    }
+
+    #[test]
+    fn collapse_spam_runs_reports_and_collapses_long_runs() {
+        let mut messages = vec![msg("A", "hello", "2023-01-01 09:00:00")];
+        for i in 0..50 {
+            messages.push(msg(
+                "B",
+                "?",
+                &format!("2023-01-01 10:{:02}:{:02}", i / 60, i % 60),
+            ));
+        }
+        messages.push(msg("A", "stop it", "2023-01-01 11:00:00"));
+
+        let (collapsed, runs) = collapse_spam_runs(&messages);
+        assert_eq!(collapsed.len(), 3);
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].sender, "B");
+        assert_eq!(runs[0].text_preview, "?");
+        assert_eq!(runs[0].count, 50);
+        assert_eq!(runs[0].date, "2023-01-01");
+    }
+
+    #[test]
+    fn routine_detection_finds_nightly_check_in() {
+        let mut messages = Vec::new();
+        for day in 1..=14 {
+            messages.push(msg("A", "night!", &format!("2023-05-{day:02} 23:05:00")));
+            // Daytime chatter only every third day.
+            if day % 3 == 0 {
+                messages.push(msg("B", "lunch?", &format!("2023-05-{day:02} 12:30:00")));
+            }
+        }
+        let routines = routine_detection(&messages);
+        assert_eq!(routines.len(), 1);
+        assert_eq!(routines[0].hour, 23);
+        assert_eq!(routines[0].days, 14);
+        assert_eq!(routines[0].coverage, 1.0);
+    }
+
+    #[test]
+    fn routine_detection_needs_enough_history() {
+        let messages: Vec<Message> = (1..=3)
+            .map(|day| msg("A", "night!", &format!("2023-05-{day:02} 23:05:00")))
+            .collect();
+        assert!(routine_detection(&messages).is_empty());
+    }
+
+    #[test]
+    fn poll_stats_tallies_polls_and_votes() {
+        let messages = vec![
+            msg(
+                "Alice",
+                "POLL:\nDinner?\nOPTION: Pizza (2 votes)\nOPTION: Sushi (1 vote)",
+                "2024-01-01 10:00:00",
+            ),
+            msg("Bob", "pizza obviously", "2024-01-01 10:05:00"),
+            msg(
+                "Alice",
+                "POLL:\nHike?\nOPTION: Yes (3 votes)",
+                "2024-01-02 09:00:00",
+            ),
+        ];
+        let stats = poll_stats(&messages);
+        assert_eq!(stats.total_polls, 2);
+        assert_eq!(stats.total_votes, 6);
+        assert_eq!(stats.by_sender[0].label, "Alice");
+        assert_eq!(stats.by_sender[0].value, 2);
+        assert_eq!(stats.polls[0].question, "Dinner?");
+        assert_eq!(stats.polls[0].options[1].label, "Sushi");
+        assert_eq!(stats.polls[1].date, "2024-01-02");
+    }
+
+    #[test]
+    fn special_days_compare_with_surrounding_days() {
+        let mut messages = Vec::new();
+        for day in (1..=28).filter(|&d| d != 14) {
+            messages.push(msg(
+                "A",
+                "just a normal day",
+                &format!("2024-02-{day:02} 10:00:00"),
+            ));
+        }
+        for i in 0..9 {
+            messages.push(msg(
+                "B",
+                "happy birthday",
+                &format!("2024-02-14 12:{i:02}:00"),
+            ));
+        }
+        let dates = vec![
+            SpecialDate {
+                date: "2024-02-14".into(),
+                label: "Birthday".into(),
+            },
+            SpecialDate {
+                date: "2030-01-01".into(),
+                label: "Someday".into(),
+            },
+        ];
+        let days = special_days(&messages, &dates, 500, &EmojiWeights::new());
+        assert_eq!(days.len(), 2);
+
+        let birthday = &days[0];
+        assert_eq!(
+            (birthday.label.as_str(), birthday.messages),
+            ("Birthday", 9)
+        );
+        // Jan 30-31 and Feb 29 are silent: 27 messages over 30 days, so 9 vs 0.9.
+        assert!((birthday.vs_average_pct - 900.0).abs() < 1e-2);
+        assert_eq!(birthday.top_phrase.as_deref(), Some("happy birthday"));
+        assert!(birthday.sentiment_mean > 0.0);
+
+        let absent = &days[1];
+        assert_eq!(absent.messages, 0);
+        assert_eq!(absent.vs_average_pct, 0.0);
+        assert_eq!(absent.top_phrase, None);
+    }
+
+    #[test]
+    fn lowercase_i_ratio_reads_raw_casing() {
+        let messages = vec![
+            msg("A", "i think i can", "2024-01-01 10:00:00"),
+            msg("A", "i'm sure", "2024-01-01 10:01:00"),
+            msg("B", "I think I can, but i doubt it", "2024-01-01 10:02:00"),
+            msg("C", "no pronouns here", "2024-01-01 10:03:00"),
+        ];
+        let stats = person_stats(&messages);
+        let ratio = |name: &str| {
+            stats
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap()
+                .lowercase_i_ratio
+        };
+        assert_eq!(ratio("A"), 1.0);
+        assert!((ratio("B") - 1.0 / 3.0).abs() < 1e-6);
+        assert_eq!(ratio("C"), 0.0);
+    }
+
+    #[test]
+    fn rapid_fire_senders_have_bigger_bursts() {
+        let messages = vec![
+            msg("A", "hey", "2024-01-01 10:00:00"),
+            msg("A", "you there", "2024-01-01 10:00:05"),
+            msg("A", "??", "2024-01-01 10:00:09"),
+            msg(
+                "B",
+                "yes, sorry, I was out walking the dog",
+                "2024-01-01 10:05:00",
+            ),
+            msg("A", "ok", "2024-01-01 10:06:00"),
+            msg("A", "cool", "2024-01-01 10:06:02"),
+            msg("A", "so", "2024-01-01 10:06:04"),
+            msg("B", "what's up?", "2024-01-01 10:07:00"),
+        ];
+        let stats = person_stats(&messages);
+        let burst = |name: &str| {
+            stats
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap()
+                .avg_burst_size
+        };
+        assert_eq!(burst("A"), 3.0);
+        assert_eq!(burst("B"), 1.0);
+    }
+
+    #[test]
+    fn base64_encode_matches_rfc_vectors() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn presence_calendar_sets_one_bit_per_active_day() {
+        let messages = vec![
+            msg("Alice", "hi", "2024-01-01 10:00:00"),
+            msg("Alice", "again", "2024-01-01 11:00:00"),
+            msg("Bob", "yo", "2024-01-02 10:00:00"),
+            msg("Alice", "hi", "2024-01-03 10:00:00"),
+            msg("Alice", "hi", "2024-01-10 10:00:00"),
+        ];
+        let calendar = presence_calendar(
+            &per_person_daily(&messages, &[]),
+            &count_by_sender(&messages),
+        );
+        assert_eq!(calendar.start, "2024-01-01");
+        assert_eq!(calendar.days, 10);
+        // Alice: days 0, 2 and 9 -> bytes [0b0000_0101, 0b0000_0010].
+        assert_eq!(calendar.people[0].name, "Alice");
+        assert_eq!(
+            calendar.people[0].bitmap,
+            base64_encode(&[0b0000_0101, 0b0000_0010])
+        );
+        // Bob: day 1 only.
+        assert_eq!(calendar.people[1].bitmap, base64_encode(&[0b0000_0010, 0]));
+
+        let empty = presence_calendar(&[], &[]);
+        assert_eq!((empty.days, empty.people.len()), (0, 0));
+    }
+
+    #[test]
+    fn busiest_and_quietest_month_pick_extremes() {
+        let mut messages = Vec::new();
+        for (month, count) in [("01", 3), ("02", 5), ("03", 5), ("04", 1), ("06", 1)] {
+            for i in 0..count {
+                messages.push(msg(
+                    "A",
+                    "hi",
+                    &format!("2023-{month}-{:02} 10:00:00", i + 1),
+                ));
+            }
+        }
+        let monthly = monthly_counts(&messages);
+        let (busiest, quietest) = busiest_and_quietest_month(&monthly);
+        let busiest = busiest.unwrap();
+        let quietest = quietest.unwrap();
+        // February and March tie; the earlier month wins.
+        assert_eq!((busiest.label.as_str(), busiest.value), ("2023-02", 5));
+        // May had no messages and is not a candidate.
+        assert_eq!((quietest.label.as_str(), quietest.value), ("2023-04", 1));
+        assert_eq!(busiest_and_quietest_month(&[]).0.map(|c| c.value), None);
+    }
+
+    #[test]
+    fn month_swings_find_spike_drop_and_mood_dip() {
+        let monthly: Vec<Count> = [
+            ("2024-01", 60),
+            ("2024-02", 200),
+            ("2024-04", 80),
+            ("2024-05", 10),
+        ]
+        .into_iter()
+        .map(|(label, value)| Count {
+            label: label.into(),
+            value,
+        })
+        .collect();
+        let day = |day: &str, mean: f32, n: u32| SentimentDay {
+            name: "A".into(),
+            day: day.into(),
+            mean,
+            pos: 0,
+            neu: n,
+            neg: 0,
+        };
+        let sentiment = vec![
+            day("2024-01-10", 0.5, 60),
+            day("2024-02-03", -0.2, 120),
+            day("2024-02-20", -0.5, 80),
+            // April has no qualifying March before it; May is too small.
+            day("2024-04-02", 0.4, 80),
+            day("2024-05-01", 0.9, 10),
+        ];
+
+        let swings = month_swings(&monthly, &sentiment);
+        let kinds: Vec<(SwingKind, &str)> =
+            swings.iter().map(|s| (s.kind, s.month.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (SwingKind::VolumeRise, "2024-02"),
+                (SwingKind::VolumeDrop, "2024-03"),
+                (SwingKind::SentimentSwing, "2024-02"),
+            ]
+        );
+        assert_eq!(
+            (swings[0].prev, swings[0].curr, swings[0].delta),
+            (60.0, 200.0, 140.0)
+        );
+        assert!((swings[0].percent.unwrap() - 233.33).abs() < 0.01);
+        assert_eq!(swings[1].percent, Some(-100.0));
+        assert!((swings[2].curr - -0.32).abs() < 1e-4);
+        assert!((swings[2].delta - -0.82).abs() < 1e-4);
+
+        assert!(month_swings(&monthly[..1], &[]).is_empty());
+    }
+
+    #[test]
+    fn conversation_starts_bucket_by_first_message() {
+        let messages = vec![
+            // Monday 22:00, running past midnight into Tuesday.
+            msg("A", "evening", "2024-01-01 22:00:00"),
+            msg("B", "still up", "2024-01-01 23:50:00"),
+            msg("A", "yes", "2024-01-02 00:10:00"),
+            // Tuesday 08:30 and Saturday 22:15.
+            msg("B", "morning", "2024-01-02 08:30:00"),
+            msg("A", "weekend", "2024-01-06 22:15:00"),
+        ];
+        let (hours, weekdays) = conversation_start_histograms(&messages, 120);
+        assert_eq!((hours[22], hours[8], hours[0]), (2, 1, 0));
+        assert_eq!(hours.iter().sum::<u32>(), 3);
+        assert_eq!(weekdays, [0, 1, 1, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn conversations_per_month_counts_by_start_month() {
+        let messages = vec![
+            msg("A", "one", "2024-01-10 10:00:00"),
+            msg("B", "two", "2024-01-20 10:00:00"),
+            // Starts in January, runs past midnight into February.
+            msg("A", "three", "2024-01-31 23:50:00"),
+            msg("B", "still three", "2024-02-01 00:05:00"),
+            msg("A", "four", "2024-02-14 09:00:00"),
+        ];
+        let monthly = conversations_per_month(&messages, 30);
+        let pairs: Vec<(&str, u32)> = monthly
+            .iter()
+            .map(|c| (c.label.as_str(), c.value))
+            .collect();
+        assert_eq!(pairs, vec![("2024-01", 3), ("2024-02", 1)]);
+        assert!(conversations_per_month(&[], 30).is_empty());
+    }
+
+    #[test]
+    fn dense_chats_outscore_sparse_ones() {
+        // Six messages a minute apart: one 5-minute conversation.
+        let dense: Vec<Message> = (0..6)
+            .map(|i| msg("A", "hi", &format!("2024-03-01 10:0{i}:00")))
+            .collect();
+        // Six messages 20 minutes apart: one 100-minute conversation.
+        let sparse: Vec<Message> = ["10:00", "10:20", "10:40", "11:00", "11:20", "11:40"]
+            .iter()
+            .map(|t| msg("A", "hi", &format!("2024-03-01 {t}:00")))
+            .collect();
+        assert_eq!(conversation_minutes(&dense, 30), 5);
+        assert_eq!(conversation_minutes(&sparse, 30), 100);
+        assert_eq!(message_density(6, 5), 72.0);
+        assert!(message_density(6, 100) < message_density(6, 5));
+        // A lone message still takes a minute.
+        assert_eq!(conversation_minutes(&dense[..1], 30), 1);
+        assert_eq!(message_density(0, 0), 0.0);
+    }
+
+    #[test]
+    fn edits_per_conversation_finds_the_edit_heavy_exchange() {
+        let messages = vec![
+            msg("A", "morning", "2024-03-01 09:00:00"),
+            msg("B", "hey", "2024-03-01 09:02:00"),
+            msg(
+                "A",
+                "dinner at 7 <This message was edited>",
+                "2024-03-01 18:00:00",
+            ),
+            msg(
+                "B",
+                "make it 8 <This message was edited>",
+                "2024-03-01 18:01:00",
+            ),
+            msg("A", "fine <This message was edited>", "2024-03-01 18:03:00"),
+            msg("B", "night", "2024-03-01 23:00:00"),
+            msg(
+                "A",
+                "typo fixed <This message was edited>",
+                "2024-03-02 10:00:00",
+            ),
+        ];
+        let edits = edits_per_conversation(&messages, 30);
+        let pairs: Vec<(&str, u32)> = edits.iter().map(|c| (c.label.as_str(), c.value)).collect();
+        assert_eq!(
+            pairs,
+            vec![("2024-03-01 18:00", 3), ("2024-03-02 10:00", 1)]
+        );
+        assert!(edits_per_conversation(&messages[..2], 30).is_empty());
+    }
+
+    #[test]
+    fn revivals_credit_whoever_breaks_the_silence() {
+        let messages = vec![
+            msg("Alice", "first ever", "2024-01-01 10:00:00"),
+            msg("Bob", "hi", "2024-01-01 10:05:00"),
+            // Four days quiet: Bob revives, three messages follow.
+            msg("Bob", "you alive?", "2024-01-05 12:00:00"),
+            msg("Alice", "yes!", "2024-01-05 12:01:00"),
+            msg("Bob", "great", "2024-01-05 12:02:00"),
+            msg("Alice", "so", "2024-01-05 12:03:00"),
+            // A week quiet: Alice revives, nothing follows.
+            msg("Alice", "hello?", "2024-01-12 09:00:00"),
+        ];
+        let revivals = revivals(&messages, 72);
+        assert_eq!(revivals.len(), 2);
+        let find = |name: &str| revivals.iter().find(|r| r.name == name).unwrap();
+        assert_eq!(
+            (find("Bob").count, find("Bob").avg_followup_messages),
+            (1, 3.0)
+        );
+        assert_eq!(
+            (find("Alice").count, find("Alice").avg_followup_messages),
+            (1, 0.0)
+        );
+
+        // With a longer threshold only the week-long silence counts.
+        let weekly = super::revivals(&messages, 24 * 6);
+        assert_eq!(weekly.len(), 1);
+        assert_eq!(weekly[0].name, "Alice");
+    }
+
+    #[test]
+    fn presence_share_favours_the_quiet_regular() {
+        let mut messages = Vec::new();
+        for day in 1..=4 {
+            for minute in 0..5 {
+                let at = format!("2024-01-{day:02} 10:{minute:02}:00");
+                messages.push(msg("Alice", "long story", &at));
+            }
+            messages.push(msg("Quinn", "ok", &format!("2024-01-{day:02} 10:06:00")));
+        }
+        for minute in 0..10 {
+            let at = format!("2024-01-05 20:{minute:02}:00");
+            messages.push(msg("Bob", "rant", &at));
+        }
+
+        let presence = presence_share(&messages, 30);
+        let shares: Vec<(&str, f32)> = presence
+            .iter()
+            .map(|s| (s.label.as_str(), s.value))
+            .collect();
+        assert_eq!(shares, vec![("Alice", 0.8), ("Quinn", 0.8), ("Bob", 0.2)]);
+
+        let by_sender = count_by_sender(&messages);
+        let speech = share_of_speech(&by_sender);
+        // Quinn sent 4 of the 34 messages but was there for everything Alice was.
+        let quinn = speech.iter().find(|s| s.label == "Quinn").unwrap();
+        assert!(quinn.value < 15.0, "{quinn:?}");
+    }
+
+    #[test]
+    fn starter_success_scores_answered_openers() {
+        let messages = vec![
+            // Alice opens twice and Bob always answers.
+            msg("Alice", "hey", "2024-01-01 09:00:00"),
+            msg("Bob", "hi", "2024-01-01 09:05:00"),
+            msg("Alice", "lunch?", "2024-01-02 12:00:00"),
+            msg("Bob", "sure", "2024-01-02 12:01:00"),
+            // Bob opens twice; once into the void.
+            msg("Bob", "anyone?", "2024-01-03 20:00:00"),
+            msg("Bob", "hello??", "2024-01-03 20:10:00"),
+            msg("Bob", "movie tonight", "2024-01-04 18:00:00"),
+            msg("Alice", "yes!", "2024-01-04 18:02:00"),
+        ];
+        let rates = starter_success(&messages, 30, true);
+        assert_eq!(rates[0].label, "Alice");
+        assert_eq!(rates[0].value, 1.0);
+        assert_eq!(rates[1].label, "Bob");
+        assert_eq!(rates[1].value, 0.5);
+    }
+
+    #[test]
+    fn first_message_hour_tracks_a_later_start() {
+        let mut messages = Vec::new();
+        for day in 1..=10 {
+            // Mornings start at 7am in early March and at 11am later in the month.
+            let hour = if day <= 5 { 7 } else { 11 };
+            messages.push(msg(
+                "Alice",
+                "morning",
+                &format!("2024-03-{day:02} {hour:02}:00:00"),
+            ));
+            messages.push(msg(
+                "Bob",
+                "hey",
+                &format!("2024-03-{day:02} {:02}:30:00", hour + 1),
+            ));
+        }
+        messages.push(msg("Bob", "shifted", "2024-04-01 07:00:00"));
+        messages.push(msg("Bob", "later", "2024-04-02 11:00:00"));
+        messages.push(msg("Bob", "still up", "2024-04-03 23:30:00"));
+
+        let monthly = first_message_hour_by_month(&messages);
+        assert_eq!(monthly.len(), 2);
+        assert_eq!(
+            (monthly[0].month.as_str(), monthly[0].days),
+            ("2024-03", 10)
+        );
+        assert_eq!(monthly[0].median_hour, 9.0);
+        // The lone late-night day still counts.
+        assert_eq!(monthly[1].median_hour, 11.0);
+
+        let per_person = first_message_hour_by_person(&messages);
+        assert_eq!(per_person[0].name, "Alice");
+        assert_eq!(per_person[0].monthly[0].median_hour, 9.0);
+        assert_eq!(per_person[1].monthly[0].median_hour, 10.5);
+    }
+
+    #[test]
+    fn language_timeline_follows_a_switch() {
+        let spanish = "pero nosotros estamos muy cansados porque ellos tienen nuestra casa y vosotros también";
+        let english = "but we would have been there if they had told them what their plans were";
+        let mut messages = Vec::new();
+        for day in 1..=6 {
+            messages.push(msg("Ana", spanish, &format!("2021-0{day}-05 10:00:00")));
+            messages.push(msg("Ana", spanish, &format!("2021-0{day}-06 10:00:00")));
+            messages.push(msg("Ana", spanish, &format!("2021-0{day}-07 10:00:00")));
+            messages.push(msg("Ana", spanish, &format!("2021-0{day}-08 10:00:00")));
+        }
+        for month in 7..=9 {
+            for day in 10..=14 {
+                messages.push(msg(
+                    "Ana",
+                    english,
+                    &format!("2021-0{month}-{day} 10:00:00"),
+                ));
+            }
+        }
+        messages.push(msg("Ana", "hola", "2021-10-01 10:00:00"));
+
+        let timeline = language_timeline(&messages);
+        let langs: Vec<&str> = timeline.iter().map(|m| m.top_language.as_str()).collect();
+        assert_eq!(
+            langs,
+            vec![
+                "spanish", "spanish", "spanish", "spanish", "spanish", "spanish", "english",
+                "english", "english", "unknown"
+            ]
+        );
+        assert!(timeline[0].share > 0.5);
+        assert_eq!(timeline[9].share, 0.0);
+    }
+
+    #[test]
+    fn long_messages_lists_pastes_over_the_limit() {
+        let lyrics = "never gonna give you up ".repeat(120);
+        let messages = vec![
+            msg("A", "short reply", "2023-01-01 10:00:00"),
+            msg("B", &lyrics, "2023-01-02 10:00:00"),
+        ];
+        let long = long_messages(&messages, 500);
+        assert_eq!(long.len(), 1);
+        assert_eq!(long[0].sender, "B");
+        assert_eq!(long[0].token_count, 600);
+        assert_eq!(long[0].date, "2023-01-02");
+        assert!(long_messages(&messages, 600).is_empty());
+    }
+
+    #[test]
+    fn split_parts_join_into_the_longest_message() {
+        let messages = vec![
+            msg("A", "one two three four five", "2024-01-01 10:00:00"),
+            msg("A", "six seven eight nine ten", "2024-01-01 10:00:00"),
+            msg("B", "short reply", "2024-01-01 10:00:00"),
+            msg("A", "a new message", "2024-01-01 10:00:01"),
+        ];
+        let joined = join_split_parts(&messages);
+        assert_eq!(joined.len(), 3);
+        assert_eq!(
+            joined[0].text,
+            "one two three four five\nsix seven eight nine ten"
+        );
+
+        let longest = |msgs: &[Message]| {
+            person_stats(msgs)
+                .into_iter()
+                .find(|p| p.name == "A")
+                .unwrap()
+                .longest_message_words
+        };
+        assert_eq!(longest(&messages), 5);
+        assert_eq!(longest(&joined), 10);
+    }
+
+    #[test]
+    fn minute_precision_bursts_stay_separate() {
+        let messages = vec![
+            msg("A", "on my way", "2024-01-01 10:00:00"),
+            msg("A", "ten minutes", "2024-01-01 10:00:00"),
+            msg("B", "ok", "2024-01-01 10:01:00"),
+        ];
+        let joined = join_split_parts(&messages);
+        assert_eq!(joined.len(), 3);
+        assert_eq!(joined[0].text, "on my way");
+    }
+
+    #[test]
+    fn collapse_spam_runs_keeps_short_repeats() {
+        let messages: Vec<Message> = (0..5)
+            .map(|i| msg("A", "lol", &format!("2023-01-01 10:00:{:02}", i)))
+            .collect();
+        let (collapsed, runs) = collapse_spam_runs(&messages);
+        assert_eq!(collapsed.len(), 5);
+        assert!(runs.is_empty());
+    }
+
+    #[test]
+    fn share_of_speech_is_percent_of_messages() {
+        let messages = vec![
+            msg("Alice", "hi", "2023-01-01 10:00:00"),
+            msg("Bob", "hey", "2023-01-01 10:01:00"),
+        ];
+        let shares = share_of_speech(&count_by_sender(&messages));
+        assert_eq!(shares.len(), 2);
+        assert!(shares.iter().all(|s| s.value == 50.0));
+        assert!(share_of_speech(&[]).is_empty());
+    }
+
+    #[test]
+    fn imbalance_flags_one_sided_dms() {
+        let mut messages: Vec<Message> = (0..8)
+            .map(|i| msg("Alice", "hi", &format!("2023-01-01 10:0{i}:00")))
+            .collect();
+        messages.push(msg("Bob", "hey", "2023-01-01 11:00:00"));
+        messages.push(msg("Bob", "hey", "2023-01-01 11:01:00"));
+        let info = imbalance(&share_of_speech(&count_by_sender(&messages))).unwrap();
+        assert_eq!((info.dominant.as_str(), info.percent), ("Alice", 80.0));
+
+        // Even split: no flag. Three people: never flagged.
+        let even = [
+            msg("Alice", "hi", "2023-01-01 10:00:00"),
+            msg("Bob", "hi", "2023-01-01 10:01:00"),
+        ];
+        assert!(imbalance(&share_of_speech(&count_by_sender(&even))).is_none());
+        messages.push(msg("Carol", "hello", "2023-01-01 12:00:00"));
+        assert!(imbalance(&share_of_speech(&count_by_sender(&messages))).is_none());
+    }
+
     #[test]
     fn conversation_initiations_empty() {
-        let (items, count) = conversation_initiations(&[], 30);
+        let (items, count) = conversation_initiations(&[], 30, true);
         assert!(items.is_empty());
         assert_eq!(count, 0);
     }
@@ -723,10 +3527,32 @@ mod tests {
             msg("Bob", "new topic", "2023-01-01 11:00:01"),
             msg("Alice", "reply", "2023-01-01 11:05:00"),
         ];
-        let (items, count) = conversation_initiations(&messages, 30);
+        let (items, count) = conversation_initiations(&messages, 30, true);
+        assert_eq!(count, 2);
+        let map: HashMap<_, _> = items.iter().map(|c| (c.label.as_str(), c.value)).collect();
+        assert_eq!(map.get("Alice"), Some(&1));
+        assert_eq!(map.get("Bob"), Some(&1));
+    }
+
+    #[test]
+    fn conversation_initiations_skip_placeholders() {
+        let messages = vec![
+            msg("Alice", "morning", "2023-01-01 08:00:00"),
+            // A photo opens the next conversation; Bob's text gets the credit.
+            msg("Alice", "<Media omitted>", "2023-01-01 09:00:00"),
+            msg("Bob", "cute dog!", "2023-01-01 09:20:00"),
+            // The photo still ended the gap, so this is the same conversation.
+            msg("Alice", "right?", "2023-01-01 09:45:00"),
+        ];
+        let (items, count) = conversation_initiations(&messages, 30, true);
         assert_eq!(count, 2);
         let map: HashMap<_, _> = items.iter().map(|c| (c.label.as_str(), c.value)).collect();
         assert_eq!(map.get("Alice"), Some(&1));
         assert_eq!(map.get("Bob"), Some(&1));
+
+        let (legacy, _) = conversation_initiations(&messages, 30, false);
+        let map: HashMap<_, _> = legacy.iter().map(|c| (c.label.as_str(), c.value)).collect();
+        assert_eq!(map.get("Alice"), Some(&2));
+        assert_eq!(map.get("Bob"), None);
     }
 }
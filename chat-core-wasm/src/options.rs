@@ -0,0 +1,598 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+use crate::error::AnalyzeError;
+use crate::filters::TextFilters;
+use crate::journey::JOURNEY_LENGTH_BONUS_CAP;
+use crate::parsing::compile_line_regex;
+use crate::sentiment::EmojiWeights;
+use crate::text::{CONVERSATION_GAP_MINUTES, LONG_MESSAGE_TOKENS};
+
+/// Optional knobs for [`crate::summarize_with_options`]. Every field defaults to the
+/// behaviour of plain [`crate::summarize`], so callers only set what they need.
+#[derive(Debug, Clone, Deserialize, Tsify)]
+#[tsify(from_wasm_abi)]
+#[serde(default)]
+pub struct AnalyzeOptions {
+    /// Replace sender names with stable pseudonyms ("Person A", "Person B", ...) ranked
+    /// by message count. Applied right after parsing so every metric sees the pseudonyms.
+    pub anonymize: bool,
+    /// Which hemisphere's meteorological seasons to use for the seasonal profile.
+    pub hemisphere: Hemisphere,
+    /// Words or short phrases to count verbatim. Matching ignores the stopword list, so
+    /// tracking "you" or "love you" works even though "you" is normally filtered.
+    pub track_keywords: Vec<String>,
+    /// Named word lists (`("food", ["pizza", "sushi"])`) whose hits are added up per
+    /// theme in `Summary::theme_counts`. Words match like `track_keywords`.
+    pub theme_clusters: Vec<(String, Vec<String>)>,
+    /// Preprocessing applied in order to message text before word, phrase, emoji and
+    /// sentiment analysis. Message counts and the journey still see the original text.
+    pub text_filters: Vec<TextFilter>,
+    /// Drop numbers, dates and number+unit tokens ("2023", "7pm", "3rd") from top words
+    /// and word clouds. Phrase mining still sees them. Defaults to `true`.
+    pub filter_numeric_tokens: bool,
+    /// Minimum messages a week needs to extend the weekly streak. Defaults to 10.
+    pub weekly_streak_threshold: u32,
+    /// Bucket size for `Summary::timeline`, `day` by default. `auto` picks days for chats
+    /// up to 400 days, weeks up to three years and months beyond.
+    pub timeline_granularity: TimelineGranularity,
+    /// Fill `Summary::per_person_emoji_monthly` with each person's top emojis per month.
+    /// Off by default because it grows with people x months in large groups.
+    pub per_person_emoji_monthly: bool,
+    /// Fill `Summary::top_phrase_examples` with the first message each top phrase
+    /// appeared in. Off by default since it copies whole messages into the summary.
+    pub phrase_examples: bool,
+    /// Fill `Summary::sentiment_series` with every message's sentiment score. Off by
+    /// default: it adds an entry per message to the payload.
+    pub per_message_sentiment: bool,
+    /// Sentiment weights from -1 to 1 for specific emoji (`("💀", 1.0)` when it means
+    /// laughing), used in place of the built-in emoji polarity for every sentiment metric.
+    pub emoji_sentiment: Vec<(String, f32)>,
+    /// Join consecutive messages from one sender stamped with the same second, the way
+    /// very long messages sometimes export, before word and length stats and fun facts.
+    /// Exports with minute-precision timestamps are left as they are.
+    pub join_split_messages: bool,
+    /// Messages with more tokens than this (pasted lyrics, articles) only feed their
+    /// first `long_message_tokens` tokens to phrase mining, count each phrase once, earn
+    /// no journey length bonus and are listed in `Summary::long_messages`. Defaults to 500.
+    pub long_message_tokens: usize,
+    /// Ceiling on the length bonus a message gets when picking journey moments.
+    /// Defaults to 3.5.
+    pub journey_length_bonus_cap: f32,
+    /// How the journey decides which sender is "you". Defaults to
+    /// [`SelfStrategy::DeletedHeuristic`].
+    pub self_strategy: SelfStrategy,
+    /// Let a photo, deleted message or call record that opens a conversation earn the
+    /// starter credit, as older versions did. By default the first real message does.
+    pub credit_placeholder_starters: bool,
+    /// Silence, in minutes, that ends one conversation and starts the next for the
+    /// conversation count, starters, per-conversation stats and the journey's first and
+    /// last exchanges. Defaults to 30.
+    pub conversation_gap_minutes: u32,
+    /// Silence, in hours, after which the next message counts as reviving a dead thread
+    /// in `Summary::revivals`. Defaults to 72.
+    pub revival_gap_hours: u32,
+    /// Dates to report on in `Summary::special_days` (birthdays, anniversaries).
+    pub special_dates: Vec<SpecialDate>,
+    /// Inclusive `("YYYY-MM-DD", "YYYY-MM-DD")` ranges (a trip, a lost phone) whose
+    /// silent days don't break the chat's or a person's daily streak. They don't add to
+    /// it either.
+    pub streak_ignore_ranges: Vec<(String, String)>,
+    /// Extra message header pattern for non-standard exports, tried after the built-in
+    /// formats. Needs the named groups `date`, `time`, `name` and `msg`; dates and
+    /// times must still use a WhatsApp layout (`13/01/2024`, `21:05` or `9:05 PM`).
+    pub custom_line_regex: Option<String>,
+    /// Hard caps that stop a huge or malformed file from tying up the analysis.
+    pub limits: Limits,
+    /// Quick preview for huge chats: run phrases, sentiment and the journey on this
+    /// fraction (0-1] of messages, sampled per month. Counts still see every message;
+    /// `Summary::sampled` lists the affected fields.
+    pub sample_fraction: Option<f32>,
+    /// Seed for `sample_fraction`; the same seed samples the same messages.
+    pub sample_seed: u32,
+}
+
+/// Size caps for [`AnalyzeOptions::limits`]. The defaults are far above any real
+/// export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Tsify)]
+#[serde(default)]
+pub struct Limits {
+    /// Largest accepted txt input, in bytes. Defaults to 512 MiB.
+    pub max_bytes: usize,
+    /// Most messages analyzed after parsing. Defaults to 5,000,000.
+    pub max_messages: usize,
+    /// Lines longer than this many bytes are cut short (and reported in
+    /// `Summary::warnings`) instead of being tokenized in full. Defaults to 64 KiB.
+    pub max_line_length: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_bytes: 512 * 1024 * 1024,
+            max_messages: 5_000_000,
+            max_line_length: 64 * 1024,
+        }
+    }
+}
+
+/// A labelled calendar date, `"YYYY-MM-DD"`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Tsify)]
+pub struct SpecialDate {
+    pub date: String,
+    pub label: String,
+}
+
+impl Default for AnalyzeOptions {
+    fn default() -> Self {
+        AnalyzeOptions {
+            anonymize: false,
+            hemisphere: Hemisphere::default(),
+            track_keywords: Vec::new(),
+            theme_clusters: Vec::new(),
+            text_filters: Vec::new(),
+            filter_numeric_tokens: true,
+            weekly_streak_threshold: 10,
+            timeline_granularity: TimelineGranularity::default(),
+            per_person_emoji_monthly: false,
+            phrase_examples: false,
+            per_message_sentiment: false,
+            emoji_sentiment: Vec::new(),
+            join_split_messages: false,
+            long_message_tokens: LONG_MESSAGE_TOKENS,
+            journey_length_bonus_cap: JOURNEY_LENGTH_BONUS_CAP,
+            self_strategy: SelfStrategy::default(),
+            credit_placeholder_starters: false,
+            conversation_gap_minutes: CONVERSATION_GAP_MINUTES as u32,
+            revival_gap_hours: 72,
+            special_dates: Vec::new(),
+            streak_ignore_ranges: Vec::new(),
+            custom_line_regex: None,
+            limits: Limits::default(),
+            sample_fraction: None,
+            sample_seed: 0,
+        }
+    }
+}
+
+impl AnalyzeOptions {
+    /// Starts from the defaults; see [`AnalyzeOptionsBuilder`].
+    ///
+    /// ```
+    /// use chat_core_wasm::AnalyzeOptions;
+    ///
+    /// let options = AnalyzeOptions::builder()
+    ///     .anonymize(true)
+    ///     .weekly_streak_threshold(5)
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(options.anonymize);
+    /// assert!(AnalyzeOptions::builder().long_message_tokens(0).build().is_err());
+    /// ```
+    pub fn builder() -> AnalyzeOptionsBuilder {
+        AnalyzeOptionsBuilder::default()
+    }
+
+    /// Checks every field, including that text filter patterns compile. Options built
+    /// by hand or deserialized from JS go through this before any analysis runs.
+    pub fn validate(&self) -> Result<(), AnalyzeError> {
+        let invalid = |field, reason: &str| AnalyzeError::InvalidOption {
+            field,
+            reason: reason.to_string(),
+        };
+        if self.weekly_streak_threshold == 0 {
+            return Err(invalid("weekly_streak_threshold", "must be at least 1"));
+        }
+        if self.conversation_gap_minutes == 0 {
+            return Err(invalid("conversation_gap_minutes", "must be at least 1"));
+        }
+        if self.revival_gap_hours == 0 {
+            return Err(invalid("revival_gap_hours", "must be at least 1"));
+        }
+        if self.long_message_tokens == 0 {
+            return Err(invalid("long_message_tokens", "must be at least 1"));
+        }
+        if !self.journey_length_bonus_cap.is_finite() || self.journey_length_bonus_cap < 0.0 {
+            return Err(invalid(
+                "journey_length_bonus_cap",
+                "must be a non-negative number",
+            ));
+        }
+        if let Some(bad) = self
+            .special_dates
+            .iter()
+            .find(|d| NaiveDate::parse_from_str(&d.date, "%Y-%m-%d").is_err())
+        {
+            return Err(AnalyzeError::InvalidOption {
+                field: "special_dates",
+                reason: format!("'{}' is not a YYYY-MM-DD date", bad.date),
+            });
+        }
+        for (start, end) in &self.streak_ignore_ranges {
+            let parse = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d");
+            match (parse(start), parse(end)) {
+                (Ok(s), Ok(e)) if s <= e => {}
+                (Ok(_), Ok(_)) => {
+                    return Err(AnalyzeError::InvalidOption {
+                        field: "streak_ignore_ranges",
+                        reason: format!("'{start}' is after '{end}'"),
+                    })
+                }
+                _ => {
+                    return Err(AnalyzeError::InvalidOption {
+                        field: "streak_ignore_ranges",
+                        reason: format!("'{start}'..'{end}' is not a YYYY-MM-DD range"),
+                    })
+                }
+            }
+        }
+        for (field, value) in [
+            ("limits.max_bytes", self.limits.max_bytes),
+            ("limits.max_messages", self.limits.max_messages),
+            ("limits.max_line_length", self.limits.max_line_length),
+        ] {
+            if value == 0 {
+                return Err(invalid(field, "must be at least 1"));
+            }
+        }
+        if let SelfStrategy::Explicit { name } = &self.self_strategy {
+            if name.trim().is_empty() {
+                return Err(invalid("self_strategy", "explicit name must not be empty"));
+            }
+        }
+        if let Some(fraction) = self.sample_fraction {
+            if !(fraction > 0.0 && fraction <= 1.0) {
+                return Err(invalid("sample_fraction", "must be above 0 and at most 1"));
+            }
+        }
+        for (emoji, weight) in &self.emoji_sentiment {
+            if emoji.trim().is_empty() {
+                return Err(invalid("emoji_sentiment", "emoji must not be empty"));
+            }
+            if !(-1.0..=1.0).contains(weight) {
+                return Err(AnalyzeError::InvalidOption {
+                    field: "emoji_sentiment",
+                    reason: format!("weight for '{emoji}' must be between -1 and 1"),
+                });
+            }
+        }
+        if let Some(pattern) = &self.custom_line_regex {
+            compile_line_regex(pattern).map_err(|reason| invalid("custom_line_regex", &reason))?;
+        }
+        TextFilters::compile(&self.text_filters)
+            .map_err(|reason| invalid("text_filters", &reason))?;
+        Ok(())
+    }
+
+    /// `streak_ignore_ranges` as dates; ranges that fail to parse are skipped, which
+    /// only happens for options that were never validated.
+    pub(crate) fn streak_ignore_dates(&self) -> Vec<(NaiveDate, NaiveDate)> {
+        let parse = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok();
+        self.streak_ignore_ranges
+            .iter()
+            .filter_map(|(start, end)| Some((parse(start)?, parse(end)?)))
+            .collect()
+    }
+
+    /// `emoji_sentiment` as a lookup; a later entry for the same emoji wins.
+    pub(crate) fn emoji_weights(&self) -> EmojiWeights {
+        self.emoji_sentiment.iter().cloned().collect()
+    }
+
+    /// Reads options from a plain JS object (missing fields take their defaults) and
+    /// validates them.
+    pub fn from_js_value(value: JsValue) -> Result<AnalyzeOptions, AnalyzeError> {
+        let options: AnalyzeOptions =
+            serde_wasm_bindgen::from_value(value).map_err(|e| AnalyzeError::InvalidOption {
+                field: "options",
+                reason: e.to_string(),
+            })?;
+        options.validate()?;
+        Ok(options)
+    }
+}
+
+/// Chainable setters over [`AnalyzeOptions::default`]; `build` validates the result.
+#[derive(Debug, Clone, Default)]
+pub struct AnalyzeOptionsBuilder {
+    options: AnalyzeOptions,
+}
+
+impl AnalyzeOptionsBuilder {
+    pub fn anonymize(mut self, anonymize: bool) -> Self {
+        self.options.anonymize = anonymize;
+        self
+    }
+
+    pub fn hemisphere(mut self, hemisphere: Hemisphere) -> Self {
+        self.options.hemisphere = hemisphere;
+        self
+    }
+
+    pub fn track_keywords(mut self, keywords: Vec<String>) -> Self {
+        self.options.track_keywords = keywords;
+        self
+    }
+
+    pub fn theme_cluster(mut self, theme: &str, words: &[&str]) -> Self {
+        let words = words.iter().map(|w| w.to_string()).collect();
+        self.options.theme_clusters.push((theme.to_string(), words));
+        self
+    }
+
+    pub fn text_filter(mut self, filter: TextFilter) -> Self {
+        self.options.text_filters.push(filter);
+        self
+    }
+
+    pub fn filter_numeric_tokens(mut self, filter: bool) -> Self {
+        self.options.filter_numeric_tokens = filter;
+        self
+    }
+
+    pub fn weekly_streak_threshold(mut self, threshold: u32) -> Self {
+        self.options.weekly_streak_threshold = threshold;
+        self
+    }
+
+    pub fn timeline_granularity(mut self, granularity: TimelineGranularity) -> Self {
+        self.options.timeline_granularity = granularity;
+        self
+    }
+
+    pub fn per_person_emoji_monthly(mut self, enabled: bool) -> Self {
+        self.options.per_person_emoji_monthly = enabled;
+        self
+    }
+
+    pub fn phrase_examples(mut self, enabled: bool) -> Self {
+        self.options.phrase_examples = enabled;
+        self
+    }
+
+    pub fn per_message_sentiment(mut self, enabled: bool) -> Self {
+        self.options.per_message_sentiment = enabled;
+        self
+    }
+
+    pub fn emoji_sentiment(mut self, emoji: &str, weight: f32) -> Self {
+        self.options
+            .emoji_sentiment
+            .push((emoji.to_string(), weight));
+        self
+    }
+
+    pub fn join_split_messages(mut self, enabled: bool) -> Self {
+        self.options.join_split_messages = enabled;
+        self
+    }
+
+    pub fn long_message_tokens(mut self, tokens: usize) -> Self {
+        self.options.long_message_tokens = tokens;
+        self
+    }
+
+    pub fn journey_length_bonus_cap(mut self, cap: f32) -> Self {
+        self.options.journey_length_bonus_cap = cap;
+        self
+    }
+
+    pub fn self_strategy(mut self, strategy: SelfStrategy) -> Self {
+        self.options.self_strategy = strategy;
+        self
+    }
+
+    pub fn credit_placeholder_starters(mut self, credit: bool) -> Self {
+        self.options.credit_placeholder_starters = credit;
+        self
+    }
+
+    pub fn conversation_gap_minutes(mut self, minutes: u32) -> Self {
+        self.options.conversation_gap_minutes = minutes;
+        self
+    }
+
+    pub fn revival_gap_hours(mut self, hours: u32) -> Self {
+        self.options.revival_gap_hours = hours;
+        self
+    }
+
+    pub fn special_date(mut self, date: &str, label: &str) -> Self {
+        self.options.special_dates.push(SpecialDate {
+            date: date.to_string(),
+            label: label.to_string(),
+        });
+        self
+    }
+
+    pub fn streak_ignore_range(mut self, start: &str, end: &str) -> Self {
+        self.options
+            .streak_ignore_ranges
+            .push((start.to_string(), end.to_string()));
+        self
+    }
+
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.options.limits = limits;
+        self
+    }
+
+    pub fn custom_line_regex(mut self, pattern: &str) -> Self {
+        self.options.custom_line_regex = Some(pattern.to_string());
+        self
+    }
+
+    pub fn sample(mut self, fraction: f32, seed: u32) -> Self {
+        self.options.sample_fraction = Some(fraction);
+        self.options.sample_seed = seed;
+        self
+    }
+
+    pub fn build(self) -> Result<AnalyzeOptions, AnalyzeError> {
+        self.options.validate()?;
+        Ok(self.options)
+    }
+}
+
+/// Built-in text preprocessing steps for [`AnalyzeOptions::text_filters`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Tsify)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TextFilter {
+    /// Drops lines starting with `>` (quoted replies).
+    StripQuotedLines,
+    /// Drops fenced ```code blocks``` and inline `code` spans.
+    StripCodeBlocks,
+    /// Replaces every match of `pattern` with `replacement` (`$1` expands groups).
+    /// Patterns are length-limited and at most a handful are accepted.
+    RegexReplace {
+        pattern: String,
+        replacement: String,
+    },
+}
+
+/// Who is "you" in [`AnalyzeOptions::self_strategy`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Tsify)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SelfStrategy {
+    /// The sender of the first "You deleted this message", else the least active one.
+    #[default]
+    DeletedHeuristic,
+    /// The sender with the fewest messages.
+    LeastActive,
+    /// The sender with the most messages.
+    MostActive,
+    /// This exact sender name; if nobody has it, no message is marked as yours.
+    Explicit { name: String },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Tsify)]
+#[serde(rename_all = "lowercase")]
+pub enum TimelineGranularity {
+    Auto,
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Tsify)]
+#[serde(rename_all = "lowercase")]
+pub enum Hemisphere {
+    #[default]
+    Northern,
+    Southern,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_defaults_match_default() {
+        let built = AnalyzeOptions::builder().build().unwrap();
+        let default = AnalyzeOptions::default();
+        assert_eq!(
+            built.weekly_streak_threshold,
+            default.weekly_streak_threshold
+        );
+        assert_eq!(built.long_message_tokens, default.long_message_tokens);
+        assert!(built.filter_numeric_tokens);
+    }
+
+    #[test]
+    fn builder_sets_fields() {
+        let options = AnalyzeOptions::builder()
+            .hemisphere(Hemisphere::Southern)
+            .timeline_granularity(TimelineGranularity::Week)
+            .text_filter(TextFilter::StripQuotedLines)
+            .track_keywords(vec!["love you".into()])
+            .theme_cluster("food", &["pizza", "sushi"])
+            .build()
+            .unwrap();
+        assert_eq!(options.hemisphere, Hemisphere::Southern);
+        assert_eq!(options.timeline_granularity, TimelineGranularity::Week);
+        assert_eq!(options.text_filters, vec![TextFilter::StripQuotedLines]);
+        assert_eq!(options.track_keywords, vec!["love you".to_string()]);
+        assert_eq!(
+            options.theme_clusters,
+            vec![(
+                "food".to_string(),
+                vec!["pizza".to_string(), "sushi".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn invalid_options_name_the_field() {
+        let field = |builder: AnalyzeOptionsBuilder| match builder.build() {
+            Err(AnalyzeError::InvalidOption { field, .. }) => field,
+            other => panic!("expected an invalid option, got {other:?}"),
+        };
+        assert_eq!(
+            field(AnalyzeOptions::builder().weekly_streak_threshold(0)),
+            "weekly_streak_threshold"
+        );
+        assert_eq!(
+            field(AnalyzeOptions::builder().long_message_tokens(0)),
+            "long_message_tokens"
+        );
+        assert_eq!(
+            field(AnalyzeOptions::builder().journey_length_bonus_cap(f32::NAN)),
+            "journey_length_bonus_cap"
+        );
+        assert_eq!(
+            field(AnalyzeOptions::builder().anonymize(true).text_filter(
+                TextFilter::RegexReplace {
+                    pattern: "(unclosed".into(),
+                    replacement: String::new(),
+                }
+            )),
+            "text_filters"
+        );
+        assert_eq!(
+            field(AnalyzeOptions::builder().special_date("14/02/2024", "Valentine's")),
+            "special_dates"
+        );
+        assert_eq!(
+            field(
+                AnalyzeOptions::builder()
+                    .self_strategy(SelfStrategy::Explicit { name: " ".into() })
+            ),
+            "self_strategy"
+        );
+        assert_eq!(
+            field(AnalyzeOptions::builder().conversation_gap_minutes(0)),
+            "conversation_gap_minutes"
+        );
+        assert_eq!(
+            field(AnalyzeOptions::builder().revival_gap_hours(0)),
+            "revival_gap_hours"
+        );
+        assert_eq!(
+            field(AnalyzeOptions::builder().streak_ignore_range("2024-03-10", "2024-03-01")),
+            "streak_ignore_ranges"
+        );
+        assert_eq!(
+            field(AnalyzeOptions::builder().custom_line_regex(r"^(?P<msg>.*)$")),
+            "custom_line_regex"
+        );
+        assert_eq!(
+            field(AnalyzeOptions::builder().limits(Limits {
+                max_messages: 0,
+                ..Limits::default()
+            })),
+            "limits.max_messages"
+        );
+        assert_eq!(
+            field(AnalyzeOptions::builder().sample(1.5, 42)),
+            "sample_fraction"
+        );
+        assert_eq!(
+            field(AnalyzeOptions::builder().emoji_sentiment("💀", 2.0)),
+            "emoji_sentiment"
+        );
+        assert!(AnalyzeOptions::builder().sample(0.1, 42).build().is_ok());
+    }
+}
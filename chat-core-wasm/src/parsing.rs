@@ -1,12 +1,44 @@
 use chrono::{Datelike, NaiveDateTime};
 use once_cell::sync::OnceCell;
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::types::ParsedMessage;
 
 #[derive(Debug, Clone)]
 pub(crate) struct Message {
     pub(crate) dt: NaiveDateTime,
     pub(crate) sender: String,
     pub(crate) text: String,
+    /// 1-based line of the message header in the raw input; it breaks ties between
+    /// messages that share a (minute-precision) timestamp. When several exports are
+    /// concatenated into one input before parsing it also keeps their file order, but
+    /// exports parsed separately and combined with `Summary::merge` get no such tie-break.
+    pub(crate) line: usize,
+}
+
+impl Message {
+    /// Chronological sort key that is total, so every sort site agrees on the order of
+    /// same-timestamp messages.
+    pub(crate) fn order_key(&self) -> (NaiveDateTime, usize) {
+        (self.dt, self.line)
+    }
+}
+
+/// `messages` in `Message::order_key` order: borrowed when they already are, which is
+/// the case for everything `summarize` hands out since it sorts once up front, else a
+/// sorted copy. Every chronological metric reads through this so they all see the same
+/// sequence and agree on which message opened a conversation.
+pub(crate) fn sorted_view(messages: &[Message]) -> Cow<'_, [Message]> {
+    if messages.is_sorted_by_key(Message::order_key) {
+        Cow::Borrowed(messages)
+    } else {
+        let mut sorted = messages.to_vec();
+        sorted.sort_by_key(Message::order_key);
+        Cow::Owned(sorted)
+    }
 }
 
 fn re_bracket() -> &'static Regex {
@@ -18,20 +50,103 @@ fn re_bracket() -> &'static Regex {
     })
 }
 
+/// Android-style headers. Re-exported files may use tabs or runs of spaces between the
+/// fields and an en dash in place of the hyphen.
 fn re_hyphen() -> &'static Regex {
     static RE: OnceCell<Regex> = OnceCell::new();
     RE.get_or_init(|| {
         // SAFE: compile-time-constant pattern, validated by tests; never depends on user input.
-        Regex::new(r"^(?P<date>\d{1,2}[\/.]\d{1,2}[\/.]\d{2,4}),\s+(?P<time>\d{1,2}:\d{2}(?::\d{2})?(?:\s*[AP]M)?)\s+-\s+(?P<name>[^:]+):\s+(?P<msg>.*)$")
+        Regex::new(r"^[\u{feff}\u{200e}]?(?P<date>\d{1,2}[\/.]\d{1,2}[\/.]\d{2,4}),\s+(?P<time>\d{1,2}:\d{2}(?::\d{2})?(?:\s*[AaPp]\.?\s*[Mm]\.?)?)\s+[-\u{2013}]\s+(?P<name>[^:]+):\s+(?P<msg>.*)$")
+            .expect("valid regex")
+    })
+}
+
+/// Channel and broadcast exports have bracketed headers with no "Name:" segment.
+fn re_senderless() -> &'static Regex {
+    static RE: OnceCell<Regex> = OnceCell::new();
+    RE.get_or_init(|| {
+        // SAFE: compile-time-constant pattern, validated by tests; never depends on user input.
+        Regex::new(r"^[\u{feff}\u{200e}]?\[(?P<date>\d{1,2}[\/.]\d{1,2}[\/.]\d{2,4}),\s+(?P<time>[^\]]+)\]\s+(?P<msg>\S.*)$")
+            .expect("valid regex")
+    })
+}
+
+/// A line that starts like a timestamped message but matched none of the header
+/// patterns above, e.g. an ISO-dated line pasted in from another export.
+fn re_foreign_header() -> &'static Regex {
+    static RE: OnceCell<Regex> = OnceCell::new();
+    RE.get_or_init(|| {
+        // SAFE: compile-time-constant pattern, validated by tests; never depends on user input.
+        Regex::new(r"^[\u{feff}\u{200e}]?\[?\d{1,4}[\/.-]\d{1,2}[\/.-]\d{1,4},?\s+\d{1,2}[:.]\d{2}")
             .expect("valid regex")
     })
 }
 
+/// What the parser kept and dropped, for `Summary::warnings`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct ParseReport {
+    /// Non-blank lines in the input.
+    pub(crate) lines: usize,
+    /// Lines not attached to any message: text before the first header, headers with
+    /// impossible dates and the continuation lines that followed them.
+    pub(crate) dropped_lines: usize,
+    /// Continuation lines that look like a message header in an unsupported format.
+    /// They are still appended to the previous message.
+    pub(crate) foreign_header_lines: usize,
+    /// Messages whose date had a 2-digit year (expanded to 20xx).
+    pub(crate) two_digit_years: usize,
+    /// Structured input entries (`InputMessage`) without a usable timestamp or sender.
+    pub(crate) skipped_entries: usize,
+    /// Sender spellings folded together by `merge_sender_variants`.
+    pub(crate) merged_senders: Vec<(String, Vec<String>)>,
+    /// Lines cut short at `Limits::max_line_length`.
+    pub(crate) truncated_lines: usize,
+}
+
+impl ParseReport {
+    /// Renames merged senders to their aliases and drops the merged spellings, which are
+    /// real names too, so the report can be shown for an anonymized summary.
+    pub(crate) fn anonymize(&mut self, aliases: &HashMap<String, String>) {
+        for (kept, others) in &mut self.merged_senders {
+            if let Some(alias) = aliases.get(kept.as_str()) {
+                kept.clone_from(alias);
+            }
+            others.clear();
+        }
+    }
+}
+
+/// Sender assigned to messages from senderless (channel/broadcast) headers.
+pub(crate) const CHANNEL_SENDER: &str = "Channel";
+
+/// Later years are treated as corrupted input rather than real messages; a bogus
+/// far-future date would otherwise stretch every gap-filled timeline across millennia.
+pub(crate) const MAX_PLAUSIBLE_YEAR: i32 = 2100;
+
+/// Rewrites a trailing meridiem such as `"A. M."` or `"P.\u{a0}M."` (Spanish-language
+/// exports) as plain `"AM"`/`"PM"` so `%p` can match it. Expects uppercased input.
+fn normalize_meridiem(time: &str) -> String {
+    let Some(split) = time.find(|c: char| c.is_alphabetic()) else {
+        return time.to_string();
+    };
+    let meridiem: String = time[split..]
+        .chars()
+        .filter(|c| *c != '.' && !c.is_whitespace())
+        .collect();
+    if meridiem == "AM" || meridiem == "PM" {
+        format!("{} {meridiem}", time[..split].trim_end())
+    } else {
+        time.to_string()
+    }
+}
+
 pub(crate) fn parse_timestamp(date: &str, time: &str) -> Option<NaiveDateTime> {
-    let cleaned = time
-        .replace(['\u{202f}', '\u{00a0}'], " ")
-        .trim()
-        .to_uppercase();
+    let cleaned = normalize_meridiem(
+        time.replace(['\u{202f}', '\u{00a0}'], " ")
+            .trim()
+            .to_uppercase()
+            .as_str(),
+    );
 
     let prefer_month_first = if date.contains('/') {
         let mut parts = date.split('/');
@@ -123,35 +238,234 @@ pub(crate) fn parse_timestamp(date: &str, time: &str) -> Option<NaiveDateTime> {
                     Some(dt)
                 }
             })
+            .filter(|dt| dt.year() <= MAX_PLAUSIBLE_YEAR)
     })
 }
 
+/// Fewest digits for a sender to be treated as a phone number rather than a name.
+const MIN_PHONE_DIGITS: usize = 7;
+
 fn clean_sender(name: &str) -> String {
-    name.trim_matches(|c: char| {
-        c.is_whitespace() || matches!(c, '\u{feff}' | '\u{200e}' | '\u{200f}')
-    })
-    .chars()
-    .filter(|c| {
-        !c.is_control()
-            && !matches!(
-                *c,
-                '\u{202a}'
-                    | '\u{202b}'
-                    | '\u{202c}'
-                    | '\u{202d}'
-                    | '\u{202e}'
-                    | '\u{202f}'
-                    | '\u{2060}'
-                    | '\u{2066}'
-                    | '\u{2067}'
-                    | '\u{2068}'
-                    | '\u{2069}'
-            )
+    let cleaned = strip_sender_marks(name);
+    normalize_phone_sender(&cleaned).unwrap_or(cleaned)
+}
+
+/// Contacts missing from the address book show up as their number, formatted
+/// differently across export versions ("+1 555 123 4567", "+1-555-123-4567"). Keeps the
+/// digits and a leading `+` so each contact stays one sender.
+fn normalize_phone_sender(name: &str) -> Option<String> {
+    let body = name.strip_prefix('+').unwrap_or(name);
+    if !body.starts_with(|c: char| c.is_ascii_digit() || c == '(')
+        || !body
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, ' ' | '\u{a0}' | '-' | '.' | '(' | ')'))
+    {
+        return None;
+    }
+    let digits: String = body.chars().filter(|c| c.is_ascii_digit()).collect();
+    if digits.len() < MIN_PHONE_DIGITS {
+        return None;
+    }
+    Some(if name.starts_with('+') {
+        format!("+{digits}")
+    } else {
+        digits
     })
-    .collect()
 }
 
-fn filter_system_messages(messages: Vec<Message>) -> Vec<Message> {
+/// Invisible format characters (zero-width spaces and joiners, direction marks, word
+/// joiners, soft hyphens) that may surround a sender name.
+fn is_format_char(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00ad}'
+            | '\u{200b}'..='\u{200f}'
+            | '\u{202a}'..='\u{202e}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{feff}'
+    )
+}
+
+/// Format characters kept inside a name, since emoji sequences and some scripts need
+/// the zero-width non-joiner and joiner.
+fn is_name_joiner(c: char) -> bool {
+    matches!(c, '\u{200c}' | '\u{200d}')
+}
+
+/// Trims whitespace and format characters around a sender name and drops control and
+/// format characters inside it, joiners excepted.
+fn strip_sender_marks(name: &str) -> String {
+    name.trim_matches(|c: char| c.is_whitespace() || is_format_char(c))
+        .chars()
+        .filter(|&c| !c.is_control() && (!is_format_char(c) || is_name_joiner(c)))
+        .collect()
+}
+
+/// Comparison key for sender names: NFKC-normalized (so full-width letters and composed
+/// or decomposed accents agree), lowercased, whitespace runs collapsed to one space.
+fn sender_key(name: &str) -> String {
+    name.nfkc()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Merges senders whose names differ only by case, spacing or Unicode normalization
+/// (exports from different phones). Each group takes its most frequent spelling, alphabetically first on a
+/// tie. Returns `(kept, merged variants)` per group that had more than one spelling.
+pub(crate) fn merge_sender_variants(messages: &mut [Message]) -> Vec<(String, Vec<String>)> {
+    let mut spellings: HashMap<String, HashMap<&str, u32>> = HashMap::new();
+    for m in messages.iter() {
+        *spellings
+            .entry(sender_key(&m.sender))
+            .or_default()
+            .entry(m.sender.as_str())
+            .or_insert(0) += 1;
+    }
+
+    let mut merges: Vec<(String, Vec<String>)> = spellings
+        .into_values()
+        .filter(|variants| variants.len() > 1)
+        .map(|variants| {
+            let mut ranked: Vec<(&str, u32)> = variants.into_iter().collect();
+            ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            let others = ranked[1..]
+                .iter()
+                .map(|(name, _)| name.to_string())
+                .collect();
+            (ranked[0].0.to_string(), others)
+        })
+        .collect();
+    merges.sort();
+
+    let renames: HashMap<String, String> = merges
+        .iter()
+        .flat_map(|(kept, others)| others.iter().map(move |o| (o.clone(), kept.clone())))
+        .collect();
+    for m in messages.iter_mut() {
+        if let Some(kept) = renames.get(&m.sender) {
+            m.sender = kept.clone();
+        }
+    }
+    merges
+}
+
+/// Parses `raw` into the public message DTO, in export order.
+pub fn parse_only(raw: &str) -> Vec<ParsedMessage> {
+    parse_messages(raw)
+        .into_iter()
+        .map(|m| ParsedMessage {
+            timestamp: m.dt.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            sender: m.sender,
+            text: m.text,
+        })
+        .collect()
+}
+
+/// Rewrites every sender to "Person A", "Person B", ... by descending message count
+/// (ties broken alphabetically so the mapping is stable across runs). Names typed in
+/// message text are replaced too: a sender's full name, and each word of it that no
+/// other sender shares ("Alice" for "Alice Smith"), matched as whole words ignoring case.
+/// Returns the alias of each real name.
+pub(crate) fn anonymize_senders(messages: &mut [Message]) -> HashMap<String, String> {
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    for m in messages.iter() {
+        *counts.entry(m.sender.clone()).or_insert(0) += 1;
+    }
+
+    let mut ranked: Vec<(String, u32)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let aliases: HashMap<String, String> = ranked
+        .into_iter()
+        .enumerate()
+        .map(|(rank, (name, _))| (name, format!("Person {}", pseudonym_letters(rank))))
+        .collect();
+
+    let mentions = name_mentions(&aliases);
+    for m in messages.iter_mut() {
+        if let Some(alias) = aliases.get(&m.sender) {
+            m.sender = alias.clone();
+        }
+        if let Some((re, by_name)) = &mentions {
+            if re.is_match(&m.text) {
+                m.text = re
+                    .replace_all(&m.text, |caps: &regex::Captures| {
+                        let found = &caps[0];
+                        by_name
+                            .get(&found.to_lowercase())
+                            .map_or_else(|| found.to_string(), String::clone)
+                    })
+                    .into_owned();
+            }
+        }
+    }
+    aliases
+}
+
+/// Shortest name word replaced in message text; shorter ones are mostly initials or
+/// ordinary words.
+const MIN_NAME_WORD_CHARS: usize = 3;
+
+/// A whole-word, case-insensitive matcher for the names in `aliases` and the alias
+/// for each lowercased match. `None` when there is nothing to match.
+fn name_mentions(aliases: &HashMap<String, String>) -> Option<(Regex, HashMap<String, String>)> {
+    let mut by_name: HashMap<String, String> = HashMap::new();
+    let mut shared_words: HashMap<String, u32> = HashMap::new();
+    for name in aliases.keys() {
+        let mut words: Vec<String> = name.split_whitespace().map(str::to_lowercase).collect();
+        words.dedup();
+        for word in words {
+            *shared_words.entry(word).or_insert(0) += 1;
+        }
+    }
+    for (name, alias) in aliases {
+        for word in name.split_whitespace() {
+            let word = word.to_lowercase();
+            if shared_words[&word] == 1
+                && word.chars().count() >= MIN_NAME_WORD_CHARS
+                && word.chars().all(char::is_alphabetic)
+            {
+                by_name.insert(word, alias.clone());
+            }
+        }
+    }
+    for (name, alias) in aliases {
+        if name.chars().any(char::is_alphabetic) {
+            by_name.insert(name.to_lowercase(), alias.clone());
+        }
+    }
+    if by_name.is_empty() {
+        return None;
+    }
+
+    // Longest first so a full name wins over its first word.
+    let mut names: Vec<&String> = by_name.keys().collect();
+    names.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    let alternation: Vec<String> = names.iter().map(|n| regex::escape(n)).collect();
+    let re = RegexBuilder::new(&format!(r"\b(?:{})\b", alternation.join("|")))
+        .case_insensitive(true)
+        .build()
+        .ok()?;
+    Some((re, by_name))
+}
+
+/// Spreadsheet-style labels: 0 -> "A", 25 -> "Z", 26 -> "AA".
+fn pseudonym_letters(rank: usize) -> String {
+    let mut n = rank + 1;
+    let mut letters = Vec::new();
+    while n > 0 {
+        let rem = (n - 1) % 26;
+        letters.push((b'A' + rem as u8) as char);
+        n = (n - 1) / 26;
+    }
+    letters.iter().rev().collect()
+}
+
+pub(crate) fn filter_system_messages(messages: Vec<Message>) -> Vec<Message> {
     let mut filtered = Vec::with_capacity(messages.len());
     let mut iter = messages.into_iter();
 
@@ -184,13 +498,86 @@ fn is_system_message(msg: &Message) -> bool {
 }
 
 pub(crate) fn parse_messages(raw: &str) -> Vec<Message> {
+    parse_messages_with_report(raw).0
+}
+
+/// Caps on a user-supplied header pattern, as for regex text filters.
+const MAX_LINE_PATTERN_LEN: usize = 512;
+const LINE_REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// Named groups a custom header pattern must define.
+const LINE_REGEX_GROUPS: [&str; 4] = ["date", "time", "name", "msg"];
+
+/// Compiles a user-supplied message header pattern. The `date` and `time` groups go
+/// through `parse_timestamp`, so they must use one of the WhatsApp layouts it knows.
+pub(crate) fn compile_line_regex(pattern: &str) -> Result<Regex, String> {
+    if pattern.len() > MAX_LINE_PATTERN_LEN {
+        return Err(format!(
+            "pattern is too long (max {MAX_LINE_PATTERN_LEN} bytes)"
+        ));
+    }
+    let re = RegexBuilder::new(pattern)
+        .size_limit(LINE_REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| format!("invalid pattern: {e}"))?;
+    let missing: Vec<&str> = LINE_REGEX_GROUPS
+        .into_iter()
+        .filter(|group| !re.capture_names().flatten().any(|name| name == *group))
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "pattern is missing named group(s) {}",
+            missing.join(", ")
+        ));
+    }
+    Ok(re)
+}
+
+/// `parse_messages` plus a tally of the lines it could not make sense of.
+pub(crate) fn parse_messages_with_report(raw: &str) -> (Vec<Message>, ParseReport) {
+    parse_messages_with(raw, None, usize::MAX)
+}
+
+/// `line` cut to at most `max_len` bytes, backing off to a character boundary.
+pub(crate) fn truncate_line(line: &str, max_len: usize) -> &str {
+    if line.len() <= max_len {
+        return line;
+    }
+    let mut end = max_len;
+    while !line.is_char_boundary(end) {
+        end -= 1;
+    }
+    &line[..end]
+}
+
+/// `parse_messages_with_report` that also accepts headers matching `custom`, tried
+/// after the built-in formats, and cuts lines longer than `max_line_length` bytes.
+pub(crate) fn parse_messages_with(
+    raw: &str,
+    custom: Option<&Regex>,
+    max_line_length: usize,
+) -> (Vec<Message>, ParseReport) {
     let mut messages = Vec::new();
     let mut current: Option<Message> = None;
+    let mut report = ParseReport::default();
 
-    for line in raw.lines() {
+    for (index, line) in raw.lines().enumerate() {
+        // `lines()` only drops one `\r` before a `\n`; doubled `\r\r\n` endings and a
+        // final bare `\r` would otherwise leak into message text.
+        let line = line.trim_end_matches('\r');
+        if !line.trim().is_empty() {
+            report.lines += 1;
+        }
+        let full_len = line.len();
+        let line = truncate_line(line, max_line_length);
+        if line.len() < full_len {
+            report.truncated_lines += 1;
+        }
         if let Some(caps) = re_bracket()
             .captures(line)
             .or_else(|| re_hyphen().captures(line))
+            .or_else(|| re_senderless().captures(line))
+            .or_else(|| custom.and_then(|re| re.captures(line)))
         {
             if let Some(msg) = current.take() {
                 messages.push(msg);
@@ -201,23 +588,35 @@ pub(crate) fn parse_messages(raw: &str) -> Vec<Message> {
             let name = caps
                 .name("name")
                 .map(|m| clean_sender(m.as_str()))
-                .unwrap_or_else(String::new);
+                .unwrap_or_else(|| CHANNEL_SENDER.to_string());
             let text = caps
                 .name("msg")
                 .map(|m| m.as_str())
                 .unwrap_or("")
                 .to_string();
 
-            if let Some(dt) = parse_timestamp(date, time) {
-                current = Some(Message {
-                    dt,
-                    sender: name,
-                    text,
-                });
+            match parse_timestamp(date, time) {
+                Some(dt) => {
+                    if has_two_digit_year(date) {
+                        report.two_digit_years += 1;
+                    }
+                    current = Some(Message {
+                        dt,
+                        sender: name,
+                        text,
+                        line: index + 1,
+                    });
+                }
+                None => report.dropped_lines += 1,
             }
         } else if let Some(msg) = current.as_mut() {
+            if re_foreign_header().is_match(line) {
+                report.foreign_header_lines += 1;
+            }
             msg.text.push('\n');
             msg.text.push_str(line.trim());
+        } else if !line.trim().is_empty() {
+            report.dropped_lines += 1;
         }
     }
 
@@ -225,7 +624,13 @@ pub(crate) fn parse_messages(raw: &str) -> Vec<Message> {
         messages.push(msg);
     }
 
-    filter_system_messages(messages)
+    let mut messages = filter_system_messages(messages);
+    report.merged_senders = merge_sender_variants(&mut messages);
+    (messages, report)
+}
+
+fn has_two_digit_year(date: &str) -> bool {
+    date.rsplit(['/', '.']).next().is_some_and(|y| y.len() == 2)
 }
 pub(crate) fn weekday_index(wd: chrono::Weekday) -> usize {
     wd.num_days_from_sunday() as usize
@@ -253,6 +658,10 @@ pub(crate) fn re_hyphen_pattern() -> &'static Regex {
     re_hyphen()
 }
 
+pub(crate) fn re_senderless_pattern() -> &'static Regex {
+    re_senderless()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -295,6 +704,107 @@ mod tests {
         assert_eq!(dt.minute(), 15);
     }
 
+    #[test]
+    fn parse_timestamp_handles_dotted_spanish_meridiem() {
+        // Mexican Spanish exports: "a. m." with a non-breaking space inside.
+        let am = parse_timestamp("19/8/19", "5:04:35\u{a0}a.\u{a0}m.").expect("parses a. m.");
+        assert_eq!((am.hour(), am.minute(), am.second()), (5, 4, 35));
+        let pm = parse_timestamp("19/8/19", "5:04:35 p.\u{a0}m.").expect("parses p. m.");
+        assert_eq!(pm.hour(), 17);
+        assert_eq!(normalize_meridiem("5:04 A.M."), "5:04 AM");
+        assert_eq!(normalize_meridiem("17:04"), "17:04");
+    }
+
+    #[test]
+    fn parse_messages_spanish_meridiem_in_both_formats() {
+        let raw = "[19/8/19, 5:04:35\u{a0}p.\u{a0}m.] Ana: hola\n20/8/19, 7:00\u{a0}a.\u{a0}m. - Luis: buenos días";
+        let msgs = parse_messages(raw);
+        assert_eq!(msgs.len(), 2);
+        assert_eq!((msgs[0].sender.as_str(), msgs[0].dt.hour()), ("Ana", 17));
+        assert_eq!((msgs[1].sender.as_str(), msgs[1].dt.hour()), ("Luis", 7));
+        assert_eq!(msgs[1].text, "buenos días");
+    }
+
+    #[test]
+    fn custom_line_regex_parses_tab_separated_exports() {
+        let re = compile_line_regex(
+            r"^(?P<date>\d{1,2}/\d{1,2}/\d{4})\t(?P<time>\d{1,2}:\d{2})\t(?P<name>[^\t]+)\t(?P<msg>.*)$",
+        )
+        .unwrap();
+        let raw = "13/01/2024\t21:05\tAlice\thello\tthere\n[1/14/24, 9:00:00 AM] Bob: built-in still works";
+        let (msgs, report) = parse_messages_with(raw, Some(&re), usize::MAX);
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(
+            (msgs[0].sender.as_str(), msgs[0].text.as_str()),
+            ("Alice", "hello\tthere")
+        );
+        assert_eq!((msgs[0].dt.day(), msgs[0].dt.hour()), (13, 21));
+        assert_eq!(msgs[1].sender, "Bob");
+        assert_eq!(report.dropped_lines, 0);
+
+        // Without the pattern the tab line is not a message.
+        assert_eq!(parse_messages(raw).len(), 1);
+    }
+
+    #[test]
+    fn compile_line_regex_requires_named_groups() {
+        let err = compile_line_regex(r"^(?P<date>\S+) (?P<msg>.*)$").unwrap_err();
+        assert!(err.contains("time, name"), "{err}");
+        assert!(compile_line_regex("(")
+            .unwrap_err()
+            .contains("invalid pattern"));
+        assert!(compile_line_regex(&"a".repeat(600)).is_err());
+    }
+
+    #[test]
+    fn sender_variants_collapse_to_the_common_spelling() {
+        let raw = "[1/1/24, 10:00:00 AM] Alice: hi\n[1/1/24, 10:01:00 AM] Alice: again\n[1/1/24, 10:02:00 AM] alice: other phone\n[1/1/24, 10:03:00 AM] Bob\u{2007}: figure space\n[1/1/24, 10:04:00 AM] \u{200b}Bob: zero width\n[1/1/24, 10:05:00 AM] ＢＯＢ: full width";
+        let (msgs, report) = parse_messages_with_report(raw);
+        let senders: Vec<&str> = msgs.iter().map(|m| m.sender.as_str()).collect();
+        assert_eq!(
+            senders,
+            vec!["Alice", "Alice", "Alice", "Bob", "Bob", "Bob"]
+        );
+        assert_eq!(
+            report.merged_senders,
+            vec![
+                ("Alice".to_string(), vec!["alice".to_string()]),
+                ("Bob".to_string(), vec!["ＢＯＢ".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn composed_and_decomposed_senders_merge() {
+        let raw = "[1/1/24, 10:00:00 AM] Zoé: from iOS\n[1/1/24, 10:01:00 AM] Zoé: again\n[1/1/24, 10:02:00 AM] Zoe\u{301}: from Android\n[1/1/24, 10:03:00 AM] Bob: hi";
+        let (msgs, report) = parse_messages_with_report(raw);
+        let mut senders: Vec<&str> = msgs.iter().map(|m| m.sender.as_str()).collect();
+        senders.dedup();
+        assert_eq!(senders, vec!["Zoé", "Bob"]);
+        assert_eq!(
+            report.merged_senders,
+            vec![("Zoé".to_string(), vec!["Zoe\u{301}".to_string()])]
+        );
+    }
+
+    #[test]
+    fn long_lines_are_truncated_and_counted() {
+        let raw = format!(
+            "[1/1/24, 10:00:00 AM] Alice: {}\n{}\n[1/1/24, 10:01:00 AM] Bob: short",
+            "é".repeat(40),
+            "x".repeat(100)
+        );
+        let (msgs, report) = parse_messages_with(&raw, None, 50);
+        assert_eq!(report.truncated_lines, 2);
+        assert_eq!(msgs.len(), 2);
+        // The header keeps 50 bytes, backing off to a char boundary inside "é".
+        let first_line = msgs[0].text.lines().next().unwrap();
+        assert!(first_line.chars().all(|c| c == 'é'));
+        assert_eq!(msgs[0].text.lines().nth(1).unwrap().len(), 50);
+        assert_eq!(truncate_line("héllo", 2), "h");
+        assert_eq!(truncate_line("short", 50), "short");
+    }
+
     #[test]
     fn parse_timestamp_two_digit_year_expands() {
         let dt = parse_timestamp("3/4/05", "1:00 PM").expect("parses");
@@ -330,6 +840,48 @@ mod tests {
         assert_eq!(msgs[1].sender, "Bob");
     }
 
+    #[test]
+    fn parse_messages_hyphen_format_with_leading_bom() {
+        let raw = "\u{feff}8/20/19, 7:00 AM - Bob: morning\n8/20/19, 7:01 AM - Alice: hi";
+        let msgs = parse_messages(raw);
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(msgs[0].sender, "Bob");
+        assert_eq!(msgs[0].text, "morning");
+    }
+
+    #[test]
+    fn parse_messages_accepts_tabs_spaces_and_en_dash_separators() {
+        let raw = "1/2/24, 3:00 PM -\tAlice:\thi\n\
+1/2/24,\t3:01 PM\t-\tBob:  hey there\n\
+1/2/24,  3:02 PM  \u{2013}  Alice: en dash\n\
+[1/2/24,\t3:03:00 PM]\tBob:\tbracketed";
+        let msgs = parse_messages(raw);
+        let got: Vec<(&str, &str, u32)> = msgs
+            .iter()
+            .map(|m| (m.sender.as_str(), m.text.as_str(), m.dt.minute()))
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                ("Alice", "hi", 0),
+                ("Bob", "hey there", 1),
+                ("Alice", "en dash", 2),
+                ("Bob", "bracketed", 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn sorted_view_borrows_sorted_input_and_sorts_the_rest() {
+        let raw = "[1/1/24, 10:00:00 AM] A: one\n[1/1/24, 9:00:00 AM] B: two\n[1/1/24, 9:00:00 AM] C: three";
+        let parsed = parse_messages(raw);
+        let sorted = sorted_view(&parsed);
+        assert!(matches!(sorted, Cow::Owned(_)));
+        let senders: Vec<&str> = sorted.iter().map(|m| m.sender.as_str()).collect();
+        assert_eq!(senders, vec!["B", "C", "A"]);
+        assert!(matches!(sorted_view(&sorted), Cow::Borrowed(_)));
+    }
+
     #[test]
     fn parse_messages_multiline_continuation() {
         let raw = "[8/19/19, 5:04:35 PM] Alice: first line\nsecond line\nthird line\n[8/19/19, 5:05:00 PM] Bob: reply";
@@ -348,6 +900,32 @@ mod tests {
         assert_eq!(msgs[0].sender, "Alice");
     }
 
+    #[test]
+    fn parse_report_counts_dropped_and_foreign_lines() {
+        let raw = "stray preamble\n[1/1/24, 10:00:00 AM] Alice: hi\n2024-01-01 10:05 - Bob: pasted\n[45/45/2024, 10:00:00 AM] Bob: bad date\nlost continuation\n[1/2/2024, 9:00:00 AM] Bob: ok";
+        let (messages, report) = parse_messages_with_report(raw);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            report,
+            ParseReport {
+                lines: 6,
+                dropped_lines: 3,
+                foreign_header_lines: 1,
+                two_digit_years: 1,
+                skipped_entries: 0,
+                merged_senders: Vec::new(),
+                truncated_lines: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_timestamp_rejects_far_future_years() {
+        assert!(parse_timestamp("1/1/2100", "10:00").is_some());
+        assert!(parse_timestamp("1/1/9999", "10:00").is_none());
+        assert!(parse_timestamp("31.12.2101", "23:59").is_none());
+    }
+
     #[test]
     fn parse_messages_filters_system_messages() {
         let raw = "[8/19/19, 5:00:00 PM] System: Messages and calls are end-to-end encrypted.\n[8/19/19, 5:04:35 PM] Alice: real message";
@@ -383,10 +961,63 @@ mod tests {
         assert!(msgs[0].text.contains("你好"));
     }
 
+    #[test]
+    fn parse_messages_channel_only_export() {
+        let raw = "[1/2/24, 3:00 PM] Welcome to the channel\nsecond line\n[1/2/24, 3:05 PM] Big news today";
+        let msgs = parse_messages(raw);
+        assert_eq!(msgs.len(), 2);
+        assert!(msgs.iter().all(|m| m.sender == CHANNEL_SENDER));
+        assert_eq!(msgs[0].text, "Welcome to the channel\nsecond line");
+        assert_eq!(msgs[1].text, "Big news today");
+    }
+
+    #[test]
+    fn parse_messages_keeps_channel_forwards_separate() {
+        let raw = "[1/2/24, 3:00 PM] Alice: look at this\n[1/2/24, 3:01 PM] Forwarded headline\n[1/2/24, 3:02 PM] Bob: wow";
+        let msgs = parse_messages(raw);
+        assert_eq!(msgs.len(), 3);
+        assert_eq!(msgs[0].text, "look at this");
+        assert_eq!(msgs[1].sender, CHANNEL_SENDER);
+        assert_eq!(msgs[1].text, "Forwarded headline");
+        assert_eq!(msgs[2].sender, "Bob");
+    }
+
+    #[test]
+    fn crlf_line_endings_leave_no_carriage_returns() {
+        let raw = "[1/2/2024, 10:00:00 AM] Alice: hello\r\n\
+                   [1/2/2024, 10:01:00 AM] Bob: hello there\r\r\n\
+                   second line\r\n\
+                   [1/2/2024, 10:02:00 AM] Alice: bye\r";
+        let messages = parse_messages(raw);
+        assert_eq!(messages.len(), 3);
+        let senders: Vec<&str> = messages.iter().map(|m| m.sender.as_str()).collect();
+        assert_eq!(senders, vec!["Alice", "Bob", "Alice"]);
+        let texts: Vec<&str> = messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, vec!["hello", "hello there\nsecond line", "bye"]);
+    }
+
+    #[test]
+    fn huge_continuation_message_parses_quickly() {
+        let mut raw = String::from("[1/2/2024, 10:00:00 AM] Alice: start\n");
+        for i in 0..5_000 {
+            raw.push_str(&format!("pasted line {i}\r\n"));
+        }
+        raw.push_str("[1/2/2024, 10:01:00 AM] Bob: ok\n");
+
+        let started = std::time::Instant::now();
+        let messages = parse_messages(&raw);
+        assert!(started.elapsed() < std::time::Duration::from_secs(2));
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].text.lines().count(), 5_001);
+        assert!(!messages[0].text.contains('\r'));
+    }
+
     #[test]
     fn clean_sender_strips_bidi_and_control_marks() {
         let cleaned = clean_sender("\u{200e}\u{202a}Alice\u{202c}\u{200f}");
         assert_eq!(cleaned, "Alice");
+        let inner = clean_sender("Al\u{200e}ice \u{2060}\u{1f469}\u{200d}\u{1f4bb}");
+        assert_eq!(inner, "Alice \u{1f469}\u{200d}\u{1f4bb}");
     }
 
     #[test]
@@ -394,12 +1025,35 @@ mod tests {
         assert_eq!(clean_sender("  Bob  "), "Bob");
     }
 
+    #[test]
+    fn clean_sender_normalizes_phone_numbers() {
+        assert_eq!(clean_sender("+1 555 123 4567"), "+15551234567");
+        assert_eq!(
+            clean_sender("\u{202a}+1 (555) 123-4567\u{202c}"),
+            "+15551234567"
+        );
+        assert_eq!(clean_sender("+15551234567"), "+15551234567");
+        // Short numbers and names with digits are left alone.
+        assert_eq!(clean_sender("Room 101"), "Room 101");
+        assert_eq!(clean_sender("2024"), "2024");
+    }
+
+    #[test]
+    fn phone_senders_merge_across_formats() {
+        let raw =
+            "[1/1/24, 10:00:00 AM] +1 555 123 4567: hi\n[1/1/24, 10:01:00 AM] +15551234567: again";
+        let msgs = parse_messages(raw);
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(msgs[0].sender, msgs[1].sender);
+    }
+
     #[test]
     fn is_system_message_detects_banners() {
         let sys = Message {
             dt: parse_timestamp("1/1/20", "1:00 PM").unwrap(),
             sender: "Alice".into(),
             text: "Your security code with Bob changed. Tap to learn more.".into(),
+            line: 1,
         };
         assert!(is_system_message(&sys));
 
@@ -407,6 +1061,7 @@ mod tests {
             dt: parse_timestamp("1/1/20", "1:00 PM").unwrap(),
             sender: "Alice".into(),
             text: "hello".into(),
+            line: 1,
         };
         assert!(!is_system_message(&normal));
     }
@@ -417,10 +1072,42 @@ mod tests {
             dt: parse_timestamp("1/1/20", "1:00 PM").unwrap(),
             sender: "system".into(),
             text: "anything".into(),
+            line: 1,
         };
         assert!(is_system_message(&sys));
     }
 
+    #[test]
+    fn anonymize_senders_ranks_by_message_count() {
+        let raw =
+            "[1/1/20, 1:00:00 PM] Zed: a\n[1/1/20, 1:01:00 PM] Amy: b\n[1/1/20, 1:02:00 PM] Zed: c";
+        let mut msgs = parse_messages(raw);
+        anonymize_senders(&mut msgs);
+        assert_eq!(msgs[0].sender, "Person A");
+        assert_eq!(msgs[1].sender, "Person B");
+        assert_eq!(msgs[2].sender, "Person A");
+    }
+
+    #[test]
+    fn anonymize_senders_replaces_names_in_text() {
+        let raw = "[1/1/20, 1:00:00 PM] Zed Ray: ask alice later
+[1/1/20, 1:01:00 PM] Alice: Zed Ray said so, and Zed agreed
+[1/1/20, 1:02:00 PM] Zed Ray: Alicent is someone else";
+        let mut msgs = parse_messages(raw);
+        anonymize_senders(&mut msgs);
+        assert_eq!(msgs[0].text, "ask Person B later");
+        assert_eq!(msgs[1].text, "Person A said so, and Person A agreed");
+        assert_eq!(msgs[2].text, "Alicent is someone else");
+    }
+
+    #[test]
+    fn pseudonym_letters_roll_over_past_z() {
+        assert_eq!(pseudonym_letters(0), "A");
+        assert_eq!(pseudonym_letters(25), "Z");
+        assert_eq!(pseudonym_letters(26), "AA");
+        assert_eq!(pseudonym_letters(27), "AB");
+    }
+
     #[test]
     fn weekday_index_and_label_round_trip() {
         assert_eq!(weekday_label(weekday_index(chrono::Weekday::Sun)), "Sun");
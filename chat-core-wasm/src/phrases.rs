@@ -1,20 +1,59 @@
-use std::collections::HashMap;
+use chrono::Datelike;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use crate::parsing::Message;
 use crate::text::{
-    extract_emojis, is_media_omitted_message, stopwords_set, tokenize, tokens_alpha_numeric_stats,
-    tokens_stop_stats,
+    adjacent_emoji_pairs, extract_emojis, is_media_omitted_message, is_numeric_token,
+    is_poll_message, stopwords_set, tokenize, tokens_alpha_numeric_stats, tokens_stop_stats,
+    truncate_chars, SNIPPET_MAX_CHARS,
+};
+use crate::types::{
+    Count, EmojiMonth, FloatCount, PersonEmojiMonthly, PersonPhrases, PhraseWithExample,
+    VocabDrift, WordOrigin,
 };
-use crate::types::{Count, PersonPhrases};
 
-pub(crate) fn salient_phrases(messages: &[Message], take: usize) -> Vec<Count> {
-    let min_count: u32 = if messages.len() > 100000 {
+/// Tokens a message feeds into phrase windows. Messages over `max_tokens` (pasted
+/// lyrics, articles) are cut to their first `max_tokens` tokens and flagged, so callers
+/// count each of their n-grams once rather than letting repeated lines dominate.
+fn phrase_tokens(
+    text: &str,
+    stop: &HashSet<&'static str>,
+    max_tokens: usize,
+) -> (Vec<String>, bool) {
+    let mut tokens = tokenize(text, false, stop);
+    let capped = tokens.len() > max_tokens;
+    tokens.truncate(max_tokens);
+    (tokens, capped)
+}
+
+/// Occurrences a phrase needs to make `salient_phrases`, scaled by chat size.
+pub(crate) fn salient_min_count(message_count: usize) -> u32 {
+    if message_count > 100000 {
         5
-    } else if messages.len() > 10000 {
+    } else if message_count > 10000 {
         3
     } else {
         2
-    };
+    }
+}
+
+/// Occurrences a phrase needs to make `top_phrases`, scaled by token volume.
+pub(crate) fn top_phrases_min_count(total_tokens: u32) -> u32 {
+    if total_tokens > 500000 {
+        5
+    } else if total_tokens > 100000 {
+        4
+    } else if total_tokens > 50000 {
+        3
+    } else if total_tokens > 10000 {
+        2
+    } else {
+        1
+    }
+}
+
+pub(crate) fn salient_phrases(messages: &[Message], take: usize, max_tokens: usize) -> Vec<Count> {
+    let min_count = salient_min_count(messages.len());
     let stop = stopwords_set();
 
     let mut unigram_counts: HashMap<String, u32> = HashMap::new();
@@ -23,13 +62,14 @@ pub(crate) fn salient_phrases(messages: &[Message], take: usize) -> Vec<Count> {
     let mut total_tokens: u32 = 0;
 
     for m in messages {
-        if is_media_omitted_message(&m.text) {
+        if is_media_omitted_message(&m.text) || is_poll_message(&m.text) {
             continue;
         }
-        let tokens = tokenize(&m.text, false, stop);
+        let (tokens, capped) = phrase_tokens(&m.text, stop, max_tokens);
         if tokens.len() < 2 {
             continue;
         }
+        let mut seen: HashSet<String> = HashSet::new();
 
         for t in &tokens {
             *unigram_counts.entry(t.clone()).or_insert(0) += 1;
@@ -66,6 +106,9 @@ pub(crate) fn salient_phrases(messages: &[Message], take: usize) -> Vec<Count> {
                 }
 
                 let phrase = slice.join(" ");
+                if capped && !seen.insert(phrase.clone()) {
+                    continue;
+                }
                 let entry = phrase_counts.entry(phrase.clone()).or_insert((
                     0,
                     window,
@@ -173,23 +216,199 @@ pub(crate) fn top_emojis(messages: &[Message], take: usize) -> Vec<Count> {
         .into_iter()
         .map(|(label, value)| Count { label, value })
         .collect();
-    items.sort_by_key(|c| std::cmp::Reverse(c.value));
+    items.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
     items.truncate(take);
     items
 }
 
-pub(crate) fn top_words(messages: &[Message], take: usize, filter_stop: bool) -> Vec<Count> {
+/// Times an emoji pair must appear to be listed in `emoji_pairs`.
+const EMOJI_PAIR_MIN_COUNT: u32 = 3;
+
+/// The most used emoji pairs (see `adjacent_emoji_pairs`) across `texts`, leaving out
+/// pairs seen fewer than `EMOJI_PAIR_MIN_COUNT` times.
+pub(crate) fn emoji_pairs<'a>(texts: impl IntoIterator<Item = &'a str>, take: usize) -> Vec<Count> {
+    let mut map: HashMap<String, u32> = HashMap::new();
+    for text in texts {
+        for pair in adjacent_emoji_pairs(text) {
+            *map.entry(pair).or_insert(0) += 1;
+        }
+    }
+    let mut items: Vec<Count> = map
+        .into_iter()
+        .filter(|&(_, value)| value >= EMOJI_PAIR_MIN_COUNT)
+        .map(|(label, value)| Count { label, value })
+        .collect();
+    items.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
+    items.truncate(take);
+    items
+}
+
+/// Days after which an emoji use counts half as much in `recent_emojis`.
+pub(crate) const RECENT_EMOJI_HALF_LIFE_DAYS: f32 = 90.0;
+
+/// Emoji counts where each use is worth `0.5^(age / half_life_days)`, age being whole
+/// days before the chat's last day. Highest score first, ties by emoji.
+pub(crate) fn recent_emojis(
+    messages: &[Message],
+    take: usize,
+    half_life_days: f32,
+) -> Vec<FloatCount> {
+    let Some(last) = messages.iter().map(|m| m.dt.date()).max() else {
+        return Vec::new();
+    };
+    let mut map: HashMap<String, f32> = HashMap::new();
+    for m in messages {
+        let age = (last - m.dt.date()).num_days() as f32;
+        let weight = 0.5f32.powf(age / half_life_days);
+        for hit in extract_emojis(&m.text) {
+            *map.entry(hit).or_insert(0.0) += weight;
+        }
+    }
+    let mut items: Vec<FloatCount> = map
+        .into_iter()
+        .map(|(label, value)| FloatCount { label, value })
+        .collect();
+    items.sort_by(|a, b| {
+        b.value
+            .total_cmp(&a.value)
+            .then_with(|| a.label.cmp(&b.label))
+    });
+    items.truncate(take);
+    items
+}
+
+/// Inclusive codepoint ranges and their coarse category, matched against an emoji's
+/// first codepoint. Earlier rows win, so the specific ranges (hearts, hands) sit above
+/// the broad blocks they are carved out of.
+const EMOJI_CATEGORIES: &[(u32, u32, &str)] = &[
+    (0x2763, 0x2764, "hearts"),
+    (0x1F493, 0x1F49F, "hearts"),
+    (0x1F5A4, 0x1F5A4, "hearts"),
+    (0x1F90D, 0x1F90E, "hearts"),
+    (0x1F9E1, 0x1F9E1, "hearts"),
+    (0x1FA75, 0x1FA77, "hearts"),
+    (0x261D, 0x261D, "hands"),
+    (0x270A, 0x270D, "hands"),
+    (0x1F446, 0x1F450, "hands"),
+    (0x1F4AA, 0x1F4AA, "hands"),
+    (0x1F590, 0x1F596, "hands"),
+    (0x1F64C, 0x1F64C, "hands"),
+    (0x1F64F, 0x1F64F, "hands"),
+    (0x1F918, 0x1F91F, "hands"),
+    (0x1F932, 0x1F932, "hands"),
+    (0x1FAF0, 0x1FAF8, "hands"),
+    (0x2639, 0x263A, "faces"),
+    (0x1F600, 0x1F64F, "faces"),
+    (0x1F910, 0x1F92F, "faces"),
+    (0x1F970, 0x1F97A, "faces"),
+    (0x1F9D0, 0x1F9D0, "faces"),
+    (0x1FAE0, 0x1FAE8, "faces"),
+    (0x1F400, 0x1F43F, "animals"),
+    (0x1F54A, 0x1F54A, "animals"),
+    (0x1F980, 0x1F9AE, "animals"),
+    (0x1F1E6, 0x1F1FF, "flags"),
+    (0x1F3F3, 0x1F3F4, "flags"),
+    (0x1F6A9, 0x1F6A9, "flags"),
+    (0x2300, 0x23FF, "objects"),
+    (0x1F380, 0x1F3FF, "objects"),
+    (0x1F4A0, 0x1F5FF, "objects"),
+    (0x1F680, 0x1F6FF, "objects"),
+    (0x1F9F0, 0x1F9FF, "objects"),
+    (0x1FA70, 0x1FAFF, "objects"),
+];
+
+fn emoji_category(emoji: &str) -> &'static str {
+    let Some(first) = emoji.chars().next().map(u32::from) else {
+        return "other";
+    };
+    EMOJI_CATEGORIES
+        .iter()
+        .find(|(lo, hi, _)| (*lo..=*hi).contains(&first))
+        .map_or("other", |(_, _, category)| category)
+}
+
+/// Emoji use grouped into coarse categories (faces, hearts, hands, animals, objects,
+/// flags, other), most used first. Categories nobody used are left out.
+pub(crate) fn emoji_categories(messages: &[Message]) -> Vec<Count> {
+    let mut map: HashMap<&str, u32> = HashMap::new();
+    for m in messages {
+        for e in extract_emojis(&m.text) {
+            *map.entry(emoji_category(&e)).or_insert(0) += 1;
+        }
+    }
+    let mut items: Vec<Count> = map
+        .into_iter()
+        .map(|(label, value)| Count {
+            label: label.to_string(),
+            value,
+        })
+        .collect();
+    items.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
+    items
+}
+
+/// Emojis kept per person per month in the emoji diary.
+const EMOJI_DIARY_TOP: usize = 3;
+
+/// Emoji counts bucketed by sender and `"YYYY-MM"` month in a single scan.
+fn emoji_counts_by_person_month(
+    messages: &[Message],
+) -> BTreeMap<(&str, String), HashMap<String, u32>> {
+    let mut buckets: BTreeMap<(&str, String), HashMap<String, u32>> = BTreeMap::new();
+    for m in messages {
+        let emojis = extract_emojis(&m.text);
+        if emojis.is_empty() {
+            continue;
+        }
+        let month = format!("{:04}-{:02}", m.dt.year(), m.dt.month());
+        let counts = buckets.entry((m.sender.as_str(), month)).or_default();
+        for e in emojis {
+            *counts.entry(e).or_insert(0) += 1;
+        }
+    }
+    buckets
+}
+
+/// Each person's top emojis per month, skipping months without any emoji. People are
+/// sorted by name and months chronologically.
+pub(crate) fn per_person_emoji_monthly(messages: &[Message]) -> Vec<PersonEmojiMonthly> {
+    let mut out: Vec<PersonEmojiMonthly> = Vec::new();
+    for ((name, month), counts) in emoji_counts_by_person_month(messages) {
+        let mut top: Vec<Count> = counts
+            .into_iter()
+            .map(|(label, value)| Count { label, value })
+            .collect();
+        top.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
+        top.truncate(EMOJI_DIARY_TOP);
+        let entry = EmojiMonth { month, top };
+        match out.last_mut() {
+            Some(person) if person.name == name => person.monthly.push(entry),
+            _ => out.push(PersonEmojiMonthly {
+                name: name.to_string(),
+                monthly: vec![entry],
+            }),
+        }
+    }
+    out
+}
+
+pub(crate) fn top_words(
+    messages: &[Message],
+    take: usize,
+    filter_stop: bool,
+    filter_numeric: bool,
+) -> Vec<Count> {
     let stop = stopwords_set();
 
     let mut map = HashMap::new();
     for m in messages {
         let text = m.text.as_str();
-        if is_media_omitted_message(text) {
+        if is_media_omitted_message(text) || is_poll_message(text) {
             continue;
         }
         for token in tokenize(text, filter_stop, stop) {
             let short_alnum = token.len() < 3 && token.chars().all(|c| c.is_alphanumeric());
-            if short_alnum {
+            if short_alnum || (filter_numeric && is_numeric_token(&token)) {
                 continue;
             }
             *map.entry(token).or_insert(0u32) += 1;
@@ -199,21 +418,257 @@ pub(crate) fn top_words(messages: &[Message], take: usize, filter_stop: bool) ->
         .into_iter()
         .map(|(label, value)| Count { label, value })
         .collect();
-    items.sort_by_key(|c| std::cmp::Reverse(c.value));
+    items.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
     items.truncate(take);
     items
 }
 
-pub(crate) fn word_cloud(messages: &[Message], take: usize, filter_stop: bool) -> Vec<Count> {
+/// Words listed in `word_origins`.
+const WORD_ORIGINS_TAKE: usize = 20;
+
+/// Messages from other people a coined word needs before it counts as adopted.
+const WORD_ORIGIN_MIN_ADOPTIONS: u32 = 5;
+
+struct WordUse<'a> {
+    coined_by: &'a str,
+    first_date: chrono::NaiveDate,
+    adopted: u32,
+}
+
+/// Non-stopword words one person used first that others then picked up in at least
+/// `WORD_ORIGIN_MIN_ADOPTIONS` messages, most adopted first. Words seen in the first 1%
+/// of messages are skipped since the export may start mid-conversation, as are
+/// participants' names. `tokenized` must be in chat order.
+pub(crate) fn word_origins(tokenized: &[MessageTokens]) -> Vec<WordOrigin> {
+    let stop = stopwords_set();
+    let names: HashSet<String> = tokenized
+        .iter()
+        .flat_map(|t| t.message.sender.split_whitespace())
+        .map(str::to_lowercase)
+        .collect();
+
+    let warmup = tokenized.len() / 100;
+
+    let mut preexisting: HashSet<&str> = HashSet::new();
+    let mut uses: HashMap<&str, WordUse> = HashMap::new();
+    for (i, MessageTokens { message: m, tokens }) in tokenized.iter().enumerate() {
+        let words: HashSet<&str> = tokens
+            .iter()
+            .map(|t| t.trim_matches(|c: char| !c.is_alphanumeric()))
+            .filter(|t| {
+                !stop.contains(t)
+                    && t.chars().count() >= 3
+                    && t.chars().any(char::is_alphabetic)
+                    && !is_numeric_token(t)
+                    && !names.contains(*t)
+            })
+            .collect();
+        for word in words {
+            if i < warmup {
+                preexisting.insert(word);
+            } else if preexisting.contains(&word) {
+                continue;
+            } else if let Some(origin) = uses.get_mut(&word) {
+                if origin.coined_by != m.sender {
+                    origin.adopted += 1;
+                }
+            } else {
+                uses.insert(
+                    word,
+                    WordUse {
+                        coined_by: &m.sender,
+                        first_date: m.dt.date(),
+                        adopted: 0,
+                    },
+                );
+            }
+        }
+    }
+
+    let mut origins: Vec<WordOrigin> = uses
+        .into_iter()
+        .filter(|(_, u)| u.adopted >= WORD_ORIGIN_MIN_ADOPTIONS)
+        .map(|(word, u)| WordOrigin {
+            word: word.to_string(),
+            coined_by: u.coined_by.to_string(),
+            first_date: u.first_date.format("%Y-%m-%d").to_string(),
+            adopted_count: u.adopted,
+        })
+        .collect();
+    origins.sort_by(|a, b| {
+        b.adopted_count
+            .cmp(&a.adopted_count)
+            .then_with(|| a.word.cmp(&b.word))
+    });
+    origins.truncate(WORD_ORIGINS_TAKE);
+    origins
+}
+
+/// A message's tokens, stopwords kept, from the one tokenization pass that
+/// `summarize_parsed` shares between the vocabulary metrics.
+pub(crate) struct MessageTokens<'a> {
+    pub(crate) message: &'a Message,
+    pub(crate) tokens: Vec<String>,
+}
+
+/// Tokenizes `messages` once, in input order, leaving out media placeholders, polls and
+/// messages with no tokens. `summarize_parsed` passes the chat-ordered `sorted_view`.
+pub(crate) fn tokenize_messages(messages: &[Message]) -> Vec<MessageTokens<'_>> {
+    let stop = stopwords_set();
+    messages
+        .iter()
+        .filter(|m| !is_media_omitted_message(&m.text) && !is_poll_message(&m.text))
+        .map(|message| MessageTokens {
+            message,
+            tokens: tokenize(&message.text, false, stop),
+        })
+        .filter(|t| !t.tokens.is_empty())
+        .collect()
+}
+
+/// Words listed per direction in each `vocab_drift` period.
+const VOCAB_DRIFT_TAKE: usize = 5;
+
+/// Uses a word needs in one of the two compared quarters before its rate change counts,
+/// so a word going from 1 to 2 uses doesn't top the list.
+const VOCAB_DRIFT_MIN_COUNT: u32 = 5;
+
+/// For each calendar quarter after the first active one, the non-stopword words whose
+/// rate per 1,000 tokens rose and fell the most against the previous active quarter.
+/// `rising` values are this quarter's uses, `falling` values the previous quarter's.
+pub(crate) fn vocab_drift(tokenized: &[MessageTokens]) -> Vec<VocabDrift> {
+    let stop = stopwords_set();
+
+    let mut quarters: BTreeMap<String, (HashMap<String, u32>, u32)> = BTreeMap::new();
+    for MessageTokens { message: m, tokens } in tokenized {
+        let period = format!("{:04}-Q{}", m.dt.year(), (m.dt.month() - 1) / 3 + 1);
+        let (words, total) = quarters.entry(period).or_default();
+        *total += tokens.len() as u32;
+        for token in tokens {
+            let word = token.trim_matches(|c: char| !c.is_alphanumeric());
+            let countable = word.chars().count() >= 3
+                && !stop.contains(word)
+                && word.chars().any(char::is_alphabetic)
+                && !is_numeric_token(word);
+            if countable {
+                *words.entry(word.to_string()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let quarters: Vec<_> = quarters.into_iter().collect();
+    quarters
+        .windows(2)
+        .filter_map(|pair| {
+            let [(_, (prev, prev_total)), (period, (now, now_total))] = pair else {
+                return None;
+            };
+            let rate = |count: u32, total: u32| count as f64 * 1000.0 / total as f64;
+            let mut deltas: Vec<(&str, f64, u32, u32)> = prev
+                .keys()
+                .chain(now.keys().filter(|w| !prev.contains_key(*w)))
+                .filter_map(|word| {
+                    let before = prev.get(word).copied().unwrap_or(0);
+                    let after = now.get(word).copied().unwrap_or(0);
+                    (before.max(after) >= VOCAB_DRIFT_MIN_COUNT).then(|| {
+                        let delta = rate(after, *now_total) - rate(before, *prev_total);
+                        (word.as_str(), delta, before, after)
+                    })
+                })
+                .collect();
+            deltas.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+            let rising: Vec<Count> = deltas
+                .iter()
+                .filter(|d| d.1 > 0.0)
+                .take(VOCAB_DRIFT_TAKE)
+                .map(|&(word, _, _, after)| Count {
+                    label: word.to_string(),
+                    value: after,
+                })
+                .collect();
+            let falling: Vec<Count> = deltas
+                .iter()
+                .rev()
+                .filter(|d| d.1 < 0.0)
+                .take(VOCAB_DRIFT_TAKE)
+                .map(|&(word, _, before, _)| Count {
+                    label: word.to_string(),
+                    value: before,
+                })
+                .collect();
+            (!rising.is_empty() || !falling.is_empty()).then(|| VocabDrift {
+                period: period.clone(),
+                rising,
+                falling,
+            })
+        })
+        .collect()
+}
+
+/// Counts user-tracked keywords (single words or multi-word phrases) in input order.
+/// Tokens are taken without stopword filtering so a tracked stopword is never hidden.
+pub(crate) fn keyword_counts(messages: &[Message], keywords: &[String]) -> Vec<Count> {
+    let stop = stopwords_set();
+    let targets: Vec<(String, Vec<String>)> = keywords
+        .iter()
+        .filter_map(|k| {
+            let parts: Vec<String> = k.split_whitespace().map(canonical_token).collect();
+            if parts.is_empty() || parts.iter().any(|p| p.is_empty()) {
+                None
+            } else {
+                Some((k.trim().to_string(), parts))
+            }
+        })
+        .collect();
+    if targets.is_empty() {
+        return Vec::new();
+    }
+
+    let mut counts = vec![0u32; targets.len()];
+    for m in messages {
+        if is_media_omitted_message(&m.text) {
+            continue;
+        }
+        let tokens: Vec<String> = tokenize(&m.text, false, stop)
+            .iter()
+            .map(|t| canonical_token(t))
+            .collect();
+        for (i, (_, parts)) in targets.iter().enumerate() {
+            if tokens.len() >= parts.len() {
+                counts[i] += tokens.windows(parts.len()).filter(|w| w == parts).count() as u32;
+            }
+        }
+    }
+
+    targets
+        .into_iter()
+        .zip(counts)
+        .map(|((label, _), value)| Count { label, value })
+        .collect()
+}
+
+fn canonical_token(token: &str) -> String {
+    token
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+pub(crate) fn word_cloud(
+    messages: &[Message],
+    take: usize,
+    filter_stop: bool,
+    filter_numeric: bool,
+) -> Vec<Count> {
     let stop = stopwords_set();
     let mut map = HashMap::new();
     for m in messages {
         let text = m.text.as_str();
-        if is_media_omitted_message(text) {
+        if is_media_omitted_message(text) || is_poll_message(text) {
             continue;
         }
         for token in tokenize(text, filter_stop, stop) {
-            if token.is_empty() {
+            if token.is_empty() || (filter_numeric && is_numeric_token(&token)) {
                 continue;
             }
             *map.entry(token).or_insert(0u32) += 1;
@@ -223,7 +678,7 @@ pub(crate) fn word_cloud(messages: &[Message], take: usize, filter_stop: bool) -
         .into_iter()
         .map(|(label, value)| Count { label, value })
         .collect();
-    items.sort_by_key(|c| std::cmp::Reverse(c.value));
+    items.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
     items.truncate(take);
     items
 }
@@ -237,7 +692,45 @@ pub(crate) fn emoji_cloud(messages: &[Message], take: usize) -> Vec<Count> {
 /// Extract top phrases from messages.
 /// Note: `filter_stop` is currently unused - phrase detection requires all tokens
 /// for accurate n-gram PMI scoring. The param is kept for API consistency.
-pub(crate) fn top_phrases(messages: &[Message], take: usize, _filter_stop: bool) -> Vec<Count> {
+pub(crate) fn top_phrases(
+    messages: &[Message],
+    take: usize,
+    _filter_stop: bool,
+    max_tokens: usize,
+) -> Vec<Count> {
+    top_phrases_with_examples(messages, take, max_tokens, false)
+        .0
+        .into_iter()
+        .map(|p| Count {
+            label: p.label,
+            value: p.value,
+        })
+        .collect()
+}
+
+/// `top_phrases` over messages containing `?` only: the questions a chat keeps asking
+/// ("what are you doing", "did you eat").
+pub(crate) fn top_question_phrases(
+    messages: &[Message],
+    take: usize,
+    max_tokens: usize,
+) -> Vec<Count> {
+    let questions: Vec<Message> = messages
+        .iter()
+        .filter(|m| m.text.contains('?'))
+        .cloned()
+        .collect();
+    top_phrases(&questions, take, false, max_tokens)
+}
+
+/// `top_phrases` plus the `min_count` floor it applied. With `with_examples`, each
+/// phrase carries the first message it was counted in.
+pub(crate) fn top_phrases_with_examples(
+    messages: &[Message],
+    take: usize,
+    max_tokens: usize,
+    with_examples: bool,
+) -> (Vec<PhraseWithExample>, u32) {
     const MAX_N: usize = 5;
     const PMI_THRESHOLD: f64 = 0.1;
     const SEP: &str = "\x00";
@@ -248,26 +741,30 @@ pub(crate) fn top_phrases(messages: &[Message], take: usize, _filter_stop: bool)
     let mut ngram_counts: HashMap<String, u32> = HashMap::new();
     let mut unigram_counts: HashMap<String, u32> = HashMap::new();
 
-    let mut all_token_lists: Vec<Vec<String>> = Vec::with_capacity(messages.len());
+    let mut first_seen: HashMap<String, &str> = HashMap::new();
+
+    let mut all_token_lists: Vec<(Vec<String>, bool, &str)> = Vec::with_capacity(messages.len());
     for m in messages {
         let text = m.text.as_str();
-        if is_media_omitted_message(text) {
+        if is_media_omitted_message(text) || is_poll_message(text) {
             continue;
         }
         // Always tokenize without stop-word filtering for phrase detection
-        let tokens = tokenize(text, false, stop);
+        let (tokens, capped) = phrase_tokens(text, stop, max_tokens);
         if tokens.is_empty() {
             continue;
         }
         total_tokens += tokens.len() as u32;
-        all_token_lists.push(tokens);
+        all_token_lists.push((tokens, capped, text));
     }
 
+    let min_count = top_phrases_min_count(total_tokens);
     if total_tokens == 0 {
-        return Vec::new();
+        return (Vec::new(), min_count);
     }
 
-    for tokens in &all_token_lists {
+    for &(ref tokens, capped, text) in &all_token_lists {
+        let mut seen: HashSet<String> = HashSet::new();
         let tlen = tokens.len();
         for i in 0..tlen {
             for n in 1..=MAX_N.min(tlen - i) {
@@ -293,6 +790,12 @@ pub(crate) fn top_phrases(messages: &[Message], take: usize, _filter_stop: bool)
                 }
 
                 let key = slice.join(SEP);
+                if capped && n > 1 && !seen.insert(key.clone()) {
+                    continue;
+                }
+                if with_examples && n > 1 {
+                    first_seen.entry(key.clone()).or_insert(text);
+                }
                 *ngram_counts.entry(key).or_insert(0) += 1;
 
                 if n == 1 {
@@ -305,18 +808,6 @@ pub(crate) fn top_phrases(messages: &[Message], take: usize, _filter_stop: bool)
     let total_tokens_f = total_tokens as f64;
     let mut records: Vec<PhraseRecord> = Vec::new();
 
-    let min_count: u32 = if total_tokens > 500000 {
-        5
-    } else if total_tokens > 100000 {
-        4
-    } else if total_tokens > 50000 {
-        3
-    } else if total_tokens > 10000 {
-        2
-    } else {
-        1
-    };
-
     for (key, &count) in ngram_counts.iter() {
         if count < min_count {
             continue;
@@ -380,14 +871,18 @@ pub(crate) fn top_phrases(messages: &[Message], take: usize, _filter_stop: bool)
             .then_with(|| a.phrase.cmp(&b.phrase))
     });
 
-    suppress_subphrases(records, take * 5)
+    let phrases = suppress_subphrases(records, take * 5)
         .into_iter()
         .take(take)
-        .map(|r| Count {
+        .map(|r| PhraseWithExample {
+            example: first_seen
+                .get(&r.tokens.join(SEP))
+                .map(|t| truncate_chars(t, SNIPPET_MAX_CHARS)),
             label: r.phrase,
             value: r.count,
         })
-        .collect()
+        .collect();
+    (phrases, min_count)
 }
 
 /// Extract top phrases per person.
@@ -396,6 +891,7 @@ pub(crate) fn per_person_phrases(
     messages: &[Message],
     take: usize,
     _filter_stop: bool,
+    max_tokens: usize,
 ) -> Vec<PersonPhrases> {
     let min_count: u32 = if messages.len() > 100000 {
         5
@@ -409,14 +905,15 @@ pub(crate) fn per_person_phrases(
     let mut map: HashMap<String, HashMap<String, PhraseData>> = HashMap::new();
 
     for m in messages {
-        if is_media_omitted_message(&m.text) {
+        if is_media_omitted_message(&m.text) || is_poll_message(&m.text) {
             continue;
         }
         // Always tokenize without stop-word filtering for phrase detection
-        let tokens = tokenize(&m.text, false, stop);
+        let (tokens, capped) = phrase_tokens(&m.text, stop, max_tokens);
         if tokens.len() < 2 {
             continue;
         }
+        let mut seen: HashSet<String> = HashSet::new();
         for window in 2..=5 {
             if tokens.len() < window {
                 break;
@@ -446,6 +943,9 @@ pub(crate) fn per_person_phrases(
                 }
 
                 let phrase = slice.join(" ");
+                if capped && !seen.insert(phrase.clone()) {
+                    continue;
+                }
                 let entry = map.entry(m.sender.clone()).or_default();
                 let val = entry.entry(phrase.clone()).or_insert((
                     0u32,
@@ -577,6 +1077,7 @@ struct PhraseRecord {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::text::LONG_MESSAGE_TOKENS;
     use chrono::NaiveDateTime;
 
     fn msg(sender: &str, text: &str) -> Message {
@@ -584,7 +1085,46 @@ mod tests {
             dt: NaiveDateTime::parse_from_str("2023-01-01 10:00:00", "%Y-%m-%d %H:%M:%S").unwrap(),
             sender: sender.to_string(),
             text: text.to_string(),
+            line: 0,
+        }
+    }
+
+    #[test]
+    fn recent_emojis_favor_current_habits() {
+        let at = |text: &str, dt: String| Message {
+            dt: NaiveDateTime::parse_from_str(&dt, "%Y-%m-%d %H:%M:%S").unwrap(),
+            ..msg("A", text)
+        };
+        let mut messages: Vec<Message> = (1..=20)
+            .map(|day| at("old times 🎉", format!("2022-01-{day:02} 10:00:00")))
+            .collect();
+        for day in 1..=5 {
+            messages.push(at("lately 🫠", format!("2024-06-{day:02} 10:00:00")));
         }
+        // All-time, the old favorite wins; weighted by recency, the new one does.
+        assert_eq!(top_emojis(&messages, 1)[0].label, "🎉");
+        let recent = recent_emojis(&messages, 5, RECENT_EMOJI_HALF_LIFE_DAYS);
+        assert_eq!(recent[0].label, "🫠");
+        assert!(recent[0].value > 4.0 && recent[0].value < 5.0);
+        assert!(recent[1].value < 0.1);
+        assert!(recent_emojis(&[], 5, 90.0).is_empty());
+    }
+
+    #[test]
+    fn emoji_pairs_need_three_adjacent_uses() {
+        let texts = [
+            "😂😭",
+            "so funny 😭😂",
+            "😂 😭",
+            "🔥❤️",
+            "❤️🔥",
+            // Not adjacent: never a pair.
+            "🔥 nice ❤️",
+            "🔥 again ❤️",
+        ];
+        let pairs = emoji_pairs(texts, 10);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!((pairs[0].label.as_str(), pairs[0].value), ("😂😭", 3));
     }
 
     #[test]
@@ -609,13 +1149,13 @@ mod tests {
 
     #[test]
     fn top_words_empty() {
-        assert!(top_words(&[], 10, true).is_empty());
+        assert!(top_words(&[], 10, true, true).is_empty());
     }
 
     #[test]
     fn top_words_filters_short_alnum_tokens() {
         let messages = vec![msg("A", "hi ok hello world hello")];
-        let words = top_words(&messages, 10, false);
+        let words = top_words(&messages, 10, false, true);
         let labels: Vec<&str> = words.iter().map(|c| c.label.as_str()).collect();
         // "hi" and "ok" are short (<3) pure-alnum tokens -> dropped.
         assert!(!labels.contains(&"hi"));
@@ -628,7 +1168,7 @@ mod tests {
     #[test]
     fn top_words_skips_media_omitted() {
         let messages = vec![msg("A", "<Media omitted>"), msg("A", "hello world")];
-        let words = top_words(&messages, 10, false);
+        let words = top_words(&messages, 10, false, true);
         let labels: Vec<&str> = words.iter().map(|c| c.label.as_str()).collect();
         assert!(labels.contains(&"hello"));
         assert!(!labels.contains(&"omitted"));
@@ -637,25 +1177,175 @@ mod tests {
     #[test]
     fn top_words_stopword_toggle() {
         let messages = vec![msg("A", "the the hello world")];
-        let with_stop = top_words(&messages, 10, true);
-        let no_stop = top_words(&messages, 10, false);
+        let with_stop = top_words(&messages, 10, true, true);
+        let no_stop = top_words(&messages, 10, false, true);
         assert!(!with_stop.iter().any(|c| c.label == "the"));
         assert!(no_stop.iter().any(|c| c.label == "the"));
     }
 
+    #[test]
+    fn keyword_counts_bypass_stopwords() {
+        let stop = stopwords_set();
+        assert!(stop.contains("you"));
+
+        let messages = vec![
+            msg("A", "love you!"),
+            msg("B", "Love you too, you know"),
+            msg("A", "<Media omitted>"),
+        ];
+        let keywords = vec!["you".to_string(), "love you".to_string()];
+        let counts = keyword_counts(&messages, &keywords);
+        assert_eq!(counts[0].label, "you");
+        assert_eq!(counts[0].value, 3);
+        assert_eq!(counts[1].label, "love you");
+        assert_eq!(counts[1].value, 2);
+    }
+
+    #[test]
+    fn keyword_counts_skips_blank_keywords() {
+        let messages = vec![msg("A", "hello")];
+        assert!(keyword_counts(&messages, &["  ".to_string()]).is_empty());
+    }
+
     #[test]
     fn word_cloud_empty() {
-        assert!(word_cloud(&[], 10, true).is_empty());
+        assert!(word_cloud(&[], 10, true, true).is_empty());
+    }
+
+    #[test]
+    fn word_origins_credit_the_first_user() {
+        let mut script = vec![
+            // Inside the first 1% of the chat: origin unknowable.
+            ("Alice", "sunshine everyone"),
+            ("Carol", "hello"),
+            ("Bob", "that movie was bussin"),
+            ("Carol", "ask bob"),
+            ("Dana", "meh"),
+        ];
+        for _ in 0..5 {
+            script.push(("Alice", "bussin honestly"));
+            script.push(("Carol", "sunshine bob"));
+            script.push(("Bob", "bussin"));
+        }
+        for _ in 0..4 {
+            script.push(("Alice", "meh"));
+        }
+        while script.len() < 200 {
+            script.push(("Carol", "filler"));
+        }
+        let messages: Vec<Message> = script
+            .into_iter()
+            .enumerate()
+            .map(|(line, (sender, text))| Message {
+                line,
+                ..msg(sender, text)
+            })
+            .collect();
+
+        let origins = word_origins(&tokenize_messages(&messages));
+        let found: Vec<(&str, &str, u32)> = origins
+            .iter()
+            .map(|o| (o.word.as_str(), o.coined_by.as_str(), o.adopted_count))
+            .collect();
+        assert_eq!(found, vec![("bussin", "Bob", 5)]);
+        assert_eq!(origins[0].first_date, "2023-01-01");
+    }
+
+    #[test]
+    fn word_origins_merge_punctuated_variants() {
+        let mut script = vec![("Bob", "yeet alice")];
+        script.extend([("Alice", "yeet! alice,"); 3]);
+        script.extend([("Carol", "yeet, alice?"); 2]);
+        let messages: Vec<Message> = script
+            .into_iter()
+            .enumerate()
+            .map(|(line, (sender, text))| Message {
+                line,
+                ..msg(sender, text)
+            })
+            .collect();
+
+        let origins = word_origins(&tokenize_messages(&messages));
+        let found: Vec<(&str, &str, u32)> = origins
+            .iter()
+            .map(|o| (o.word.as_str(), o.coined_by.as_str(), o.adopted_count))
+            .collect();
+        assert_eq!(found, vec![("yeet", "Bob", 5)]);
+    }
+
+    #[test]
+    fn vocab_drift_finds_rising_and_falling_words() {
+        let on = |date: &str, text: &str| Message {
+            dt: NaiveDateTime::parse_from_str(&format!("{date} 10:00:00"), "%Y-%m-%d %H:%M:%S")
+                .unwrap(),
+            ..msg("A", text)
+        };
+        let mut messages = Vec::new();
+        for _ in 0..6 {
+            messages.push(on("2024-01-15", "pizza tonight maybe"));
+            messages.push(on("2024-04-15", "sushi tonight maybe"));
+        }
+        // Under the minimum count: too rare to report either way.
+        messages.push(on("2024-01-20", "kayak"));
+        messages.push(on("2024-04-20", "canoe"));
+
+        let drift = vocab_drift(&tokenize_messages(&messages));
+        assert_eq!(drift.len(), 1);
+        assert_eq!(drift[0].period, "2024-Q2");
+        let labels = |list: &[Count]| -> Vec<(String, u32)> {
+            list.iter().map(|c| (c.label.clone(), c.value)).collect()
+        };
+        assert_eq!(labels(&drift[0].rising), vec![("sushi".to_string(), 6)]);
+        assert_eq!(labels(&drift[0].falling), vec![("pizza".to_string(), 6)]);
+        assert!(vocab_drift(&tokenize_messages(&messages[..1])).is_empty());
+    }
+
+    #[test]
+    fn vocab_drift_merges_punctuated_variants() {
+        let on = |date: &str, text: &str| Message {
+            dt: NaiveDateTime::parse_from_str(&format!("{date} 10:00:00"), "%Y-%m-%d %H:%M:%S")
+                .unwrap(),
+            ..msg("A", text)
+        };
+        let mut messages = Vec::new();
+        for _ in 0..3 {
+            messages.push(on("2024-01-15", "pizza tonight"));
+            messages.push(on("2024-01-16", "pizza? tonight"));
+            messages.push(on("2024-04-15", "sushi tonight"));
+            messages.push(on("2024-04-16", "sushi!! tonight"));
+        }
+
+        let drift = vocab_drift(&tokenize_messages(&messages));
+        let labels = |list: &[Count]| -> Vec<(String, u32)> {
+            list.iter().map(|c| (c.label.clone(), c.value)).collect()
+        };
+        assert_eq!(labels(&drift[0].rising), vec![("sushi".to_string(), 6)]);
+        assert_eq!(labels(&drift[0].falling), vec![("pizza".to_string(), 6)]);
     }
 
     #[test]
     fn word_cloud_counts_words() {
         let messages = vec![msg("A", "apple apple banana")];
-        let cloud = word_cloud(&messages, 10, false);
+        let cloud = word_cloud(&messages, 10, false, true);
         let apple = cloud.iter().find(|c| c.label == "apple").unwrap();
         assert_eq!(apple.value, 2);
     }
 
+    #[test]
+    fn numeric_tokens_filtered_from_word_lists_when_enabled() {
+        let messages = vec![msg("A", "dinner at 7pm on the 3rd, 2023 was great <3")];
+        let words = top_words(&messages, 20, false, true);
+        let labels: Vec<&str> = words.iter().map(|c| c.label.as_str()).collect();
+        assert!(!labels.contains(&"7pm"));
+        assert!(!labels.contains(&"3rd,"));
+        assert!(!labels.contains(&"2023"));
+        assert!(labels.contains(&"<3"));
+        assert!(labels.contains(&"dinner"));
+
+        let cloud = word_cloud(&messages, 20, false, false);
+        assert!(cloud.iter().any(|c| c.label == "7pm"));
+    }
+
     #[test]
     fn emoji_cloud_truncates() {
         let messages = vec![msg("A", "😀 😢 👍 ❤️")];
@@ -663,9 +1353,58 @@ mod tests {
         assert!(cloud.len() <= 2);
     }
 
+    #[test]
+    fn emoji_categories_group_hearts_and_faces() {
+        assert_eq!(emoji_category("❤️"), "hearts");
+        assert_eq!(emoji_category("💔"), "hearts");
+        assert_eq!(emoji_category("🙏"), "hands");
+        assert_eq!(emoji_category("😂"), "faces");
+        assert_eq!(emoji_category("🐶"), "animals");
+        assert_eq!(emoji_category("🇧🇷"), "flags");
+
+        let messages = vec![msg("A", "❤️ 💔 😂"), msg("B", "❤️ 🎉")];
+        let categories = emoji_categories(&messages);
+        assert_eq!(categories[0].label, "hearts");
+        assert_eq!(categories[0].value, 3);
+        assert!(categories.iter().all(|c| c.label != "animals"));
+    }
+
+    #[test]
+    fn per_person_emoji_monthly_tracks_favourite_switch() {
+        let at = |sender: &str, text: &str, dt: &str| Message {
+            dt: NaiveDateTime::parse_from_str(dt, "%Y-%m-%d %H:%M:%S").unwrap(),
+            ..msg(sender, text)
+        };
+        let messages = vec![
+            at("Alice", "😂😂😂 ❤️", "2024-01-05 10:00:00"),
+            at("Alice", "😂", "2024-01-20 10:00:00"),
+            at("Alice", "no emoji this month", "2024-02-10 10:00:00"),
+            at("Alice", "🔥🔥 😂", "2024-03-01 10:00:00"),
+            at("Bob", "👍", "2024-01-06 10:00:00"),
+        ];
+        let diary = per_person_emoji_monthly(&messages);
+        assert_eq!(diary.len(), 2);
+        let alice = &diary[0];
+        assert_eq!(alice.name, "Alice");
+        let months: Vec<&str> = alice.monthly.iter().map(|m| m.month.as_str()).collect();
+        assert_eq!(months, ["2024-01", "2024-03"]);
+        assert_eq!(alice.monthly[0].top[0].label, "😂");
+        assert_eq!(alice.monthly[0].top[0].value, 4);
+        assert_eq!(alice.monthly[1].top[0].label, "🔥");
+        assert_eq!(diary[1].monthly[0].top[0].label, "👍");
+    }
+
+    #[test]
+    fn per_person_emoji_monthly_keeps_top_three() {
+        let messages = vec![msg("A", "😀😀😀😀 😢😢😢 👍👍 ❤️")];
+        let diary = per_person_emoji_monthly(&messages);
+        assert_eq!(diary[0].monthly[0].top.len(), 3);
+        assert!(diary[0].monthly[0].top.iter().all(|c| c.label != "❤️"));
+    }
+
     #[test]
     fn top_phrases_empty() {
-        assert!(top_phrases(&[], 10, true).is_empty());
+        assert!(top_phrases(&[], 10, true, LONG_MESSAGE_TOKENS).is_empty());
     }
 
     #[test]
@@ -674,19 +1413,87 @@ mod tests {
             msg("A", "hello world hello world"),
             msg("A", "hello world again"),
         ];
-        let phrases = top_phrases(&messages, 10, true);
+        let phrases = top_phrases(&messages, 10, true, LONG_MESSAGE_TOKENS);
         assert!(phrases.iter().any(|c| c.label == "hello world"));
     }
 
+    #[test]
+    fn top_question_phrases_only_read_questions() {
+        let messages = vec![
+            msg("A", "did you eat?"),
+            msg("B", "yes pasta"),
+            msg("A", "did you eat? it's late"),
+            msg("B", "good night good night"),
+            msg("B", "good night"),
+            msg("A", "hey did you eat"),
+            msg("B", "so did you eat?"),
+        ];
+        let questions = top_question_phrases(&messages, 10, LONG_MESSAGE_TOKENS);
+        let eat = questions
+            .iter()
+            .find(|c| c.label == "did you eat?")
+            .unwrap();
+        assert_eq!(eat.value, 3);
+        assert!(!questions.iter().any(|c| c.label == "good night"));
+    }
+
+    #[test]
+    fn top_phrase_examples_contain_the_phrase() {
+        let messages = vec![
+            msg("A", "Good Morning sunshine"),
+            msg("B", "good morning to you too"),
+            msg("A", "good morning again"),
+        ];
+        let (phrases, _) = top_phrases_with_examples(&messages, 10, LONG_MESSAGE_TOKENS, true);
+        let phrase = phrases
+            .iter()
+            .find(|p| p.label == "good morning")
+            .expect("repeated bigram is a top phrase");
+        let example = phrase.example.as_deref().unwrap();
+        assert_eq!(example, "Good Morning sunshine");
+        assert!(example.to_lowercase().contains(&phrase.label));
+
+        let (plain, _) = top_phrases_with_examples(&messages, 10, LONG_MESSAGE_TOKENS, false);
+        assert!(plain.iter().all(|p| p.example.is_none()));
+    }
+
     #[test]
     fn top_phrases_ignores_media_only() {
         let messages = vec![msg("A", "<Media omitted>"), msg("A", "<Media omitted>")];
-        assert!(top_phrases(&messages, 10, true).is_empty());
+        assert!(top_phrases(&messages, 10, true, LONG_MESSAGE_TOKENS).is_empty());
+    }
+
+    #[test]
+    fn long_pasted_message_does_not_dominate_phrases() {
+        // 5,000 tokens of one lyric line on repeat, plus a phrase the chat actually uses.
+        let lyrics = "never gonna give you up ".repeat(1000);
+        let mut messages = vec![msg("A", &lyrics)];
+        for _ in 0..3 {
+            messages.push(msg("B", "pizza night tomorrow"));
+        }
+        let most_repeated = |phrases: &[Count]| {
+            phrases
+                .iter()
+                .max_by_key(|c| c.value)
+                .map(|c| c.label.clone())
+        };
+        let uncapped = top_phrases(&messages, 50, true, usize::MAX);
+        assert!(most_repeated(&uncapped).unwrap().contains("gonna"));
+
+        let phrases = top_phrases(&messages, 50, true, LONG_MESSAGE_TOKENS);
+        assert_eq!(most_repeated(&phrases).unwrap(), "pizza night tomorrow");
+        assert!(phrases
+            .iter()
+            .filter(|c| c.label.contains("gonna"))
+            .all(|c| c.value == 1));
+
+        let salient = salient_phrases(&messages, 10, LONG_MESSAGE_TOKENS);
+        assert!(salient.iter().all(|c| !c.label.contains("gonna")));
     }
 
     #[test]
     fn per_person_phrases_empty() {
-        assert!(per_person_phrases(&[], 10, true).is_empty());
+        assert!(per_person_phrases(&[], 10, true, LONG_MESSAGE_TOKENS).is_empty());
     }
 
     #[test]
@@ -697,7 +1504,7 @@ mod tests {
             msg("Bob", "see you later alligator"),
             msg("Bob", "see you later alligator"),
         ];
-        let pp = per_person_phrases(&messages, 10, true);
+        let pp = per_person_phrases(&messages, 10, true, LONG_MESSAGE_TOKENS);
         let alice = pp.iter().find(|p| p.name == "Alice").unwrap();
         let bob = pp.iter().find(|p| p.name == "Bob").unwrap();
         assert!(alice.phrases.iter().any(|c| c.label.contains("morning")));
@@ -708,7 +1515,7 @@ mod tests {
 
     #[test]
     fn salient_phrases_empty() {
-        assert!(salient_phrases(&[], 10).is_empty());
+        assert!(salient_phrases(&[], 10, LONG_MESSAGE_TOKENS).is_empty());
     }
 
     #[test]
@@ -721,11 +1528,21 @@ mod tests {
             msg("A", "quantum entanglement feels magical"),
             msg("A", "quantum entanglement again"),
         ];
-        let salient = salient_phrases(&messages, 10);
+        let salient = salient_phrases(&messages, 10, LONG_MESSAGE_TOKENS);
         assert!(!salient.is_empty());
         assert!(salient.iter().any(|c| c.label == "quantum entanglement"));
     }
 
+    #[test]
+    fn min_counts_scale_with_volume() {
+        assert_eq!(salient_min_count(100), 2);
+        assert_eq!(salient_min_count(10_001), 3);
+        assert_eq!(salient_min_count(100_001), 5);
+        assert_eq!(top_phrases_min_count(0), 1);
+        assert_eq!(top_phrases_min_count(10_001), 2);
+        assert_eq!(top_phrases_min_count(500_001), 5);
+    }
+
     #[test]
     fn contains_subsequence_basic() {
         let long = vec!["a".to_string(), "b".to_string(), "c".to_string()];
@@ -0,0 +1,247 @@
+/// Samples kept verbatim for exact percentiles; past this the P² estimator takes over
+/// so memory stays constant however long the chat is.
+const EXACT_LIMIT: usize = 100_000;
+
+/// The percentiles reported next to medians.
+const TRACKED: [f64; 3] = [0.25, 0.75, 0.95];
+
+/// p25/p75/p95 of a sample; all zero for an empty one.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Percentiles {
+    pub(crate) p25: f32,
+    pub(crate) p75: f32,
+    pub(crate) p95: f32,
+}
+
+impl Percentiles {
+    /// Exact percentiles of values already held in memory, sorted ascending.
+    pub(crate) fn of_sorted(sorted: &[f32]) -> Self {
+        let [p25, p75, p95] = TRACKED.map(|p| exact_quantile(sorted, p));
+        Percentiles { p25, p75, p95 }
+    }
+}
+
+/// Collects values and reports `Percentiles`: exact (linearly interpolated) up to
+/// `EXACT_LIMIT` samples, then a P² streaming estimate. Both paths depend only on the
+/// order values arrive in, so results are deterministic.
+pub(crate) struct QuantileSketch {
+    exact: Vec<f32>,
+    streams: Option<[P2; 3]>,
+}
+
+impl QuantileSketch {
+    pub(crate) fn new() -> Self {
+        QuantileSketch {
+            exact: Vec::new(),
+            streams: None,
+        }
+    }
+
+    pub(crate) fn push(&mut self, value: f32) {
+        if let Some(streams) = &mut self.streams {
+            streams.iter_mut().for_each(|s| s.push(value as f64));
+            return;
+        }
+        self.exact.push(value);
+        if self.exact.len() > EXACT_LIMIT {
+            let mut streams = TRACKED.map(P2::new);
+            for &v in &self.exact {
+                streams.iter_mut().for_each(|s| s.push(v as f64));
+            }
+            self.streams = Some(streams);
+            self.exact = Vec::new();
+        }
+    }
+
+    pub(crate) fn percentiles(&self) -> Percentiles {
+        match &self.streams {
+            Some(streams) => {
+                let [p25, p75, p95] = streams.each_ref().map(|s| s.estimate() as f32);
+                Percentiles { p25, p75, p95 }
+            }
+            None => {
+                let mut sorted = self.exact.clone();
+                sorted.sort_by(|a, b| a.total_cmp(b));
+                Percentiles::of_sorted(&sorted)
+            }
+        }
+    }
+}
+
+impl FromIterator<f32> for QuantileSketch {
+    fn from_iter<I: IntoIterator<Item = f32>>(iter: I) -> Self {
+        let mut sketch = QuantileSketch::new();
+        iter.into_iter().for_each(|v| sketch.push(v));
+        sketch
+    }
+}
+
+/// Linear interpolation between the closest ranks of an ascending slice.
+fn exact_quantile(sorted: &[f32], p: f64) -> f32 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = p * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    let frac = (rank - lower as f64) as f32;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * frac
+}
+
+/// Jain & Chlamtac's P² estimator: five markers track one quantile in O(1) memory.
+#[derive(Debug, Clone)]
+struct P2 {
+    p: f64,
+    count: usize,
+    /// Marker heights.
+    q: [f64; 5],
+    /// Actual marker positions (1-based ranks).
+    n: [f64; 5],
+    /// Desired marker positions and their per-sample increments.
+    desired: [f64; 5],
+    step: [f64; 5],
+}
+
+impl P2 {
+    fn new(p: f64) -> Self {
+        P2 {
+            p,
+            count: 0,
+            q: [0.0; 5],
+            n: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            step: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    fn push(&mut self, x: f64) {
+        if self.count < 5 {
+            self.q[self.count] = x;
+            self.count += 1;
+            if self.count == 5 {
+                self.q.sort_by(|a, b| a.total_cmp(b));
+            }
+            return;
+        }
+        self.count += 1;
+
+        let k = if x < self.q[0] {
+            self.q[0] = x;
+            0
+        } else if x >= self.q[4] {
+            self.q[4] = x;
+            3
+        } else {
+            (0..4).find(|&i| x < self.q[i + 1]).unwrap_or(3)
+        };
+        for pos in &mut self.n[k + 1..] {
+            *pos += 1.0;
+        }
+        for (d, s) in self.desired.iter_mut().zip(self.step) {
+            *d += s;
+        }
+
+        for i in 1..4 {
+            let d = self.desired[i] - self.n[i];
+            if (d >= 1.0 && self.n[i + 1] - self.n[i] > 1.0)
+                || (d <= -1.0 && self.n[i - 1] - self.n[i] < -1.0)
+            {
+                let d = d.signum();
+                let parabolic = self.parabolic(i, d);
+                self.q[i] = if self.q[i - 1] < parabolic && parabolic < self.q[i + 1] {
+                    parabolic
+                } else {
+                    self.linear(i, d)
+                };
+                self.n[i] += d;
+            }
+        }
+    }
+
+    fn parabolic(&self, i: usize, d: f64) -> f64 {
+        let (q, n) = (&self.q, &self.n);
+        q[i] + d / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    fn linear(&self, i: usize, d: f64) -> f64 {
+        let j = if d > 0.0 { i + 1 } else { i - 1 };
+        self.q[i] + d * (self.q[j] - self.q[i]) / (self.n[j] - self.n[i])
+    }
+
+    fn estimate(&self) -> f64 {
+        if self.count >= 5 {
+            return self.q[2];
+        }
+        // Fewer than five samples: fall back to the exact answer.
+        let mut seen: Vec<f32> = self.q[..self.count].iter().map(|&v| v as f32).collect();
+        seen.sort_by(|a, b| a.total_cmp(b));
+        exact_quantile(&seen, self.p) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Small deterministic LCG so the test sample is stable across runs.
+    fn seeded_sample(len: usize, mut seed: u64) -> Vec<f32> {
+        (0..len)
+            .map(|_| {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                // Skewed like reply times: most small, a long tail.
+                let u = (seed >> 33) as f32 / (1u64 << 31) as f32;
+                u * u * 600.0
+            })
+            .collect()
+    }
+
+    #[test]
+    fn exact_percentiles_interpolate() {
+        let sketch: QuantileSketch = [1.0, 2.0, 3.0, 4.0, 5.0].into_iter().collect();
+        let p = sketch.percentiles();
+        assert_eq!(p.p25, 2.0);
+        assert_eq!(p.p75, 4.0);
+        assert!((p.p95 - 4.8).abs() < 1e-6);
+        assert_eq!(QuantileSketch::new().percentiles(), Percentiles::default());
+    }
+
+    #[test]
+    fn p2_estimate_tracks_exact_quantiles() {
+        let sample = seeded_sample(50_000, 42);
+        let mut sorted = sample.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        for p in TRACKED {
+            let mut p2 = P2::new(p);
+            sample.iter().for_each(|&v| p2.push(v as f64));
+            let exact = exact_quantile(&sorted, p) as f64;
+            // Within 2% of the value range.
+            assert!(
+                (p2.estimate() - exact).abs() < 12.0,
+                "p{p}: {} vs {exact}",
+                p2.estimate()
+            );
+        }
+    }
+
+    #[test]
+    fn sketch_switches_to_streaming_past_the_limit() {
+        let sample = seeded_sample(EXACT_LIMIT + 1000, 7);
+        let streamed: QuantileSketch = sample.iter().copied().collect();
+        assert!(streamed.streams.is_some());
+        assert!(streamed.exact.is_empty());
+
+        let exact: QuantileSketch = sample[..EXACT_LIMIT].iter().copied().collect();
+        let (a, b) = (streamed.percentiles(), exact.percentiles());
+        assert!((a.p25 - b.p25).abs() < 12.0);
+        assert!((a.p75 - b.p75).abs() < 12.0);
+        assert!((a.p95 - b.p95).abs() < 12.0);
+        // Same input, same answer.
+        let again: QuantileSketch = sample.iter().copied().collect();
+        assert_eq!(again.percentiles(), a);
+    }
+}
@@ -0,0 +1,79 @@
+use chrono::Datelike;
+use std::collections::BTreeMap;
+
+use crate::parsing::Message;
+
+/// A deterministic `fraction` of `messages`, drawn separately within each calendar
+/// month so the sample keeps the chat's shape over time. Every month with messages
+/// keeps at least one. The same `seed` picks the same messages; order is preserved.
+pub(crate) fn sample_messages(messages: &[Message], fraction: f32, seed: u32) -> Vec<Message> {
+    let mut by_month: BTreeMap<(i32, u32), Vec<usize>> = BTreeMap::new();
+    for (i, m) in messages.iter().enumerate() {
+        by_month
+            .entry((m.dt.year(), m.dt.month()))
+            .or_default()
+            .push(i);
+    }
+
+    let mut keep: Vec<usize> = Vec::new();
+    for indices in by_month.into_values() {
+        let take = ((indices.len() as f32 * fraction).round() as usize).clamp(1, indices.len());
+        let mut ranked: Vec<(u64, usize)> = indices
+            .into_iter()
+            .map(|i| (mix(seed as u64, i as u64), i))
+            .collect();
+        ranked.sort_unstable();
+        keep.extend(ranked.into_iter().take(take).map(|(_, i)| i));
+    }
+    keep.sort_unstable();
+    keep.into_iter().map(|i| messages[i].clone()).collect()
+}
+
+/// SplitMix64 of `seed` and `index`: a cheap, well-spread per-message rank.
+fn mix(seed: u64, index: u64) -> u64 {
+    let mut z = seed
+        .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        .wrapping_add(index)
+        .wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn month_of_messages(month: u32, count: u32) -> Vec<Message> {
+        (0..count)
+            .map(|i| Message {
+                dt: NaiveDate::from_ymd_opt(2024, month, 1)
+                    .unwrap()
+                    .and_hms_opt(10, 0, 0)
+                    .unwrap()
+                    + chrono::Duration::minutes(i as i64),
+                sender: "A".into(),
+                text: format!("message {month}-{i}"),
+                line: 0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sample_is_stratified_by_month_and_seeded() {
+        let mut messages = month_of_messages(1, 200);
+        messages.extend(month_of_messages(2, 20));
+        messages.extend(month_of_messages(3, 3));
+
+        let sample = sample_messages(&messages, 0.1, 42);
+        let in_month = |m: u32| sample.iter().filter(|s| s.dt.month() == m).count();
+        assert_eq!((in_month(1), in_month(2), in_month(3)), (20, 2, 1));
+        assert!(sample.windows(2).all(|w| w[0].dt < w[1].dt));
+
+        let texts = |s: &[Message]| s.iter().map(|m| m.text.clone()).collect::<Vec<_>>();
+        assert_eq!(texts(&sample), texts(&sample_messages(&messages, 0.1, 42)));
+        assert_ne!(texts(&sample), texts(&sample_messages(&messages, 0.1, 7)));
+        assert_eq!(sample_messages(&messages, 1.0, 42).len(), messages.len());
+    }
+}
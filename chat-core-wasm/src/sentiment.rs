@@ -1,10 +1,12 @@
 use once_cell::sync::OnceCell;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::parsing::Message;
+use crate::parsing::{sorted_view, Message};
 use crate::text::extract_emojis;
-use crate::types::{SentimentDay, SentimentOverall};
+use crate::types::{
+    Count, EmojiCloudItem, MessageSentiment, SentimentDay, SentimentExchange, SentimentOverall,
+};
 
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum SentimentClass {
@@ -13,6 +15,28 @@ pub(crate) enum SentimentClass {
     Negative,
 }
 
+/// Lower bounds on the positive share of non-neutral messages for each
+/// `dominant_emotion` label, checked in order; anything lower is "Mostly negative".
+const EMOTION_LABELS: [(f32, &str); 4] = [
+    (0.75, "Mostly positive"),
+    (0.55, "Leaning positive"),
+    (0.45, "Balanced"),
+    (0.25, "Often negative"),
+];
+
+/// A readable label for a person's tone, from how their positive and negative messages
+/// compare. Neutral messages don't count; someone with none of either is "Neutral".
+fn dominant_emotion(pos: u32, neg: u32) -> &'static str {
+    if pos + neg == 0 {
+        return "Neutral";
+    }
+    let positive_share = pos as f32 / (pos + neg) as f32;
+    EMOTION_LABELS
+        .iter()
+        .find(|(min, _)| positive_share >= *min)
+        .map_or("Mostly negative", |(_, label)| label)
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 pub(crate) struct SentimentAgg {
     sum: f32,
@@ -40,6 +64,37 @@ impl SentimentAgg {
             self.sum / self.count as f32
         }
     }
+
+    fn into_overall(self, name: String) -> SentimentOverall {
+        SentimentOverall {
+            name,
+            mean: self.mean(),
+            pos: self.pos,
+            neu: self.neu,
+            neg: self.neg,
+            dominant_emotion: dominant_emotion(self.pos, self.neg).to_string(),
+        }
+    }
+
+    /// Rebuilds an aggregate from a serialized mean and class counts.
+    fn from_parts(mean: f32, pos: u32, neu: u32, neg: u32) -> Self {
+        let count = pos + neu + neg;
+        SentimentAgg {
+            sum: mean * count as f32,
+            count,
+            pos,
+            neu,
+            neg,
+        }
+    }
+
+    fn absorb(&mut self, other: SentimentAgg) {
+        self.sum += other.sum;
+        self.count += other.count;
+        self.pos += other.pos;
+        self.neu += other.neu;
+        self.neg += other.neg;
+    }
 }
 
 fn sentiment_lexicons() -> (
@@ -53,10 +108,14 @@ fn sentiment_lexicons() -> (
     (pos, neg)
 }
 
-pub(crate) fn sentiment_score(text: &str) -> (f32, SentimentClass) {
+/// Sentiment weights (-1 to 1) for specific emoji, from
+/// `AnalyzeOptions::emoji_sentiment`. They replace the built-in emoji polarity.
+pub(crate) type EmojiWeights = HashMap<String, f32>;
+
+pub(crate) fn sentiment_score(text: &str, emoji_weights: &EmojiWeights) -> (f32, SentimentClass) {
     let (pos_words, neg_words) = sentiment_lexicons();
 
-    let mut score: i32 = 0;
+    let mut score: f32 = 0.0;
     let mut hits: u32 = 0;
 
     for token in text.unicode_words() {
@@ -67,20 +126,18 @@ pub(crate) fn sentiment_score(text: &str) -> (f32, SentimentClass) {
             continue;
         }
         if pos_words.contains(cleaned.as_str()) {
-            score += 2;
+            score += 2.0;
             hits += 1;
         } else if neg_words.contains(cleaned.as_str()) {
-            score -= 2;
+            score -= 2.0;
             hits += 1;
         }
     }
 
     for glyph in extract_emojis(text) {
-        if POSITIVE_EMOJIS.contains(&glyph.as_str()) {
-            score += 2;
-            hits += 1;
-        } else if NEGATIVE_EMOJIS.contains(&glyph.as_str()) {
-            score -= 2;
+        let weight = emoji_weight(&glyph, emoji_weights);
+        if weight != 0.0 {
+            score += 2.0 * weight;
             hits += 1;
         }
     }
@@ -88,7 +145,7 @@ pub(crate) fn sentiment_score(text: &str) -> (f32, SentimentClass) {
     let compound = if hits == 0 {
         0.0
     } else {
-        (score as f32) / (hits as f32 * 2.0)
+        score / (hits as f32 * 2.0)
     }
     .clamp(-1.0, 1.0);
 
@@ -96,6 +153,50 @@ pub(crate) fn sentiment_score(text: &str) -> (f32, SentimentClass) {
     (compound, class)
 }
 
+/// +1 for emoji in the positive lexicon, -1 for the negative one, 0 otherwise.
+pub(crate) fn emoji_polarity(glyph: &str) -> i8 {
+    if POSITIVE_EMOJIS.contains(&glyph) {
+        1
+    } else if NEGATIVE_EMOJIS.contains(&glyph) {
+        -1
+    } else {
+        0
+    }
+}
+
+/// The user's weight for `glyph` if they set one, else its lexicon polarity.
+fn emoji_weight(glyph: &str, emoji_weights: &EmojiWeights) -> f32 {
+    emoji_weights
+        .get(glyph)
+        .copied()
+        .unwrap_or_else(|| emoji_polarity(glyph) as f32)
+}
+
+/// Tags each emoji cloud entry with its polarity (the sign of any custom weight) for
+/// frontend coloring.
+pub(crate) fn emoji_cloud_sentiment(
+    cloud: &[Count],
+    emoji_weights: &EmojiWeights,
+) -> Vec<EmojiCloudItem> {
+    cloud
+        .iter()
+        .map(|c| {
+            let weight = emoji_weight(&c.label, emoji_weights);
+            EmojiCloudItem {
+                label: c.label.clone(),
+                value: c.value,
+                sentiment: if weight > 0.0 {
+                    1
+                } else if weight < 0.0 {
+                    -1
+                } else {
+                    0
+                },
+            }
+        })
+        .collect()
+}
+
 pub(crate) fn classify_sentiment(compound: f32) -> SentimentClass {
     if compound > 0.05 {
         SentimentClass::Positive
@@ -106,20 +207,35 @@ pub(crate) fn classify_sentiment(compound: f32) -> SentimentClass {
     }
 }
 
+pub(crate) struct SentimentBreakdown {
+    pub(crate) by_day: Vec<SentimentDay>,
+    pub(crate) overall: Vec<SentimentOverall>,
+    pub(crate) exchange: Option<Vec<SentimentExchange>>,
+    /// Mean compound score over every message, so busier senders weigh more.
+    pub(crate) chat_mean: f32,
+}
+
 pub(crate) fn sentiment_breakdown(
     messages: &[Message],
-) -> (Vec<SentimentDay>, Vec<SentimentOverall>) {
+    emoji_weights: &EmojiWeights,
+) -> SentimentBreakdown {
     if messages.is_empty() {
-        return (Vec::new(), Vec::new());
+        return SentimentBreakdown {
+            by_day: Vec::new(),
+            overall: Vec::new(),
+            exchange: None,
+            chat_mean: 0.0,
+        };
     }
 
     let mut per_day: std::collections::HashMap<(String, String), SentimentAgg> =
         std::collections::HashMap::new();
     let mut per_person: std::collections::HashMap<String, SentimentAgg> =
         std::collections::HashMap::new();
+    let mut whole_chat = SentimentAgg::default();
 
     for m in messages {
-        let (compound, class) = sentiment_score(&m.text);
+        let (compound, class) = sentiment_score(&m.text, emoji_weights);
         let day = m.dt.date().format("%Y-%m-%d").to_string();
 
         let entry = per_day.entry((m.sender.clone(), day.clone())).or_default();
@@ -129,6 +245,7 @@ pub(crate) fn sentiment_breakdown(
             .entry(m.sender.clone())
             .or_default()
             .push(compound, class);
+        whole_chat.push(compound, class);
     }
 
     let mut sentiment_by_day: Vec<SentimentDay> = per_day
@@ -145,15 +262,11 @@ pub(crate) fn sentiment_breakdown(
 
     sentiment_by_day.sort_by(|a, b| a.day.cmp(&b.day).then_with(|| a.name.cmp(&b.name)));
 
+    let exchange = sentiment_exchange(&per_person);
+
     let mut sentiment_overall: Vec<SentimentOverall> = per_person
         .into_iter()
-        .map(|(name, agg)| SentimentOverall {
-            name,
-            mean: agg.mean(),
-            pos: agg.pos,
-            neu: agg.neu,
-            neg: agg.neg,
-        })
+        .map(|(name, agg)| agg.into_overall(name))
         .collect();
 
     sentiment_overall.sort_by(|a, b| {
@@ -163,7 +276,113 @@ pub(crate) fn sentiment_breakdown(
             .then_with(|| a.name.cmp(&b.name))
     });
 
-    (sentiment_by_day, sentiment_overall)
+    SentimentBreakdown {
+        by_day: sentiment_by_day,
+        overall: sentiment_overall,
+        exchange,
+        chat_mean: whole_chat.mean(),
+    }
+}
+
+/// The compound score of every message, in chronological order, for plotting or
+/// re-analysis outside the app.
+pub(crate) fn per_message_scores(
+    messages: &[Message],
+    emoji_weights: &EmojiWeights,
+) -> Vec<MessageSentiment> {
+    sorted_view(messages)
+        .iter()
+        .map(|m| MessageSentiment {
+            timestamp: m.dt.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            sender: m.sender.clone(),
+            score: sentiment_score(&m.text, emoji_weights).0,
+        })
+        .collect()
+}
+
+/// Combines per-person sentiment from two summaries by message-weighting each mean, and
+/// recomputes the DM exchange from the result.
+pub(crate) fn merge_sentiment_overall(
+    a: Vec<SentimentOverall>,
+    b: Vec<SentimentOverall>,
+) -> (Vec<SentimentOverall>, Option<Vec<SentimentExchange>>) {
+    let mut per_person: std::collections::HashMap<String, SentimentAgg> =
+        std::collections::HashMap::new();
+    for o in a.into_iter().chain(b) {
+        per_person
+            .entry(o.name)
+            .or_default()
+            .absorb(SentimentAgg::from_parts(o.mean, o.pos, o.neu, o.neg));
+    }
+
+    let exchange = sentiment_exchange(&per_person);
+    let mut overall: Vec<SentimentOverall> = per_person
+        .into_iter()
+        .map(|(name, agg)| agg.into_overall(name))
+        .collect();
+    overall.sort_by(|a, b| {
+        b.mean
+            .partial_cmp(&a.mean)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    (overall, exchange)
+}
+
+/// Combines per-(person, day) sentiment from two summaries, message-weighted.
+pub(crate) fn merge_sentiment_by_day(
+    a: Vec<SentimentDay>,
+    b: Vec<SentimentDay>,
+) -> Vec<SentimentDay> {
+    let mut per_day: std::collections::BTreeMap<(String, String), SentimentAgg> =
+        std::collections::BTreeMap::new();
+    for d in a.into_iter().chain(b) {
+        per_day
+            .entry((d.day, d.name))
+            .or_default()
+            .absorb(SentimentAgg::from_parts(d.mean, d.pos, d.neu, d.neg));
+    }
+    per_day
+        .into_iter()
+        .map(|((day, name), agg)| SentimentDay {
+            name,
+            day,
+            mean: agg.mean(),
+            pos: agg.pos,
+            neu: agg.neu,
+            neg: agg.neg,
+        })
+        .collect()
+}
+
+/// In a two-person chat everything one person writes is addressed to the other, so the
+/// sentiment a person "receives" is simply the other participant's outgoing mean.
+/// Group chats have no such pairing and yield `None`.
+fn sentiment_exchange(
+    per_person: &std::collections::HashMap<String, SentimentAgg>,
+) -> Option<Vec<SentimentExchange>> {
+    if per_person.len() != 2 {
+        return None;
+    }
+
+    let mut people: Vec<(&String, &SentimentAgg)> = per_person.iter().collect();
+    people.sort_by(|a, b| a.0.cmp(b.0));
+
+    let exchange = people
+        .iter()
+        .enumerate()
+        .map(|(i, (name, agg))| {
+            let given_mean = agg.mean();
+            let received_mean = people[1 - i].1.mean();
+            SentimentExchange {
+                name: (*name).clone(),
+                given_mean,
+                received_mean,
+                delta: given_mean - received_mean,
+            }
+        })
+        .collect();
+    Some(exchange)
 }
 
 // Compact lexicon for sentiment scoring to keep WASM footprint small.
@@ -229,6 +448,7 @@ mod tests {
             dt: NaiveDateTime::parse_from_str(dt_str, "%Y-%m-%d %H:%M:%S").unwrap(),
             sender: sender.to_string(),
             text: text.to_string(),
+            line: 0,
         }
     }
 
@@ -248,63 +468,128 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn per_message_scores_cover_every_message() {
+        let messages = vec![
+            msg("B", "this is awful and terrible", "2024-01-01 10:05:00"),
+            msg("A", "I love this, it is great", "2024-01-01 10:00:00"),
+            msg("A", "the cat sat on the mat", "2024-01-01 10:01:00"),
+            msg("B", "😭😭", "2024-01-01 10:02:00"),
+        ];
+        let series = per_message_scores(&messages, &EmojiWeights::new());
+        assert_eq!(series.len(), messages.len());
+        assert!(series.iter().all(|s| (-1.0..=1.0).contains(&s.score)));
+        assert_eq!(series[0].timestamp, "2024-01-01T10:00:00");
+        assert!(series[0].score > 0.0);
+        assert_eq!(series[1].score, 0.0);
+        assert_eq!(series[3].sender, "B");
+        assert!(series[3].score < 0.0);
+    }
+
+    #[test]
+    fn mostly_positive_people_are_labelled_so() {
+        let messages = vec![
+            msg("A", "I love this, it is great", "2024-01-01 10:00:00"),
+            msg("A", "awesome, thanks so much", "2024-01-01 10:01:00"),
+            msg("A", "the cat sat on the mat", "2024-01-01 10:02:00"),
+            msg("A", "so happy today", "2024-01-01 10:03:00"),
+            msg("B", "this is awful", "2024-01-01 10:04:00"),
+        ];
+        let overall = sentiment_breakdown(&messages, &EmojiWeights::new()).overall;
+        let label = |name: &str| {
+            overall
+                .iter()
+                .find(|o| o.name == name)
+                .map(|o| o.dominant_emotion.as_str())
+        };
+        assert_eq!(label("A"), Some("Mostly positive"));
+        assert_eq!(label("B"), Some("Mostly negative"));
+
+        assert_eq!(dominant_emotion(0, 0), "Neutral");
+        assert_eq!(dominant_emotion(5, 5), "Balanced");
+        assert_eq!(dominant_emotion(6, 4), "Leaning positive");
+        assert_eq!(dominant_emotion(3, 7), "Often negative");
+    }
+
     #[test]
     fn sentiment_score_neutral_for_empty_or_plain() {
-        let (compound, class) = sentiment_score("");
+        let (compound, class) = sentiment_score("", &EmojiWeights::new());
         assert_eq!(compound, 0.0);
         assert!(matches!(class, SentimentClass::Neutral));
 
-        let (c2, _) = sentiment_score("the cat sat on the mat");
+        let (c2, _) = sentiment_score("the cat sat on the mat", &EmojiWeights::new());
         assert_eq!(c2, 0.0);
     }
 
     #[test]
     fn sentiment_score_positive_words() {
-        let (compound, class) = sentiment_score("I love this, it is great and awesome");
+        let (compound, class) =
+            sentiment_score("I love this, it is great and awesome", &EmojiWeights::new());
         assert!(compound > 0.0);
         assert!(matches!(class, SentimentClass::Positive));
     }
 
     #[test]
     fn sentiment_score_negative_words() {
-        let (compound, class) = sentiment_score("this is terrible and awful, I hate it");
+        let (compound, class) = sentiment_score(
+            "this is terrible and awful, I hate it",
+            &EmojiWeights::new(),
+        );
         assert!(compound < 0.0);
         assert!(matches!(class, SentimentClass::Negative));
     }
 
     #[test]
     fn sentiment_score_clamped_to_unit_range() {
-        let (compound, _) = sentiment_score("love love love amazing awesome great perfect best");
+        let (compound, _) = sentiment_score(
+            "love love love amazing awesome great perfect best",
+            &EmojiWeights::new(),
+        );
         assert!(compound <= 1.0);
         assert!(compound >= -1.0);
     }
 
     #[test]
     fn sentiment_score_emoji_positive() {
-        let (compound, class) = sentiment_score("😀😍👍");
+        let (compound, class) = sentiment_score("😀😍👍", &EmojiWeights::new());
         assert!(compound > 0.0);
         assert!(matches!(class, SentimentClass::Positive));
     }
 
     #[test]
     fn sentiment_score_emoji_negative() {
-        let (compound, _) = sentiment_score("😢😭💔");
+        let (compound, _) = sentiment_score("😢😭💔", &EmojiWeights::new());
         assert!(compound < 0.0);
     }
 
     #[test]
     fn sentiment_score_mixed_can_cancel() {
         // Two positive (+2 each) and two negative (-2 each) words -> score 0.
-        let (compound, class) = sentiment_score("love hate good bad");
+        let (compound, class) = sentiment_score("love hate good bad", &EmojiWeights::new());
         assert_eq!(compound, 0.0);
         assert!(matches!(class, SentimentClass::Neutral));
     }
 
     #[test]
     fn sentiment_breakdown_empty() {
-        let (by_day, overall) = sentiment_breakdown(&[]);
-        assert!(by_day.is_empty());
-        assert!(overall.is_empty());
+        let breakdown = sentiment_breakdown(&[], &EmojiWeights::new());
+        assert!(breakdown.by_day.is_empty());
+        assert!(breakdown.overall.is_empty());
+        assert!(breakdown.exchange.is_none());
+        assert_eq!(breakdown.chat_mean, 0.0);
+    }
+
+    #[test]
+    fn sentiment_breakdown_chat_mean_is_message_weighted() {
+        let messages = vec![
+            msg("Alice", "love it", "2023-01-01 10:00:00"),
+            msg("Alice", "great news", "2023-01-01 10:01:00"),
+            msg("Alice", "so happy", "2023-01-01 10:02:00"),
+            msg("Bob", "awful", "2023-01-01 10:03:00"),
+        ];
+        let breakdown = sentiment_breakdown(&messages, &EmojiWeights::new());
+        // Three +1 messages and one -1 message: (3 - 1) / 4, not the per-person 0.0.
+        assert!((breakdown.chat_mean - 0.5).abs() < 1e-6);
     }
 
     #[test]
@@ -314,7 +599,9 @@ mod tests {
             msg("Bob", "this is awful and terrible", "2023-01-01 11:00:00"),
             msg("Alice", "another good one", "2023-01-02 10:00:00"),
         ];
-        let (by_day, overall) = sentiment_breakdown(&messages);
+        let SentimentBreakdown {
+            by_day, overall, ..
+        } = sentiment_breakdown(&messages, &EmojiWeights::new());
 
         // 3 (person, day) buckets.
         assert_eq!(by_day.len(), 3);
@@ -338,14 +625,91 @@ mod tests {
             msg("Alice", "good", "2023-02-01 10:00:00"),
             msg("Alice", "bad", "2023-01-01 10:00:00"),
         ];
-        let (by_day, _) = sentiment_breakdown(&messages);
+        let by_day = sentiment_breakdown(&messages, &EmojiWeights::new()).by_day;
         assert_eq!(by_day.len(), 2);
         assert!(by_day[0].day <= by_day[1].day);
     }
 
+    #[test]
+    fn sentiment_exchange_pairs_given_and_received_in_dms() {
+        let messages = vec![
+            msg("Alice", "I love this, great day", "2023-01-01 10:00:00"),
+            msg("Bob", "ugh this is terrible", "2023-01-01 10:01:00"),
+            msg("Alice", "so happy and excited", "2023-01-01 10:02:00"),
+            msg("Bob", "meh, bad and annoyed", "2023-01-01 10:03:00"),
+        ];
+        let exchange = sentiment_breakdown(&messages, &EmojiWeights::new())
+            .exchange
+            .expect("two-person chat has an exchange");
+        assert_eq!(exchange.len(), 2);
+
+        let alice = exchange.iter().find(|e| e.name == "Alice").unwrap();
+        let bob = exchange.iter().find(|e| e.name == "Bob").unwrap();
+        assert!(alice.given_mean > 0.0);
+        assert!(alice.received_mean < 0.0);
+        assert_eq!(alice.given_mean, bob.received_mean);
+        assert_eq!(alice.received_mean, bob.given_mean);
+        assert!(alice.delta > 0.0);
+        assert!(bob.delta < 0.0);
+    }
+
+    #[test]
+    fn sentiment_exchange_is_none_for_groups() {
+        let messages = vec![
+            msg("Alice", "good", "2023-01-01 10:00:00"),
+            msg("Bob", "bad", "2023-01-01 10:01:00"),
+            msg("Carol", "ok", "2023-01-01 10:02:00"),
+        ];
+        assert!(sentiment_breakdown(&messages, &EmojiWeights::new())
+            .exchange
+            .is_none());
+    }
+
+    #[test]
+    fn emoji_cloud_sentiment_tags_polarity() {
+        let cloud = vec![
+            Count {
+                label: "😂".into(),
+                value: 5,
+            },
+            Count {
+                label: "💔".into(),
+                value: 2,
+            },
+            Count {
+                label: "🔥".into(),
+                value: 1,
+            },
+        ];
+        let items = emoji_cloud_sentiment(&cloud, &EmojiWeights::new());
+        let polarity: Vec<i8> = items.iter().map(|i| i.sentiment).collect();
+        assert_eq!(polarity, vec![1, -1, 0]);
+        assert_eq!(items[0].value, 5);
+    }
+
     #[test]
     fn sentiment_agg_mean_handles_zero_count() {
         let agg = SentimentAgg::default();
         assert_eq!(agg.mean(), 0.0);
     }
+
+    #[test]
+    fn emoji_weights_override_the_lexicon() {
+        let text = "that was awful 💀💀💀";
+        let (_, class) = sentiment_score(text, &EmojiWeights::new());
+        assert!(matches!(class, SentimentClass::Negative));
+
+        let weights = EmojiWeights::from([("💀".to_string(), 1.0)]);
+        let (compound, class) = sentiment_score(text, &weights);
+        assert!(matches!(class, SentimentClass::Positive));
+        assert!((compound - 0.5).abs() < 1e-6);
+
+        let muted = EmojiWeights::from([("😭".to_string(), 0.0)]);
+        assert_eq!(sentiment_score("😭", &muted).0, 0.0);
+        let cloud = [Count {
+            label: "💀".into(),
+            value: 3,
+        }];
+        assert_eq!(emoji_cloud_sentiment(&cloud, &weights)[0].sentiment, 1);
+    }
 }
@@ -2,10 +2,21 @@ use once_cell::sync::OnceCell;
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
 use stopwords::{Language, Spark, Stopwords};
+use unicode_segmentation::UnicodeSegmentation;
 
 // Fixed 30-minute gap threshold to define a new conversation
 pub(crate) const CONVERSATION_GAP_MINUTES: i64 = 30;
 
+// A change of sender within a day counts as a reply; longer silences don't.
+pub(crate) const REPLY_WINDOW_MINUTES: i64 = 24 * 60;
+
+// Messages longer than this many tokens are treated as pastes (lyrics, articles).
+pub(crate) const LONG_MESSAGE_TOKENS: usize = 500;
+
+// Longest message text surfaced whole (journey, conversation previews, phrase examples),
+// in graphemes; `truncate_chars` cuts anything longer.
+pub(crate) const SNIPPET_MAX_CHARS: usize = 500;
+
 // Extras seen in WhatsApp exports that should be filtered from stopwords.
 pub(crate) const WHATSAPP_EXTRAS: [&str; 27] = [
     "<media",
@@ -75,6 +86,40 @@ pub(crate) fn pick_dominant_color(freq: &HashMap<String, u32>) -> Option<String>
     color_hex_for_word(best_word).map(|hex| hex.to_string())
 }
 
+// Keyword categories for celebration days, matched as lowercase substrings. Extend a
+// category's list to add phrasings or languages; add a tuple to add a new kind.
+const CELEBRATION_CATEGORIES: [(&str, &[&str]); 2] = [
+    (
+        "birthday",
+        &[
+            "happy birthday",
+            "happy bday",
+            "feliz cumpleaños",
+            "feliz cumple",
+            "feliz aniversário",
+        ],
+    ),
+    (
+        "celebration",
+        &[
+            "congratulations",
+            "congrats",
+            "felicidades",
+            "felicitaciones",
+            "parabéns",
+        ],
+    ),
+];
+
+/// The first celebration category whose keywords appear in `text`.
+pub(crate) fn celebration_category(text: &str) -> Option<&'static str> {
+    let lower = text.to_lowercase();
+    CELEBRATION_CATEGORIES
+        .iter()
+        .find(|(_, keywords)| keywords.iter().any(|k| lower.contains(k)))
+        .map(|(kind, _)| *kind)
+}
+
 /// Languages supported for stopword filtering.
 const SUPPORTED_LANGUAGES: [Language; 3] =
     [Language::English, Language::Portuguese, Language::Spanish];
@@ -96,35 +141,303 @@ pub(crate) fn stopwords_set() -> &'static HashSet<&'static str> {
     })
 }
 
+/// Per-language stopword sets keyed by the names `effective_stopwords` accepts, with
+/// words shared by two or more languages ("a", "de", "no") removed so a hit says
+/// something about the language.
+fn distinctive_stopwords() -> &'static [(&'static str, HashSet<&'static str>)] {
+    static SETS: OnceCell<Vec<(&'static str, HashSet<&'static str>)>> = OnceCell::new();
+    SETS.get_or_init(|| {
+        let names = ["english", "portuguese", "spanish"];
+        let sets: Vec<HashSet<&'static str>> = SUPPORTED_LANGUAGES
+            .iter()
+            .map(|&lang| {
+                Spark::stopwords(lang)
+                    .map(|words| words.iter().copied().collect())
+                    .unwrap_or_default()
+            })
+            .collect();
+        names
+            .iter()
+            .enumerate()
+            .map(|(i, &name)| {
+                let own = sets[i]
+                    .iter()
+                    .copied()
+                    .filter(|w| {
+                        sets.iter()
+                            .enumerate()
+                            .all(|(j, s)| j == i || !s.contains(w))
+                    })
+                    .collect();
+                (name, own)
+            })
+            .collect()
+    })
+}
+
+/// Cheap language guess from distinctive stopword hits: the language with the most
+/// hits and its share of all hits, or `None` when no token is a distinctive stopword.
+pub(crate) fn detect_language<'a>(
+    tokens: impl IntoIterator<Item = &'a str>,
+) -> Option<(&'static str, f32)> {
+    let sets = distinctive_stopwords();
+    let mut hits = vec![0u32; sets.len()];
+    for token in tokens {
+        for (i, (_, set)) in sets.iter().enumerate() {
+            if set.contains(token) {
+                hits[i] += 1;
+            }
+        }
+    }
+    let total: u32 = hits.iter().sum();
+    let (best, &count) = hits
+        .iter()
+        .enumerate()
+        .max_by_key(|(i, &h)| (h, std::cmp::Reverse(*i)))?;
+    (total > 0).then(|| (sets[best].0, count as f32 / total as f32))
+}
+
+/// Name of the merged stopword set every analysis uses.
+pub(crate) const STOPWORD_LANGUAGE_AUTO: &str = "auto";
+
+/// The stopword list for `language`, sorted: `"auto"` is the merged set used by the
+/// analysis, `"english"`, `"portuguese"` or `"spanish"` a single base list. Either way
+/// the WhatsApp extras are included.
+pub fn effective_stopwords(language: &str) -> Result<Vec<String>, String> {
+    let mut words: Vec<String> = if language == STOPWORD_LANGUAGE_AUTO {
+        stopwords_set().iter().map(|w| w.to_string()).collect()
+    } else {
+        let lang = match language {
+            "english" => Language::English,
+            "portuguese" => Language::Portuguese,
+            "spanish" => Language::Spanish,
+            other => return Err(format!("Unsupported stopword language: {other}")),
+        };
+        let mut set: HashSet<&'static str> = Spark::stopwords(lang)
+            .map(|words| words.iter().copied().collect())
+            .unwrap_or_default();
+        set.extend(WHATSAPP_EXTRAS);
+        set.into_iter().map(String::from).collect()
+    };
+    words.sort();
+    Ok(words)
+}
+
 pub(crate) fn is_media_omitted_message(text: &str) -> bool {
     text.trim().eq_ignore_ascii_case("<media omitted>")
 }
 
+/// Attachments, deletions and call records: lines that stand in for something other
+/// than words. Covers Android's `<Media omitted>`, iOS's "image omitted" style
+/// placeholders and `<attached: ...>` lines.
+pub(crate) fn is_placeholder_message(text: &str) -> bool {
+    let lower = text.trim().to_lowercase();
+    is_media_omitted_message(&lower)
+        || lower.starts_with("<attached:")
+        || (lower.ends_with(" omitted") && lower.split_whitespace().count() <= 3)
+        || lower == "you deleted this message"
+        || lower == "this message was deleted"
+        || matches!(
+            lower.as_str(),
+            "voice call" | "video call" | "missed voice call" | "missed video call"
+        )
+}
+
+/// Both platforms append `<This message was edited>` to the text of an edited message.
+pub(crate) fn is_edited_message(text: &str) -> bool {
+    text.trim_end_matches(|c: char| c.is_whitespace() || c == '\u{200e}')
+        .to_lowercase()
+        .ends_with("<this message was edited>")
+}
+
+/// Polls export as a `POLL:` line followed by the question and one `OPTION:` line per
+/// choice, e.g.
+///
+/// ```text
+/// POLL:
+/// Where should we eat?
+/// OPTION: Pizza (3 votes)
+/// OPTION: Sushi (1 vote)
+/// ```
+///
+/// Android and iOS use the same layout; the whole block is one message.
+pub(crate) fn is_poll_message(text: &str) -> bool {
+    text.trim_start().starts_with("POLL:")
+}
+
+/// The question and `(option, votes)` pairs of a poll message. Options without a vote
+/// count (older exports) count as zero.
+pub(crate) fn parse_poll(text: &str) -> Option<(String, Vec<(String, u32)>)> {
+    let body = text.trim_start().strip_prefix("POLL:")?;
+    let mut question = Vec::new();
+    let mut options = Vec::new();
+    for line in body.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        match line.strip_prefix("OPTION:") {
+            Some(option) => {
+                let option = option.trim();
+                options.push(match poll_votes_re().captures(option) {
+                    Some(caps) => (
+                        caps["label"].to_string(),
+                        caps["votes"].parse().unwrap_or(0),
+                    ),
+                    None => (option.to_string(), 0),
+                });
+            }
+            None if options.is_empty() => question.push(line),
+            None => {}
+        }
+    }
+    Some((question.join(" "), options))
+}
+
+fn poll_votes_re() -> &'static Regex {
+    static RE: OnceCell<Regex> = OnceCell::new();
+    RE.get_or_init(|| {
+        // SAFE: compile-time-constant pattern; independent of user input.
+        Regex::new(r"^(?P<label>.*?)\s*\((?P<votes>\d+)\s+votes?\)$").expect("poll votes regex")
+    })
+}
+
+/// Voice notes show up as `PTT-20230101-WA0001.opus (0:42)` when an export tool annotates
+/// the attachment, or as the bare iOS "audio omitted" placeholder without a duration.
+pub(crate) fn is_voice_note(text: &str) -> bool {
+    let lower = text.trim().to_lowercase();
+    lower.contains("audio omitted")
+        || (lower.contains("ptt-") && lower.contains(".opus"))
+        || (lower.contains("-audio-") && lower.contains(".opus"))
+}
+
+/// Parses a trailing `(m:ss)` or `(h:mm:ss)` duration into seconds.
+pub(crate) fn voice_note_seconds(text: &str) -> Option<u32> {
+    let caps = duration_re().captures(text)?;
+    let parts: Vec<u32> = caps
+        .name("dur")?
+        .as_str()
+        .split(':')
+        .map(|p| p.parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    match parts.as_slice() {
+        [m, s] if *s < 60 => Some(m * 60 + s),
+        [h, m, s] if *m < 60 && *s < 60 => Some(h * 3600 + m * 60 + s),
+        _ => None,
+    }
+}
+
 pub(crate) fn extract_emojis(text: &str) -> Vec<String> {
-    let mut out = Vec::new();
-    let mut idx = 0;
-    while idx < text.len() {
-        let rest = &text[idx..];
-        if let Some(m) = emoji_re().find(rest) {
-            if m.start() == 0 {
-                // Greedy match at the current cursor; should cover full ZWJ/skin-tone sequence.
-                out.push(m.as_str().to_string());
-                idx += m.end();
-                continue;
+    // One left-to-right pass; each match is greedy, so a full ZWJ/skin-tone sequence comes
+    // out as one emoji.
+    emoji_re()
+        .find_iter(text)
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+/// Pairs of different emojis written next to each other (nothing but whitespace between
+/// them), in order of appearance. Pairs are normalized: variation selectors are dropped
+/// and each pair is sorted, so "😭😂" and "😂 😭" are the same pair. A repeated emoji
+/// ("😂😂") is not a pair.
+pub(crate) fn adjacent_emoji_pairs(text: &str) -> Vec<String> {
+    let mut pairs = Vec::new();
+    let mut previous: Option<(usize, String)> = None;
+    for m in emoji_re().find_iter(text) {
+        let emoji = m.as_str().replace('\u{FE0F}', "");
+        if let Some((end, prev)) = &previous {
+            if text[*end..m.start()].trim().is_empty() && *prev != emoji {
+                let (first, second) = if *prev < emoji {
+                    (prev.as_str(), emoji.as_str())
+                } else {
+                    (emoji.as_str(), prev.as_str())
+                };
+                pairs.push(format!("{first}{second}"));
+            }
+        }
+        previous = Some((m.end(), emoji));
+    }
+    pairs
+}
+
+/// Messages made of nothing but one emoji repeated at least this many times are spam.
+const EMOJI_SPAM_MIN: usize = 3;
+
+/// True for messages like "😂😂😂😂" (one emoji repeated, nothing else but whitespace).
+pub(crate) fn is_emoji_spam(text: &str) -> bool {
+    let emojis = extract_emojis(text);
+    if emojis.len() < EMOJI_SPAM_MIN || emojis.iter().any(|e| *e != emojis[0]) {
+        return false;
+    }
+    let non_space: usize = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(char::len_utf8)
+        .sum();
+    non_space == emojis.iter().map(String::len).sum::<usize>()
+}
+
+/// `s` cut to at most `max_chars` graphemes, the last of them an ellipsis when anything
+/// was dropped. Cuts fall between graphemes, so emoji sequences and accented letters
+/// are never split.
+pub(crate) fn truncate_chars(s: &str, max_chars: usize) -> String {
+    let mut graphemes = s.grapheme_indices(true);
+    match graphemes.nth(max_chars) {
+        None => s.to_string(),
+        Some(_) => {
+            let keep = max_chars.saturating_sub(1);
+            let end = s
+                .grapheme_indices(true)
+                .nth(keep)
+                .map_or(s.len(), |(i, _)| i);
+            let mut out = s[..end].trim_end().to_string();
+            if max_chars > 0 {
+                out.push('…');
             }
+            out
         }
-        // Advance by one scalar if no emoji match at current position.
-        idx += rest.chars().next().map(|c| c.len_utf8()).unwrap_or(1);
     }
-    out
 }
 
+/// Longest run of one mark that counts, so "!!!!!!!" scores like "!!!".
+const PUNCTUATION_RUN_CAP: u32 = 3;
+
+/// `(question marks, exclamation marks)` in `text`, skipping URLs (query strings) and
+/// counting at most `PUNCTUATION_RUN_CAP` of each run.
+pub(crate) fn punctuation_counts(text: &str) -> (u32, u32) {
+    let cleaned = url_re().replace_all(text, " ");
+    let (mut questions, mut exclamations) = (0, 0);
+    let mut run: Option<(char, u32)> = None;
+    for c in cleaned.chars() {
+        let len = match run {
+            Some((prev, len)) if prev == c => len + 1,
+            _ => 1,
+        };
+        run = Some((c, len));
+        if len > PUNCTUATION_RUN_CAP {
+            continue;
+        }
+        match c {
+            '?' => questions += 1,
+            '!' => exclamations += 1,
+            _ => {}
+        }
+    }
+    (questions, exclamations)
+}
+
+/// Invisible characters that text copied from web pages carries inside words: soft
+/// hyphen, zero-width space, zero-width non-joiner and word joiner. The zero-width
+/// joiner (U+200D) is not among them, so emoji ZWJ sequences survive.
+const INVISIBLE_CHARS: [char; 4] = ['\u{ad}', '\u{200b}', '\u{200c}', '\u{2060}'];
+
 pub(crate) fn tokenize(text: &str, filter_stop: bool, stop: &HashSet<&'static str>) -> Vec<String> {
     let cleaned_urls = url_re().replace_all(text, " ");
     cleaned_urls
         .split_whitespace()
         .filter_map(|raw| {
-            let token = raw.to_lowercase();
+            // Strip before the stopword lookup so "the\u{ad}" still counts as "the".
+            let token = if raw.contains(&INVISIBLE_CHARS[..]) {
+                raw.replace(&INVISIBLE_CHARS[..], "").to_lowercase()
+            } else {
+                raw.to_lowercase()
+            };
             let canonical = token
                 .trim_matches(|c: char| !c.is_alphanumeric())
                 .to_string();
@@ -142,6 +455,25 @@ pub(crate) fn tokenize(text: &str, filter_stop: bool, stop: &HashSet<&'static st
         .collect()
 }
 
+/// Units and ordinal suffixes that may trail a number, as in "7pm", "10kg" or "3rd".
+const NUMERIC_SUFFIXES: [&str; 16] = [
+    "am", "pm", "h", "hr", "hrs", "m", "min", "mins", "s", "k", "kg", "km", "st", "nd", "rd", "th",
+];
+
+/// True for numbers, dates/times ("2023", "12/05", "10:30") and number+unit tokens
+/// ("7pm", "3rd"). The "<3" heart is never numeric.
+pub(crate) fn is_numeric_token(token: &str) -> bool {
+    if token.starts_with("<3") && token.trim_start_matches("<3").is_empty() {
+        return false;
+    }
+    let canonical = token.trim_matches(|c: char| !c.is_alphanumeric());
+    if !canonical.starts_with(|c: char| c.is_ascii_digit()) {
+        return false;
+    }
+    let suffix = canonical.trim_start_matches(|c: char| c.is_ascii_digit() || ".,:/-".contains(c));
+    suffix.is_empty() || NUMERIC_SUFFIXES.contains(&suffix)
+}
+
 pub(crate) fn tokens_stop_stats(tokens: &[String], stop: &HashSet<&'static str>) -> (usize, usize) {
     let stop_count = tokens.iter().filter(|t| stop.contains(t.as_str())).count();
     let non_stop = tokens.len().saturating_sub(stop_count);
@@ -190,6 +522,14 @@ fn emoji_re() -> &'static Regex {
     })
 }
 
+fn duration_re() -> &'static Regex {
+    static RE: OnceCell<Regex> = OnceCell::new();
+    RE.get_or_init(|| {
+        // SAFE: compile-time-constant pattern; independent of user input.
+        Regex::new(r"\((?P<dur>\d{1,2}(?::\d{2}){1,2})\)").expect("duration regex")
+    })
+}
+
 fn url_re() -> &'static Regex {
     static RE: OnceCell<Regex> = OnceCell::new();
     RE.get_or_init(|| {
@@ -203,6 +543,99 @@ fn url_re() -> &'static Regex {
 mod tests {
     use super::*;
 
+    #[test]
+    fn effective_stopwords_match_tokenize_filtering() {
+        let stop = stopwords_set();
+        let exported = effective_stopwords(STOPWORD_LANGUAGE_AUTO).unwrap();
+        assert_eq!(exported.len(), stop.len());
+        assert!(exported.iter().all(|w| stop.contains(w.as_str())));
+        // tokenize matches on the punctuation-trimmed token, so every plain word on the
+        // exported list is dropped.
+        let plain = exported
+            .iter()
+            .filter(|w| w.trim_matches(|c: char| !c.is_alphanumeric()) == w.as_str());
+        for word in plain {
+            assert!(tokenize(word, true, stop).is_empty(), "{word} not filtered");
+        }
+    }
+
+    #[test]
+    fn effective_stopwords_single_language_includes_extras() {
+        let english = effective_stopwords("english").unwrap();
+        assert!(english.iter().any(|w| w == "the"));
+        assert!(english.iter().any(|w| w == "omitted"));
+        assert!(!english.iter().any(|w| w == "porque"));
+        assert!(effective_stopwords("klingon").is_err());
+    }
+
+    #[test]
+    fn detect_language_uses_distinctive_stopwords() {
+        let (lang, share) =
+            detect_language("the cat is with them and they would".split(' ')).unwrap();
+        assert_eq!(lang, "english");
+        assert!(share > 0.5);
+        let (lang, _) = detect_language("pero nosotros estamos muy cansados".split(' ')).unwrap();
+        assert_eq!(lang, "spanish");
+        assert!(detect_language("pizza tonight".split(' ')).is_none());
+    }
+
+    #[test]
+    fn placeholder_messages_cover_media_deletions_and_calls() {
+        for text in [
+            "<Media omitted>",
+            "image omitted",
+            "<attached: 00000012-PHOTO-2024-01-01.jpg>",
+            "This message was deleted",
+            "Missed voice call",
+        ] {
+            assert!(is_placeholder_message(text), "{text}");
+        }
+        assert!(!is_placeholder_message("voice call later?"));
+        assert!(!is_placeholder_message(
+            "the rest was omitted from the report"
+        ));
+    }
+
+    #[test]
+    fn emoji_pairs_need_adjacent_distinct_emojis() {
+        assert_eq!(adjacent_emoji_pairs("lol 😂😭"), vec!["😂😭"]);
+        // Order and variation selectors don't matter; whitespace between is fine.
+        assert_eq!(adjacent_emoji_pairs("🔥 ❤️"), adjacent_emoji_pairs("❤🔥"));
+        assert_eq!(adjacent_emoji_pairs("😂😭🔥").len(), 2);
+        assert!(adjacent_emoji_pairs("😂 haha 😭").is_empty());
+        assert!(adjacent_emoji_pairs("😂😂").is_empty());
+        assert!(adjacent_emoji_pairs("no emoji").is_empty());
+    }
+
+    #[test]
+    fn edited_marker_is_detected_at_the_end() {
+        assert!(is_edited_message("see you at 8 <This message was edited>"));
+        assert!(is_edited_message(
+            "ok\u{200e}<This message was edited>\u{200e}"
+        ));
+        assert!(!is_edited_message(
+            "<This message was edited> is what it says"
+        ));
+        assert!(!is_edited_message("I edited the doc"));
+    }
+
+    #[test]
+    fn parse_poll_reads_question_and_votes() {
+        let text = "POLL:\nWhere should we eat?\nOPTION: Pizza (3 votes)\nOPTION: Sushi (1 vote)\nOPTION: Tacos";
+        assert!(is_poll_message(text));
+        let (question, options) = parse_poll(text).unwrap();
+        assert_eq!(question, "Where should we eat?");
+        assert_eq!(
+            options,
+            vec![
+                ("Pizza".to_string(), 3),
+                ("Sushi".to_string(), 1),
+                ("Tacos".to_string(), 0)
+            ]
+        );
+        assert!(parse_poll("not a poll").is_none());
+    }
+
     #[test]
     fn color_hex_lookup_known_and_unknown() {
         assert_eq!(color_hex_for_word("blue"), Some("#64d8ff"));
@@ -253,6 +686,80 @@ mod tests {
         assert!(!is_media_omitted_message(""));
     }
 
+    #[test]
+    fn celebration_category_matches_keywords_case_insensitively() {
+        assert_eq!(
+            celebration_category("HAPPY BIRTHDAY!! 🎂"),
+            Some("birthday")
+        );
+        assert_eq!(
+            celebration_category("Feliz cumpleaños amiga"),
+            Some("birthday")
+        );
+        assert_eq!(
+            celebration_category("congrats on the job"),
+            Some("celebration")
+        );
+        assert_eq!(celebration_category("see you at the party"), None);
+    }
+
+    #[test]
+    fn voice_note_detection_covers_known_markers() {
+        assert!(is_voice_note("PTT-20230101-WA0001.opus (0:42)"));
+        assert!(is_voice_note("\u{200e}audio omitted"));
+        assert!(is_voice_note(
+            "<attached: 00000012-AUDIO-2023-01-01-10-00-00.opus>"
+        ));
+        assert!(!is_voice_note("send me the audio later"));
+    }
+
+    #[test]
+    fn voice_note_seconds_parses_both_formats() {
+        assert_eq!(
+            voice_note_seconds("PTT-20230101-WA0001.opus (0:42)"),
+            Some(42)
+        );
+        assert_eq!(
+            voice_note_seconds("PTT-20230101-WA0002.opus (12:05)"),
+            Some(725)
+        );
+        assert_eq!(
+            voice_note_seconds("PTT-20230101-WA0003.opus (1:02:03)"),
+            Some(3723)
+        );
+        assert_eq!(voice_note_seconds("audio omitted"), None);
+        assert_eq!(voice_note_seconds("PTT-20230101-WA0004.opus (0:75)"), None);
+    }
+
+    #[test]
+    fn numeric_token_classification() {
+        for t in [
+            "2023",
+            "10",
+            "7pm",
+            "10kg",
+            "3rd",
+            "(21st)",
+            "10:30",
+            "12/05/2023",
+            "1,000",
+        ] {
+            assert!(is_numeric_token(t), "{t} should be numeric");
+        }
+        for t in ["<3", "<3<3", "hello", "4ever", "b2b", "mp3"] {
+            assert!(!is_numeric_token(t), "{t} should not be numeric");
+        }
+    }
+
+    #[test]
+    fn emoji_spam_requires_one_repeated_emoji_only() {
+        assert!(is_emoji_spam("🔥🔥🔥🔥"));
+        assert!(is_emoji_spam(" 😂 😂 😂 "));
+        assert!(!is_emoji_spam("🔥😂🔥"));
+        assert!(!is_emoji_spam("🔥🔥"));
+        assert!(!is_emoji_spam("lit 🔥🔥🔥"));
+    }
+
     #[test]
     fn extract_emojis_empty_and_plain_text() {
         assert!(extract_emojis("").is_empty());
@@ -285,6 +792,21 @@ mod tests {
         assert_eq!(out, vec!["😀"]);
     }
 
+    #[test]
+    fn truncate_chars_never_splits_an_emoji() {
+        // The family emoji is one grapheme of seven chars and 25 bytes.
+        let family = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{200d}\u{1f466}";
+        let text = format!("hi {family}{family}!");
+        // Slicing `text[..5]` would panic inside the first emoji.
+        assert!(!text.is_char_boundary(5));
+        assert_eq!(truncate_chars(&text, 5), format!("hi {family}…"));
+        assert_eq!(truncate_chars(&text, 4), "hi…");
+        assert_eq!(truncate_chars(&text, 6), text);
+        assert_eq!(truncate_chars("héllo wörld", 7), "héllo…");
+        assert_eq!(truncate_chars("😀😀😀", 2), "😀…");
+        assert_eq!(truncate_chars("abc", 0), "");
+    }
+
     #[test]
     fn tokenize_strips_urls_and_lowercases() {
         let stop = stopwords_set();
@@ -295,6 +817,24 @@ mod tests {
         assert!(toks.iter().all(|t| !t.contains("example")));
     }
 
+    #[test]
+    fn tokenize_drops_soft_hyphens_and_zero_width_characters() {
+        let stop = stopwords_set();
+        let toks = tokenize(
+            "every\u{ad}one every\u{200b}one Every\u{2060}one everyone",
+            false,
+            stop,
+        );
+        assert_eq!(toks, vec!["everyone"; 4]);
+        assert!(tokenize("\u{200b}\u{ad}", false, stop).is_empty());
+        assert_eq!(tokenize("the\u{ad} cat", true, stop), vec!["cat"]);
+
+        let family = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+        let toks = tokenize(&format!("hi {family}\u{200b}"), false, stop);
+        assert_eq!(toks, vec!["hi".to_string(), family.to_string()]);
+        assert_eq!(extract_emojis(&toks[1]), vec![family.to_string()]);
+    }
+
     #[test]
     fn tokenize_filter_stop_removes_stopwords() {
         let stop = stopwords_set();
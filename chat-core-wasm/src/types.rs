@@ -9,6 +9,14 @@ pub struct Count {
     pub value: u32,
 }
 
+/// A labelled fractional value, e.g. a percentage.
+#[derive(Debug, Serialize, Clone, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct FloatCount {
+    pub label: String,
+    pub value: f32,
+}
+
 #[derive(Debug, Serialize, Clone, Tsify)]
 #[tsify(into_wasm_abi)]
 pub struct HourCount {
@@ -21,40 +29,636 @@ pub struct HourCount {
 pub struct Summary {
     pub total_messages: usize,
     pub by_sender: Vec<Count>,
+    /// Every distinct sender, sorted by name, for pickers and per-person filters.
+    pub participants: Vec<String>,
     pub daily: Vec<Count>,
+    /// Most consecutive active days across the chat. Silences inside
+    /// `AnalyzeOptions::streak_ignore_ranges` don't break it.
+    pub longest_streak: Option<DailyStreak>,
     pub hourly: Vec<HourCount>,
+    pub routines: Vec<Routine>,
+    /// Median time of each day's first message, per month ("when do we wake up").
+    pub first_message_hour_by_month: Vec<MonthHour>,
+    /// The same, counting only each person's own first message of the day.
+    pub first_message_hour_by_person: Vec<PersonMonthHours>,
     pub top_emojis: Vec<Count>,
+    /// Emoji uses weighted by recency (half-life `RECENT_EMOJI_HALF_LIFE_DAYS`, counted
+    /// back from the last day of the chat), so current favorites rank first.
+    pub recent_emojis: Vec<FloatCount>,
     pub top_words: Vec<Count>,
     pub top_words_no_stop: Vec<Count>,
+    /// The most-used word once stopwords are filtered (`top_words[0]`), for headlines.
+    pub top_word: Option<String>,
+    pub stopword_config: StopwordConfig,
+    /// Dominant language per month, from a sample of each month's words.
+    pub language_timeline: Vec<LanguageMonth>,
     pub deleted_you: u32,
     pub deleted_others: u32,
     pub timeline: Vec<Count>,
+    /// Bucket size of `timeline`: `"YYYY-MM-DD"` days, `"YYYY-Www"` ISO weeks or
+    /// `"YYYY-MM"` months.
+    pub timeline_unit: TimelineUnit,
+    pub volume_trend: Trend,
+    pub annotations: Vec<TimelineAnnotation>,
+    /// One entry per `AnalyzeOptions::special_dates` entry, in the given order.
+    pub special_days: Vec<SpecialDay>,
     pub weekly: Vec<Count>,
+    /// Messages per ISO calendar week (`"2023-W05"`), gap-filled from first to last.
+    pub iso_weekly: Vec<Count>,
+    pub weekly_streak: Option<WeeklyStreak>,
     pub monthly: Vec<Count>,
-    pub share_of_speech: Vec<Count>,
+    /// Month with the most messages; the earliest wins ties.
+    pub busiest_month: Option<Count>,
+    /// Month with the fewest messages among months that had any; the earliest wins ties.
+    pub quietest_month: Option<Count>,
+    /// Biggest month-over-month volume rise and drop, and the biggest sentiment swing.
+    /// Each kind is missing when no month qualifies.
+    pub swings: Vec<MonthSwing>,
+    pub quarterly: Vec<Count>,
+    /// Words whose usage rose and fell the most each quarter versus the one before.
+    pub vocab_drift: Vec<VocabDrift>,
+    /// Words someone introduced that the others later adopted: slang and inside jokes.
+    pub word_origins: Vec<WordOrigin>,
+    /// Messages per season across all years: `[spring, summer, autumn, winter]`.
+    pub seasonal: [u32; 4],
+    /// Each sender's percentage of all messages, in `by_sender` order.
+    pub share_of_speech: Vec<FloatCount>,
+    /// Set for a two-person chat where one side sends most of the messages.
+    pub imbalance: Option<ImbalanceInfo>,
     pub buckets_by_person: Vec<PersonBuckets>,
+    /// Who sent the most messages on each weekday, Sunday first. `None` for a weekday
+    /// without messages or where the top two people tie.
+    pub weekday_leader: [Option<String>; 7],
+    /// Each person's percentage of every weekday's messages, Sunday first; each column
+    /// sums to 100 across people (or 0 for a silent weekday). `buckets_by_person` order.
+    pub weekday_share: Vec<PersonWeekdayShare>,
     pub word_cloud: Vec<Count>,
+    pub keyword_counts: Vec<Count>,
+    /// Hits per `AnalyzeOptions::theme_clusters` theme, in the given order.
+    pub theme_counts: Vec<Count>,
     pub word_cloud_no_stop: Vec<Count>,
     pub emoji_cloud: Vec<Count>,
+    /// `emoji_cloud` with a +1/0/-1 sentiment hint per emoji.
+    pub emoji_cloud_sentiment: Vec<EmojiCloudItem>,
+    pub emoji_spam_by_sender: Vec<Count>,
+    /// Empty unless `AnalyzeOptions::per_person_emoji_monthly` is set.
+    pub per_person_emoji_monthly: Vec<PersonEmojiMonthly>,
+    /// Emoji counts by coarse category: faces, hearts, hands, animals, objects, flags, other.
+    pub emoji_categories: Vec<Count>,
+    /// Emojis most often written side by side, as normalized pairs (variation selectors
+    /// dropped, the two sorted) seen at least three times.
+    pub emoji_pairs: Vec<Count>,
+    /// Each person's emojis per message early in the chat versus late, by name.
+    pub emoji_trend: Vec<PersonEmojiTrend>,
+    /// Monthly "?" versus "!" counts across the chat.
+    pub punctuation_energy: Vec<PunctuationMonth>,
+    /// The same per person, by name.
+    pub punctuation_energy_by_person: Vec<PersonPunctuation>,
     pub salient_phrases: Vec<Count>,
     pub top_phrases: Vec<Count>,
     pub top_phrases_no_stop: Vec<Count>,
+    /// `top_phrases` with the first message each phrase appeared in. Empty unless
+    /// `AnalyzeOptions::phrase_examples` is set.
+    pub top_phrase_examples: Vec<PhraseWithExample>,
+    /// `top_phrases` mined from question messages (those containing `?`) only.
+    pub top_question_phrases: Vec<Count>,
+    pub phrase_min_count: PhraseMinCount,
     pub per_person_phrases: Vec<PersonPhrases>,
     pub per_person_phrases_no_stop: Vec<PersonPhrases>,
     pub fun_facts: Vec<FunFact>,
+    /// Up to six computed one-liners about the chat, each with its backing number.
+    pub highlights: Vec<Highlight>,
+    pub spam_runs: Vec<SpamRun>,
+    pub long_messages: Vec<LongMessage>,
+    /// Polls posted in the chat; they are left out of word and phrase counts.
+    pub poll_stats: PollStats,
     pub person_stats: Vec<PersonStat>,
+    /// Distinct words each sender introduced to the chat.
+    pub word_firsts: Vec<Count>,
     pub per_person_daily: Vec<PersonDaily>,
+    /// Compact per-day "who spoke" bitmaps for calendar heatmaps.
+    pub presence_calendar: PresenceCalendar,
     pub sentiment_by_day: Vec<SentimentDay>,
     pub sentiment_overall: Vec<SentimentOverall>,
+    /// Mean compound sentiment across all messages (message-weighted).
+    pub overall_sentiment: f32,
+    pub sentiment_exchange: Option<Vec<SentimentExchange>>,
+    /// Every message's score, oldest first. Empty unless
+    /// `AnalyzeOptions::per_message_sentiment` is set.
+    pub sentiment_series: Vec<MessageSentiment>,
     pub conversation_starters: Vec<Count>,
+    /// Fraction (0-1) of the conversations each person started that someone answered.
+    pub starter_success: Vec<FloatCount>,
+    /// Fraction (0-1) of all conversations each person wrote in at all, so a quiet
+    /// regular ranks above someone who talks a lot in a few.
+    pub presence_share: Vec<FloatCount>,
+    /// Who breaks long silences (`AnalyzeOptions::revival_gap_hours`), most revivals first.
+    pub revivals: Vec<Revival>,
     pub conversation_count: usize,
+    /// Minutes from first to last message of each conversation, at least one apiece.
+    pub total_conversation_minutes: u32,
+    /// Messages per hour of active conversation: how intense the chat is when it's on.
+    pub message_density: f32,
+    /// Conversations per `"YYYY-MM"`, each counted in the month it started.
+    pub conversations_per_month: Vec<Count>,
+    /// Conversations by the hour (0-23) of their first message.
+    pub conversation_start_hours: [u32; 24],
+    /// Conversations by the weekday of their first message, Sunday first.
+    pub conversation_start_weekdays: [u32; 7],
+    /// Edited messages per conversation, keyed by its `"YYYY-MM-DD HH:MM"` start, most
+    /// edits first. Conversations without edits are omitted.
+    pub edits_per_conversation: Vec<Count>,
+    pub response_latency: Vec<ResponseLatency>,
+    /// Median reply latency in minutes across the whole chat; 0 when nobody replied.
+    pub avg_response_minutes: f32,
+    pub reply_time_histogram: Vec<PersonReplyHist>,
+    pub biggest_conversation: Option<ConvHighlight>,
+    pub voice_notes: VoiceNotes,
+    pub head_to_head: Option<HeadToHead>,
+    /// 0-100 headline score with its components; see `RelationshipScore`.
+    pub relationship_score: RelationshipScore,
     pub journey: Option<Journey>,
+    /// Notices about skipped or guessed-at input, e.g. lines that could not be parsed.
+    pub warnings: Vec<String>,
+    /// Set by [`Summary::merge`]; some fields are then approximations (see there).
+    pub merged: bool,
+    /// Set when `AnalyzeOptions::sample_fraction` was used.
+    pub sampled: Option<SampleInfo>,
+    /// Cache key and provenance; see `AnalysisMeta`.
+    pub analysis_meta: AnalysisMeta,
 }
 
+/// Read-only accessors and lookups for Rust consumers, so callers don't have to reach
+/// into the serialized fields directly.
 impl Summary {
     pub fn daily_counts(&self) -> &[Count] {
         &self.daily
     }
+
+    pub fn longest_streak(&self) -> Option<&DailyStreak> {
+        self.longest_streak.as_ref()
+    }
+
+    pub fn total_messages(&self) -> usize {
+        self.total_messages
+    }
+
+    pub fn by_sender(&self) -> &[Count] {
+        &self.by_sender
+    }
+
+    pub fn participants(&self) -> &[String] {
+        &self.participants
+    }
+
+    pub fn hourly(&self) -> &[HourCount] {
+        &self.hourly
+    }
+
+    pub fn routines(&self) -> &[Routine] {
+        &self.routines
+    }
+
+    pub fn first_message_hour_by_month(&self) -> &[MonthHour] {
+        &self.first_message_hour_by_month
+    }
+
+    pub fn first_message_hour_by_person(&self) -> &[PersonMonthHours] {
+        &self.first_message_hour_by_person
+    }
+
+    pub fn top_emojis(&self) -> &[Count] {
+        &self.top_emojis
+    }
+
+    pub fn recent_emojis(&self) -> &[FloatCount] {
+        &self.recent_emojis
+    }
+
+    pub fn top_words(&self) -> &[Count] {
+        &self.top_words
+    }
+
+    pub fn top_words_no_stop(&self) -> &[Count] {
+        &self.top_words_no_stop
+    }
+
+    pub fn top_word(&self) -> Option<&str> {
+        self.top_word.as_deref()
+    }
+
+    pub fn stopword_config(&self) -> &StopwordConfig {
+        &self.stopword_config
+    }
+
+    pub fn language_timeline(&self) -> &[LanguageMonth] {
+        &self.language_timeline
+    }
+
+    pub fn deleted_you(&self) -> u32 {
+        self.deleted_you
+    }
+
+    pub fn deleted_others(&self) -> u32 {
+        self.deleted_others
+    }
+
+    pub fn timeline(&self) -> &[Count] {
+        &self.timeline
+    }
+
+    pub fn timeline_unit(&self) -> TimelineUnit {
+        self.timeline_unit
+    }
+
+    pub fn volume_trend(&self) -> &Trend {
+        &self.volume_trend
+    }
+
+    pub fn annotations(&self) -> &[TimelineAnnotation] {
+        &self.annotations
+    }
+
+    pub fn special_days(&self) -> &[SpecialDay] {
+        &self.special_days
+    }
+
+    pub fn weekly(&self) -> &[Count] {
+        &self.weekly
+    }
+
+    pub fn iso_weekly(&self) -> &[Count] {
+        &self.iso_weekly
+    }
+
+    pub fn weekly_streak(&self) -> Option<&WeeklyStreak> {
+        self.weekly_streak.as_ref()
+    }
+
+    pub fn monthly(&self) -> &[Count] {
+        &self.monthly
+    }
+
+    pub fn busiest_month(&self) -> Option<&Count> {
+        self.busiest_month.as_ref()
+    }
+
+    pub fn quietest_month(&self) -> Option<&Count> {
+        self.quietest_month.as_ref()
+    }
+
+    pub fn swings(&self) -> &[MonthSwing] {
+        &self.swings
+    }
+
+    pub fn quarterly(&self) -> &[Count] {
+        &self.quarterly
+    }
+
+    pub fn vocab_drift(&self) -> &[VocabDrift] {
+        &self.vocab_drift
+    }
+
+    pub fn word_origins(&self) -> &[WordOrigin] {
+        &self.word_origins
+    }
+
+    pub fn seasonal(&self) -> [u32; 4] {
+        self.seasonal
+    }
+
+    pub fn share_of_speech(&self) -> &[FloatCount] {
+        &self.share_of_speech
+    }
+
+    pub fn imbalance(&self) -> Option<&ImbalanceInfo> {
+        self.imbalance.as_ref()
+    }
+
+    pub fn buckets_by_person(&self) -> &[PersonBuckets] {
+        &self.buckets_by_person
+    }
+
+    pub fn weekday_leader(&self) -> &[Option<String>; 7] {
+        &self.weekday_leader
+    }
+
+    pub fn weekday_share(&self) -> &[PersonWeekdayShare] {
+        &self.weekday_share
+    }
+
+    pub fn word_cloud(&self) -> &[Count] {
+        &self.word_cloud
+    }
+
+    pub fn keyword_counts(&self) -> &[Count] {
+        &self.keyword_counts
+    }
+
+    pub fn theme_counts(&self) -> &[Count] {
+        &self.theme_counts
+    }
+
+    pub fn word_cloud_no_stop(&self) -> &[Count] {
+        &self.word_cloud_no_stop
+    }
+
+    pub fn emoji_cloud(&self) -> &[Count] {
+        &self.emoji_cloud
+    }
+
+    pub fn emoji_cloud_sentiment(&self) -> &[EmojiCloudItem] {
+        &self.emoji_cloud_sentiment
+    }
+
+    pub fn emoji_spam_by_sender(&self) -> &[Count] {
+        &self.emoji_spam_by_sender
+    }
+
+    pub fn per_person_emoji_monthly(&self) -> &[PersonEmojiMonthly] {
+        &self.per_person_emoji_monthly
+    }
+
+    pub fn emoji_categories(&self) -> &[Count] {
+        &self.emoji_categories
+    }
+
+    pub fn emoji_pairs(&self) -> &[Count] {
+        &self.emoji_pairs
+    }
+
+    pub fn emoji_trend(&self) -> &[PersonEmojiTrend] {
+        &self.emoji_trend
+    }
+
+    pub fn punctuation_energy(&self) -> &[PunctuationMonth] {
+        &self.punctuation_energy
+    }
+
+    pub fn punctuation_energy_by_person(&self) -> &[PersonPunctuation] {
+        &self.punctuation_energy_by_person
+    }
+
+    pub fn salient_phrases(&self) -> &[Count] {
+        &self.salient_phrases
+    }
+
+    pub fn top_phrases(&self) -> &[Count] {
+        &self.top_phrases
+    }
+
+    pub fn top_phrases_no_stop(&self) -> &[Count] {
+        &self.top_phrases_no_stop
+    }
+
+    pub fn top_phrase_examples(&self) -> &[PhraseWithExample] {
+        &self.top_phrase_examples
+    }
+
+    pub fn top_question_phrases(&self) -> &[Count] {
+        &self.top_question_phrases
+    }
+
+    pub fn phrase_min_count(&self) -> &PhraseMinCount {
+        &self.phrase_min_count
+    }
+
+    pub fn per_person_phrases(&self) -> &[PersonPhrases] {
+        &self.per_person_phrases
+    }
+
+    pub fn per_person_phrases_no_stop(&self) -> &[PersonPhrases] {
+        &self.per_person_phrases_no_stop
+    }
+
+    pub fn fun_facts(&self) -> &[FunFact] {
+        &self.fun_facts
+    }
+
+    pub fn highlights(&self) -> &[Highlight] {
+        &self.highlights
+    }
+
+    pub fn spam_runs(&self) -> &[SpamRun] {
+        &self.spam_runs
+    }
+
+    pub fn long_messages(&self) -> &[LongMessage] {
+        &self.long_messages
+    }
+
+    pub fn poll_stats(&self) -> &PollStats {
+        &self.poll_stats
+    }
+
+    pub fn person_stats(&self) -> &[PersonStat] {
+        &self.person_stats
+    }
+
+    pub fn word_firsts(&self) -> &[Count] {
+        &self.word_firsts
+    }
+
+    pub fn per_person_daily(&self) -> &[PersonDaily] {
+        &self.per_person_daily
+    }
+
+    pub fn presence_calendar(&self) -> &PresenceCalendar {
+        &self.presence_calendar
+    }
+
+    pub fn sentiment_by_day(&self) -> &[SentimentDay] {
+        &self.sentiment_by_day
+    }
+
+    pub fn sentiment_overall(&self) -> &[SentimentOverall] {
+        &self.sentiment_overall
+    }
+
+    pub fn overall_sentiment(&self) -> f32 {
+        self.overall_sentiment
+    }
+
+    pub fn sentiment_exchange(&self) -> Option<&[SentimentExchange]> {
+        self.sentiment_exchange.as_deref()
+    }
+
+    pub fn sentiment_series(&self) -> &[MessageSentiment] {
+        &self.sentiment_series
+    }
+
+    pub fn conversation_starters(&self) -> &[Count] {
+        &self.conversation_starters
+    }
+
+    pub fn starter_success(&self) -> &[FloatCount] {
+        &self.starter_success
+    }
+
+    pub fn presence_share(&self) -> &[FloatCount] {
+        &self.presence_share
+    }
+
+    pub fn revivals(&self) -> &[Revival] {
+        &self.revivals
+    }
+
+    pub fn conversation_count(&self) -> usize {
+        self.conversation_count
+    }
+
+    pub fn total_conversation_minutes(&self) -> u32 {
+        self.total_conversation_minutes
+    }
+
+    pub fn message_density(&self) -> f32 {
+        self.message_density
+    }
+
+    pub fn conversations_per_month(&self) -> &[Count] {
+        &self.conversations_per_month
+    }
+
+    pub fn conversation_start_hours(&self) -> [u32; 24] {
+        self.conversation_start_hours
+    }
+
+    pub fn conversation_start_weekdays(&self) -> [u32; 7] {
+        self.conversation_start_weekdays
+    }
+
+    pub fn edits_per_conversation(&self) -> &[Count] {
+        &self.edits_per_conversation
+    }
+
+    pub fn response_latency(&self) -> &[ResponseLatency] {
+        &self.response_latency
+    }
+
+    pub fn avg_response_minutes(&self) -> f32 {
+        self.avg_response_minutes
+    }
+
+    pub fn reply_time_histogram(&self) -> &[PersonReplyHist] {
+        &self.reply_time_histogram
+    }
+
+    pub fn biggest_conversation(&self) -> Option<&ConvHighlight> {
+        self.biggest_conversation.as_ref()
+    }
+
+    pub fn voice_notes(&self) -> &VoiceNotes {
+        &self.voice_notes
+    }
+
+    pub fn head_to_head(&self) -> Option<&HeadToHead> {
+        self.head_to_head.as_ref()
+    }
+
+    pub fn relationship_score(&self) -> &RelationshipScore {
+        &self.relationship_score
+    }
+
+    pub fn journey(&self) -> Option<&Journey> {
+        self.journey.as_ref()
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    pub fn merged(&self) -> bool {
+        self.merged
+    }
+
+    pub fn sampled(&self) -> Option<&SampleInfo> {
+        self.sampled.as_ref()
+    }
+
+    pub fn analysis_meta(&self) -> &AnalysisMeta {
+        &self.analysis_meta
+    }
+
+    /// Word and emoji stats for one sender.
+    ///
+    /// ```
+    /// let raw = "[1/1/24, 1:00:00 PM] Alice: hello there\n[1/1/24, 1:01:00 PM] Bob: hi";
+    /// let summary = chat_core_wasm::summarize(raw, 10, 5).unwrap();
+    /// assert_eq!(summary.person("Alice").unwrap().total_words, 2);
+    /// assert!(summary.person("Carol").is_none());
+    /// ```
+    pub fn person(&self, name: &str) -> Option<&PersonStat> {
+        self.person_stats.iter().find(|p| p.name == name)
+    }
+
+    /// Hour/weekday/month buckets for one sender.
+    pub fn buckets_for(&self, name: &str) -> Option<&PersonBuckets> {
+        self.buckets_by_person.iter().find(|b| b.name == name)
+    }
+
+    /// Top phrases for one sender.
+    pub fn phrases_for(&self, name: &str) -> Option<&[Count]> {
+        self.per_person_phrases
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.phrases.as_slice())
+    }
+
+    /// Message count for a `"YYYY-MM"` month, or `None` if nothing was sent that month.
+    ///
+    /// ```
+    /// let raw = "[1/5/24, 1:00:00 PM] A: hi\n[1/6/24, 1:00:00 PM] B: hey";
+    /// let summary = chat_core_wasm::summarize(raw, 10, 5).unwrap();
+    /// assert_eq!(summary.month("2024-01"), Some(2));
+    /// assert_eq!(summary.month("2024-02"), None);
+    /// ```
+    pub fn month(&self, label: &str) -> Option<u32> {
+        self.monthly
+            .iter()
+            .find(|c| c.label == label)
+            .map(|c| c.value)
+    }
+
+    /// Overall sentiment for one sender.
+    ///
+    /// ```
+    /// let raw = "[1/1/24, 1:00:00 PM] A: I love this\n[1/1/24, 1:01:00 PM] B: awful";
+    /// let summary = chat_core_wasm::summarize(raw, 10, 5).unwrap();
+    /// assert!(summary.sentiment_for("A").unwrap().mean > 0.0);
+    /// ```
+    pub fn sentiment_for(&self, name: &str) -> Option<&SentimentOverall> {
+        self.sentiment_overall.iter().find(|s| s.name == name)
+    }
+
+    /// Messages sent by `name`; zero for unknown senders.
+    ///
+    /// ```
+    /// let raw = "[1/1/24, 1:00:00 PM] A: one\n[1/1/24, 1:01:00 PM] A: two";
+    /// let summary = chat_core_wasm::summarize(raw, 10, 5).unwrap();
+    /// assert_eq!(summary.total_for_sender("A"), 2);
+    /// assert_eq!(summary.total_for_sender("Z"), 0);
+    /// ```
+    pub fn total_for_sender(&self, name: &str) -> u32 {
+        self.by_sender
+            .iter()
+            .find(|c| c.label == name)
+            .map(|c| c.value)
+            .unwrap_or(0)
+    }
+
+    /// The day with the most messages; the earliest day wins ties.
+    ///
+    /// ```
+    /// let raw = "[1/1/24, 1:00:00 PM] A: a\n[1/2/24, 1:00:00 PM] A: b\n[1/2/24, 2:00:00 PM] B: c";
+    /// let summary = chat_core_wasm::summarize(raw, 10, 5).unwrap();
+    /// let day = summary.busiest_day().unwrap();
+    /// assert_eq!((day.label.as_str(), day.value), ("2024-01-02", 2));
+    /// ```
+    pub fn busiest_day(&self) -> Option<&Count> {
+        self.daily
+            .iter()
+            .reduce(|best, c| if c.value > best.value { c } else { best })
+    }
 }
 
 #[derive(Debug, Serialize, Tsify)]
@@ -67,6 +671,32 @@ pub struct PersonBuckets {
     pub monthly: [u32; 12],
 }
 
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct Revival {
+    pub name: String,
+    /// Times this person wrote first after a dead gap.
+    pub count: u32,
+    /// Mean messages (from anyone) that followed the revival before the next dead gap.
+    pub avg_followup_messages: f32,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct ImbalanceInfo {
+    /// The person sending most of the messages.
+    pub dominant: String,
+    /// Their percentage of all messages.
+    pub percent: f32,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct PersonWeekdayShare {
+    pub name: String,
+    pub percent: [f32; 7],
+}
+
 #[derive(Debug, Serialize, Tsify)]
 #[tsify(into_wasm_abi)]
 pub struct FunFact {
@@ -86,8 +716,18 @@ pub struct PersonStat {
     pub unique_words: u32,
     pub longest_message_words: u32,
     pub average_words_per_message: f32,
+    /// Percentiles of words per message.
+    pub p25_words: f32,
+    pub p75_words: f32,
+    pub p95_words: f32,
     pub top_emojis: Vec<Count>,
+    /// This person's most used emoji pairs, as in `Summary::emoji_pairs`.
+    pub emoji_pairs: Vec<Count>,
     pub dominant_color: Option<String>,
+    /// Average messages sent in a row before someone else writes.
+    pub avg_burst_size: f32,
+    /// Share (0-1) of standalone "I"s written as lowercase "i"; 0 if they never wrote one.
+    pub lowercase_i_ratio: f32,
 }
 
 #[derive(Debug, Serialize, Tsify)]
@@ -95,6 +735,38 @@ pub struct PersonStat {
 pub struct PersonDaily {
     pub name: String,
     pub daily: Vec<Count>,
+    /// Most consecutive days this person sent at least one message.
+    pub longest_streak: u32,
+}
+
+/// Which of the most active people sent anything on each day from `start` on. Empty
+/// (`days == 0`) for a chat without messages.
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct PresenceCalendar {
+    /// First day of the span, `"YYYY-MM-DD"`.
+    pub start: String,
+    /// Days in the span, including days nobody wrote.
+    pub days: u32,
+    pub people: Vec<PersonPresence>,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct PersonPresence {
+    pub name: String,
+    /// Standard base64 of a bitset with one bit per day: day `i` after `start` is bit
+    /// `i % 8` (least significant first) of byte `i / 8`.
+    pub bitmap: String,
+}
+
+/// A top phrase with a message it was used in, to make sense of it out of context.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct PhraseWithExample {
+    pub label: String,
+    pub value: u32,
+    pub example: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Tsify)]
@@ -104,6 +776,96 @@ pub struct PersonPhrases {
     pub phrases: Vec<Count>,
 }
 
+/// How a sampled summary was built: which fields come from the sample and how big it was.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct SampleInfo {
+    pub fraction: f32,
+    pub seed: u32,
+    /// Messages in the sample.
+    pub messages: usize,
+    /// Summary fields computed from the sample rather than the full chat.
+    pub fields: Vec<String>,
+}
+
+/// What a summary was computed from, so a cached copy can be matched to its input and
+/// dropped once the crate version changes.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct AnalysisMeta {
+    /// `content_fingerprint` of the input, limits and options.
+    pub fingerprint: String,
+    /// Crate version that produced the summary.
+    pub version: String,
+    pub options_hash: String,
+}
+
+/// Vocabulary change in one `"YYYY-Qn"` quarter: up to five words whose rate per 1,000
+/// tokens rose most (valued by this quarter's uses) and five that fell most (valued by
+/// the previous quarter's uses).
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct VocabDrift {
+    pub period: String,
+    pub rising: Vec<Count>,
+    pub falling: Vec<Count>,
+}
+
+/// A word `coined_by` used first, on `first_date` (`"YYYY-MM-DD"`), and the number of
+/// later messages from other people that used it.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct WordOrigin {
+    pub word: String,
+    pub coined_by: String,
+    pub first_date: String,
+    pub adopted_count: u32,
+}
+
+/// Emojis per message in the first and the last third of the chat's time span.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct PersonEmojiTrend {
+    pub name: String,
+    pub early_rate: f32,
+    pub late_rate: f32,
+    pub direction: TrendDirection,
+}
+
+/// Question and exclamation marks sent in a month (`"YYYY-MM"`), not counting URLs and
+/// at most three per run. `exclamations_per_question` is `None` without questions.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct PunctuationMonth {
+    pub month: String,
+    pub questions: u32,
+    pub exclamations: u32,
+    pub exclamations_per_question: Option<f32>,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct PersonPunctuation {
+    pub name: String,
+    pub monthly: Vec<PunctuationMonth>,
+}
+
+/// One person's "emoji diary": their favourite emojis month by month.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct PersonEmojiMonthly {
+    pub name: String,
+    pub monthly: Vec<EmojiMonth>,
+}
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct EmojiMonth {
+    /// `"YYYY-MM"`.
+    pub month: String,
+    pub top: Vec<Count>,
+}
+
 #[derive(Debug, Clone, Serialize, Tsify)]
 #[tsify(into_wasm_abi)]
 pub struct JourneyMessage {
@@ -133,6 +895,17 @@ pub struct Journey {
     pub first_messages: Vec<JourneyMessage>,
     pub last_messages: Vec<JourneyMessage>,
     pub interesting_moments: Vec<JourneyMoment>,
+    /// How long the first sender of a DM talked to themselves before getting an answer.
+    pub courtship: Option<Courtship>,
+}
+
+/// "You messaged 7 times over 3 days before Alice answered."
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct Courtship {
+    pub initiator: String,
+    pub messages_before_reply: u32,
+    pub hours_before_reply: f32,
 }
 
 #[derive(Debug, Serialize, Tsify)]
@@ -154,4 +927,400 @@ pub struct SentimentOverall {
     pub pos: u32,
     pub neu: u32,
     pub neg: u32,
+    /// Readable tone from the positive/negative split: "Mostly positive", "Leaning
+    /// positive", "Balanced", "Often negative", "Mostly negative", or "Neutral".
+    pub dominant_emotion: String,
+}
+
+/// One message's compound sentiment score, -1 (negative) to 1 (positive).
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct MessageSentiment {
+    /// `YYYY-MM-DDTHH:MM:SS`, as in `JourneyMessage::timestamp`.
+    pub timestamp: String,
+    pub sender: String,
+    pub score: f32,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct SentimentExchange {
+    pub name: String,
+    pub given_mean: f32,
+    pub received_mean: f32,
+    /// `given_mean - received_mean`: positive when this person is the cheerier side.
+    pub delta: f32,
+}
+
+#[derive(Debug, Default, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct VoiceNotes {
+    /// Every detected voice note, including those without a known duration.
+    pub count: u32,
+    /// Notes whose duration could not be read from the export.
+    pub unknown_duration: u32,
+    pub total_minutes: f32,
+    pub per_person: Vec<PersonVoiceNotes>,
+    pub longest: Option<LongestVoiceNote>,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct PersonVoiceNotes {
+    pub name: String,
+    pub count: u32,
+    pub minutes: f32,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct LongestVoiceNote {
+    pub sender: String,
+    pub date: String,
+    pub seconds: u32,
+}
+
+/// "The day we couldn't stop texting": the single conversation with the most messages.
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct ConvHighlight {
+    pub date: String,
+    pub start: String,
+    pub end: String,
+    pub message_count: u32,
+    pub participants: Vec<String>,
+    pub preview: Vec<String>,
+}
+
+/// Occurrence floors the phrase lists were filtered with. Both grow with chat size,
+/// so large chats only surface phrases that recur several times.
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct PhraseMinCount {
+    pub top_phrases: u32,
+    pub salient_phrases: u32,
+}
+
+/// Which stopword list filtered `top_words`, `word_cloud` and friends. Pass `language`
+/// to the `stopwords` export to list the words themselves.
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct StopwordConfig {
+    pub language: String,
+    /// WhatsApp export noise ("omitted", "<media", ...) filtered on top of the base list.
+    pub extra_count: u32,
+}
+
+/// Median first-message time of a month, as a fractional hour (7.5 = 07:30).
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct MonthHour {
+    pub month: String,
+    pub median_hour: f32,
+    /// Days in the month with a message, i.e. the sample behind the median.
+    pub days: u32,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct PersonMonthHours {
+    pub name: String,
+    pub monthly: Vec<MonthHour>,
+}
+
+/// The language a month was mostly written in. `top_language` is `"unknown"` (with a
+/// share of 0) for months with too few words to tell.
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct LanguageMonth {
+    pub month: String,
+    pub top_language: String,
+    /// Share of the month's distinctive stopword hits that were in `top_language`.
+    pub share: f32,
+}
+
+/// An hour of the day with messages on most active days.
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct Routine {
+    pub hour: u32,
+    /// Active days with at least one message in this hour.
+    pub days: u32,
+    pub active_days: u32,
+    /// `days / active_days`.
+    pub coverage: f32,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct SpamRun {
+    pub sender: String,
+    pub text_preview: String,
+    pub count: u32,
+    pub date: String,
+}
+
+/// A pasted wall of text (lyrics, an article) over the long-message token limit.
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct LongMessage {
+    pub sender: String,
+    pub date: String,
+    pub token_count: u32,
+    pub text_preview: String,
+}
+
+#[derive(Debug, Default, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct PollStats {
+    pub total_polls: u32,
+    pub total_votes: u32,
+    /// Polls created per sender.
+    pub by_sender: Vec<Count>,
+    pub polls: Vec<Poll>,
+}
+
+/// One poll with the vote count of each option, in the order they were listed.
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct Poll {
+    pub sender: String,
+    pub date: String,
+    pub question: String,
+    pub options: Vec<Count>,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct ResponseLatency {
+    pub name: String,
+    pub median_minutes: f32,
+    pub p25_minutes: f32,
+    pub p75_minutes: f32,
+    pub p95_minutes: f32,
+    pub replies: u32,
+}
+
+/// A notable day to overlay on the timeline chart.
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct TimelineAnnotation {
+    pub date: String,
+    /// `"birthday"` or `"celebration"`.
+    pub kind: String,
+    pub evidence_count: u32,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct PersonReplyHist {
+    pub name: String,
+    /// Reply counts per latency range, from `"<1m"` up to `">2h"`.
+    pub buckets: Vec<Count>,
+}
+
+/// Side-by-side stats for the two people in a DM, `left`/`right` in name order.
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct HeadToHead {
+    pub left: String,
+    pub right: String,
+    pub rows: Vec<HeadToHeadRow>,
+}
+
+/// One metric compared across both people. The larger value wins, except
+/// `reply_minutes` where the faster replier wins (and only when both replied).
+/// `winner` is `None` on ties.
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct HeadToHeadRow {
+    pub metric: String,
+    pub left: f32,
+    pub right: f32,
+    pub winner: Option<String>,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct EmojiCloudItem {
+    pub label: String,
+    pub value: u32,
+    /// +1 for positive emoji, -1 for negative, 0 when the lexicons don't know it.
+    pub sentiment: i8,
+}
+
+/// Whether the chat is getting busier or quieter over its lifetime.
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct Trend {
+    /// Change in messages per day, per day, from a linear fit over the daily timeline.
+    pub slope: f32,
+    pub direction: TrendDirection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Tsify)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendDirection {
+    Growing,
+    Stable,
+    Declining,
+}
+
+/// Ordered finest to coarsest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Tsify)]
+#[serde(rename_all = "lowercase")]
+pub enum TimelineUnit {
+    Day,
+    Week,
+    Month,
+}
+
+/// A run of active days (`"YYYY-MM-DD"` labels); `days` counts only days with messages.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct DailyStreak {
+    pub days: u32,
+    pub start: String,
+    pub end: String,
+}
+
+/// Most consecutive ISO weeks with at least `threshold` messages each.
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct WeeklyStreak {
+    pub weeks: u32,
+    /// ISO week labels such as `"2023-W52"`.
+    pub start_week: String,
+    pub end_week: String,
+    pub threshold: u32,
+}
+
+/// A month compared with the calendar month before it (`"YYYY-MM"` labels). Volume
+/// swings compare message counts and carry `percent` when the earlier month had any;
+/// sentiment swings compare mean compound scores.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct MonthSwing {
+    pub kind: SwingKind,
+    pub month: String,
+    pub prev: f32,
+    pub curr: f32,
+    pub delta: f32,
+    pub percent: Option<f32>,
+}
+
+/// A claim such as "Alice replies 3.2× faster than Bob". `value` is the number it
+/// quotes (the ratio, percentage, day or message count), unrounded.
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct Highlight {
+    pub text: String,
+    pub kind: HighlightKind,
+    pub value: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Tsify)]
+#[serde(rename_all = "snake_case")]
+pub enum HighlightKind {
+    ReplySpeed,
+    EmojiShare,
+    EveryDay,
+    NeverApart,
+    TopTalker,
+    NightOwls,
+    BusiestDay,
+    LongestConversation,
+    WeeklyStreak,
+    SilenceBreaker,
+    VoiceNotes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Tsify)]
+#[serde(rename_all = "snake_case")]
+pub enum SwingKind {
+    VolumeRise,
+    VolumeDrop,
+    SentimentSwing,
+}
+
+/// A parsed message as handed to JS by `parse_only`, for custom analysis outside
+/// `Summary`.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct ParsedMessage {
+    /// Local time as written in the export, `"YYYY-MM-DDTHH:MM:SS"`.
+    pub timestamp: String,
+    pub sender: String,
+    pub text: String,
+}
+
+/// How a user-supplied special date went. Dates outside the chat still appear, with
+/// zero messages.
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct SpecialDay {
+    pub date: String,
+    pub label: String,
+    pub messages: u32,
+    /// Percent above (or below) the daily average of the 30 surrounding days; 0 when
+    /// those days were silent.
+    pub vs_average_pct: f32,
+    pub top_phrase: Option<String>,
+    /// Mean compound sentiment of the day's messages; 0 without messages.
+    pub sentiment_mean: f32,
+}
+
+/// A 0-100 composite of four 0-100 components. `score` is their weighted mean:
+/// reciprocity 30%, responsiveness 25%, positivity 25%, consistency 20%.
+#[derive(Debug, Default, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct RelationshipScore {
+    pub score: f32,
+    /// How evenly messages are shared (normalized entropy of `share_of_speech`).
+    pub reciprocity: f32,
+    /// 100 for instant replies, 50 at a 30-minute median reply, falling towards 0.
+    pub responsiveness: f32,
+    /// Overall mean compound sentiment mapped from -1..1 to 0..100.
+    pub positivity: f32,
+    /// Share of days between the first and last message that had any message.
+    pub consistency: f32,
+}
+
+/// What changed between two summaries of the same chat; see [`crate::diff`].
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct SummaryDiff {
+    /// Labelled `"total"`.
+    pub total_messages: CountDelta,
+    /// Every sender in either summary, by name; missing senders count as 0.
+    pub by_sender: Vec<CountDelta>,
+    /// Every month in either summary, chronologically.
+    pub monthly: Vec<CountDelta>,
+    /// Top words of the new summary that were not top words before.
+    pub new_top_words: Vec<String>,
+    pub new_top_emojis: Vec<String>,
+    /// Mean sentiment per person; `old`/`new` are `None` for someone absent there.
+    pub sentiment: Vec<FloatDelta>,
+    /// Longest daily streak of the whole chat, labelled `"chat"`.
+    pub longest_streak: CountDelta,
+    pub longest_streak_by_sender: Vec<CountDelta>,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct CountDelta {
+    pub label: String,
+    pub old: u32,
+    pub new: u32,
+    pub delta: i32,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct FloatDelta {
+    pub label: String,
+    pub old: Option<f32>,
+    pub new: Option<f32>,
+    /// `new - old`, treating a missing side as 0.
+    pub delta: f32,
 }
@@ -0,0 +1,197 @@
+use crate::parsing::ParseReport;
+
+/// Share of non-blank lines that may go unparsed before the user is warned.
+const UNPARSED_WARN_SHARE: f32 = 0.01;
+
+/// Each rule looks at the parse report and returns a notice when it applies.
+const RULES: [fn(&ParseReport) -> Option<String>; 5] = [
+    unparsed_lines,
+    two_digit_years,
+    skipped_entries,
+    merged_senders,
+    truncated_lines,
+];
+
+/// Human-readable notices about parts of the input that were skipped or guessed at, so
+/// the numbers are not trusted blindly. Empty for a clean export.
+pub(crate) fn build_warnings(report: &ParseReport) -> Vec<String> {
+    RULES.iter().filter_map(|rule| rule(report)).collect()
+}
+
+fn unparsed_lines(report: &ParseReport) -> Option<String> {
+    let unparsed = report.dropped_lines + report.foreign_header_lines;
+    if unparsed == 0 || (unparsed as f32) < report.lines as f32 * UNPARSED_WARN_SHARE {
+        return None;
+    }
+    let noun = if unparsed == 1 { "line" } else { "lines" };
+    let hint = if report.foreign_header_lines > 0 {
+        "the file may mix export formats"
+    } else {
+        "results only cover the messages that were read"
+    };
+    Some(format!(
+        "{} {noun} could not be parsed \u{2014} {hint}",
+        with_thousands(unparsed)
+    ))
+}
+
+fn two_digit_years(report: &ParseReport) -> Option<String> {
+    (report.two_digit_years > 0).then(|| "2-digit years were interpreted as 20xx".to_string())
+}
+
+fn skipped_entries(report: &ParseReport) -> Option<String> {
+    let skipped = report.skipped_entries;
+    (skipped > 0).then(|| {
+        let (noun, verb) = if skipped == 1 {
+            ("message", "was")
+        } else {
+            ("messages", "were")
+        };
+        format!(
+            "{} {noun} {verb} skipped for a missing timestamp or sender",
+            with_thousands(skipped)
+        )
+    })
+}
+
+fn merged_senders(report: &ParseReport) -> Option<String> {
+    if report.merged_senders.is_empty() {
+        return None;
+    }
+    let merges: Vec<String> = report
+        .merged_senders
+        .iter()
+        .map(|(kept, others)| {
+            if others.is_empty() {
+                // Anonymized: the other spellings are real names.
+                return format!("spellings of \"{kept}\"");
+            }
+            let others: Vec<String> = others.iter().map(|o| format!("\"{o}\"")).collect();
+            format!("{} as \"{kept}\"", others.join(", "))
+        })
+        .collect();
+    Some(format!(
+        "Sender names differing only in case or spacing were merged: {}",
+        merges.join("; ")
+    ))
+}
+
+fn truncated_lines(report: &ParseReport) -> Option<String> {
+    let truncated = report.truncated_lines;
+    (truncated > 0).then(|| {
+        let (noun, verb) = if truncated == 1 {
+            ("line", "was")
+        } else {
+            ("lines", "were")
+        };
+        format!(
+            "{} overly long {noun} {verb} cut short before analysis",
+            with_thousands(truncated)
+        )
+    })
+}
+
+/// 12431 -> "12,431".
+fn with_thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(lines: usize, dropped: usize, foreign: usize, two_digit: usize) -> ParseReport {
+        ParseReport {
+            lines,
+            dropped_lines: dropped,
+            foreign_header_lines: foreign,
+            two_digit_years: two_digit,
+            skipped_entries: 0,
+            merged_senders: Vec::new(),
+            truncated_lines: 0,
+        }
+    }
+
+    #[test]
+    fn clean_report_has_no_warnings() {
+        assert!(build_warnings(&report(500, 0, 0, 0)).is_empty());
+        // Below the threshold: one stray line in a long file.
+        assert!(build_warnings(&report(500, 1, 0, 0)).is_empty());
+    }
+
+    #[test]
+    fn mixed_formats_warn_with_the_line_count() {
+        let warnings = build_warnings(&report(20_000, 0, 12_431, 0));
+        assert_eq!(
+            warnings,
+            vec!["12,431 lines could not be parsed \u{2014} the file may mix export formats"]
+        );
+        let warnings = build_warnings(&report(10, 1, 0, 0));
+        assert_eq!(
+            warnings,
+            vec!["1 line could not be parsed \u{2014} results only cover the messages that were read"]
+        );
+    }
+
+    #[test]
+    fn two_digit_years_warn() {
+        assert_eq!(
+            build_warnings(&report(10, 0, 0, 3)),
+            vec!["2-digit years were interpreted as 20xx"]
+        );
+    }
+
+    #[test]
+    fn with_thousands_groups_digits() {
+        assert_eq!(with_thousands(7), "7");
+        assert_eq!(with_thousands(1_000), "1,000");
+        assert_eq!(with_thousands(1_234_567), "1,234,567");
+    }
+
+    #[test]
+    fn skipped_structured_entries_are_counted() {
+        let mut skipped = report(3, 0, 0, 0);
+        skipped.skipped_entries = 2;
+        assert_eq!(
+            build_warnings(&skipped),
+            vec!["2 messages were skipped for a missing timestamp or sender"]
+        );
+    }
+
+    #[test]
+    fn truncated_lines_are_counted() {
+        let mut truncated = report(3, 0, 0, 0);
+        truncated.truncated_lines = 2;
+        assert_eq!(
+            build_warnings(&truncated),
+            vec!["2 overly long lines were cut short before analysis"]
+        );
+    }
+
+    #[test]
+    fn merged_sender_spellings_are_listed() {
+        let mut merged = report(3, 0, 0, 0);
+        merged.merged_senders = vec![("Alice".into(), vec!["alice".into(), "ALICE".into()])];
+        assert_eq!(
+            build_warnings(&merged),
+            vec![
+                "Sender names differing only in case or spacing were merged: \"alice\", \"ALICE\" as \"Alice\""
+            ]
+        );
+
+        merged.merged_senders[0].1.clear();
+        merged.merged_senders[0].0 = "Person A".into();
+        assert_eq!(
+            build_warnings(&merged),
+            vec!["Sender names differing only in case or spacing were merged: spellings of \"Person A\""]
+        );
+    }
+}
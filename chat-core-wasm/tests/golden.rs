@@ -0,0 +1,143 @@
+//! Golden-file regression test: every chat in `tests/fixtures` is summarized and compared
+//! field by field against `tests/golden/<name>.json`.
+//!
+//! After an intentional metric change, regenerate the goldens with
+//!
+//! ```text
+//! BLESS=1 cargo test --test golden
+//! ```
+//!
+//! and review the JSON diff like any other change.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+/// Absolute tolerance for non-integer numbers (sentiment means, ratios, percentiles).
+const FLOAT_TOLERANCE: f64 = 1e-4;
+
+/// Top-level `Summary` fields skipped by the comparison. Add a field here while a change
+/// to it is in flight and remove it once the goldens are blessed.
+const ALLOWED_CHANGES: &[&str] = &[];
+
+/// Top-level `Summary` fields left out of the goldens altogether: `analysis_meta` hashes
+/// the crate version and every option, so it changes with each release.
+const UNPINNED_FIELDS: &[&str] = &["analysis_meta"];
+
+const TOP_WORDS: usize = 20;
+const TOP_EMOJIS: usize = 10;
+
+/// Differences reported per fixture before the rest are summarized as a count.
+const MAX_REPORTED: usize = 20;
+
+fn fixtures() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("reading {}: {e}", dir.display()))
+        .map(|entry| entry.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn golden_path(fixture: &Path) -> PathBuf {
+    let name = fixture.file_stem().unwrap();
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name)
+        .with_extension("json")
+}
+
+/// Records every difference between `expected` and `actual` under `path`.
+fn compare(path: &str, expected: &Value, actual: &Value, diffs: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Number(e), Value::Number(a)) if e.is_f64() || a.is_f64() => {
+            let (e, a) = (e.as_f64().unwrap(), a.as_f64().unwrap());
+            if (e - a).abs() > FLOAT_TOLERANCE {
+                diffs.push(format!("{path}: expected {e}, got {a}"));
+            }
+        }
+        (Value::Object(e), Value::Object(a)) => {
+            for (key, e_value) in e {
+                let child = format!("{path}.{key}");
+                match a.get(key) {
+                    Some(a_value) => compare(&child, e_value, a_value, diffs),
+                    None => diffs.push(format!("{child}: missing")),
+                }
+            }
+            for key in a.keys().filter(|k| !e.contains_key(*k)) {
+                diffs.push(format!("{path}.{key}: new field"));
+            }
+        }
+        (Value::Array(e), Value::Array(a)) => {
+            if e.len() != a.len() {
+                diffs.push(format!(
+                    "{path}: expected {} items, got {}",
+                    e.len(),
+                    a.len()
+                ));
+            }
+            for (i, (e_item, a_item)) in e.iter().zip(a).enumerate() {
+                compare(&format!("{path}[{i}]"), e_item, a_item, diffs);
+            }
+        }
+        _ if expected != actual => diffs.push(format!("{path}: expected {expected}, got {actual}")),
+        _ => {}
+    }
+}
+
+#[test]
+fn summaries_match_goldens() {
+    let bless = std::env::var_os("BLESS").is_some();
+    let mut failures = Vec::new();
+
+    for fixture in fixtures() {
+        let raw = fs::read_to_string(&fixture).unwrap();
+        let summary = chat_core_wasm::summarize(&raw, TOP_WORDS, TOP_EMOJIS)
+            .unwrap_or_else(|e| panic!("{}: {e}", fixture.display()));
+        let mut actual = serde_json::to_value(&summary).unwrap();
+        for field in UNPINNED_FIELDS {
+            actual.as_object_mut().unwrap().remove(*field);
+        }
+        let golden = golden_path(&fixture);
+
+        if bless {
+            let pretty = serde_json::to_string_pretty(&actual).unwrap();
+            fs::write(&golden, pretty + "\n").unwrap();
+            continue;
+        }
+
+        let mut expected: Value = match fs::read_to_string(&golden) {
+            Ok(text) => serde_json::from_str(&text).unwrap(),
+            Err(_) => {
+                failures.push(format!("{}: no golden file", golden.display()));
+                continue;
+            }
+        };
+        for field in ALLOWED_CHANGES.iter().chain(UNPINNED_FIELDS) {
+            for value in [&mut expected, &mut actual] {
+                value.as_object_mut().unwrap().remove(*field);
+            }
+        }
+
+        let mut diffs = Vec::new();
+        compare("summary", &expected, &actual, &mut diffs);
+        if !diffs.is_empty() {
+            let name = fixture.file_name().unwrap().to_string_lossy();
+            let hidden = diffs.len().saturating_sub(MAX_REPORTED);
+            diffs.truncate(MAX_REPORTED);
+            if hidden > 0 {
+                diffs.push(format!("... and {hidden} more"));
+            }
+            failures.push(format!("{name}:\n  {}", diffs.join("\n  ")));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "summaries differ from the goldens (run with BLESS=1 if the change is intended):\n{}",
+        failures.join("\n")
+    );
+}
@@ -0,0 +1,66 @@
+//! Runs the wasm exports in a JS host: `wasm-pack test --node`.
+#![cfg(target_arch = "wasm32")]
+
+use chat_core_wasm::{analyze_chat, analyze_messages, AnalyzeOptions};
+use js_sys::{Array, Object, Reflect};
+use serde_json::Value;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const RAW: &str = "[1/1/24, 10:00:00 AM] Alice: hello there 😀\n[1/1/24, 10:01:00 AM] Bob: hi Alice\n[1/2/24, 9:00:00 PM] Alice: good night";
+
+fn message(timestamp: JsValue, sender: &str, text: &str) -> JsValue {
+    let m = Object::new();
+    Reflect::set(&m, &"timestamp".into(), &timestamp).unwrap();
+    Reflect::set(&m, &"sender".into(), &sender.into()).unwrap();
+    Reflect::set(&m, &"text".into(), &text.into()).unwrap();
+    m.into()
+}
+
+fn to_json(summary: JsValue) -> Value {
+    serde_wasm_bindgen::from_value(summary).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn analyze_messages_matches_analyze_chat() {
+    let messages = Array::new();
+    messages.push(&message(
+        "2024-01-01T10:00:00".into(),
+        "Alice",
+        "hello there 😀",
+    ));
+    messages.push(&message(1_704_103_260_000.0.into(), "Bob", "hi Alice"));
+    messages.push(&message(
+        "2024-01-02 21:00:00".into(),
+        "Alice",
+        "good night",
+    ));
+    let from_input =
+        to_json(analyze_messages(messages.into(), 10, 5, AnalyzeOptions::default()).unwrap());
+    let from_txt = to_json(analyze_chat(RAW, 10, 5).unwrap());
+
+    for field in [
+        "total_messages",
+        "daily",
+        "hourly",
+        "conversation_count",
+        "top_words",
+    ] {
+        assert_eq!(from_input[field], from_txt[field], "{field}");
+    }
+    assert_eq!(from_input["warnings"], Value::Array(vec![]));
+}
+
+#[wasm_bindgen_test]
+fn analyze_messages_counts_far_future_entries_as_skipped() {
+    let messages = Array::new();
+    messages.push(&message("2024-01-01T10:00:00".into(), "Alice", "hi"));
+    messages.push(&message("2999-01-01T10:00:00".into(), "Bob", "lost"));
+    let summary =
+        to_json(analyze_messages(messages.into(), 10, 5, AnalyzeOptions::default()).unwrap());
+    assert_eq!(summary["total_messages"], 1);
+    assert_eq!(
+        summary["warnings"][0],
+        "1 message was skipped for a missing timestamp or sender"
+    );
+}
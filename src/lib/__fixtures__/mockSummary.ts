@@ -63,6 +63,7 @@ const createMockJourney = (): Journey => ({
       createMockJourneyMessage("You", "I know right! 🎉", "2024-06-15T14:01:00Z", true),
     ]),
   ],
+  courtship: undefined,
 });
 
 export const createMockPersonBuckets = (name: string, messages: number): PersonBuckets => ({
@@ -100,8 +101,14 @@ const createMockPersonStat = (name: string): PersonStat => ({
   unique_words: 2500,
   longest_message_words: 150,
   average_words_per_message: 8.5,
+  p25_words: 3,
+  p75_words: 11,
+  p95_words: 32,
   top_emojis: [createMockCount("😂", 234), createMockCount("❤️", 189), createMockCount("👍", 156)],
+  emoji_pairs: [createMockCount("😂😭", 41), createMockCount("❤🔥", 12)],
   dominant_color: undefined,
+  avg_burst_size: 1.8,
+  lowercase_i_ratio: 0.42,
 });
 
 const createMockPersonPhrases = (name: string): PersonPhrases => ({
@@ -130,6 +137,7 @@ const createMockSentimentOverall = (name: string): SentimentOverall => ({
   pos: 48,
   neu: 38,
   neg: 14,
+  dominant_emotion: "Mostly positive",
 });
 
 /**
@@ -139,16 +147,32 @@ const createMockSentimentOverall = (name: string): SentimentOverall => ({
 export const createMockSummary = (): Summary => ({
   total_messages: 15432,
   by_sender: [createMockCount("Alice", 8234), createMockCount("You", 7198)],
+  participants: ["Alice", "You"],
   daily: [
     createMockCount("2024-11-28", 45),
     createMockCount("2024-11-29", 67),
     createMockCount("2024-11-30", 89),
     createMockCount("2024-12-01", 52),
   ],
+  longest_streak: { days: 4, start: "2024-11-28", end: "2024-12-01" },
   hourly: Array.from({ length: 24 }, (_, i) => ({
     hour: i,
     value: Math.floor(Math.random() * 500),
   })),
+  routines: [{ hour: 23, days: 290, active_days: 365, coverage: 0.79 }],
+  first_message_hour_by_month: [
+    { month: "2024-01", median_hour: 7.5, days: 29 },
+    { month: "2024-02", median_hour: 8.25, days: 27 },
+  ],
+  first_message_hour_by_person: [
+    {
+      name: "Alice",
+      monthly: [
+        { month: "2024-01", median_hour: 7.75, days: 26 },
+        { month: "2024-02", median_hour: 8.5, days: 25 },
+      ],
+    },
+  ],
   top_emojis: [
     createMockCount("😂", 523),
     createMockCount("❤️", 412),
@@ -156,6 +180,11 @@ export const createMockSummary = (): Summary => ({
     createMockCount("🔥", 187),
     createMockCount("😊", 156),
   ],
+  recent_emojis: [
+    createMockCount("🔥", 64.2),
+    createMockCount("😂", 58.7),
+    createMockCount("🫠", 31.5),
+  ],
   top_words: [
     createMockCount("the", 2345),
     createMockCount("you", 1987),
@@ -170,6 +199,13 @@ export const createMockSummary = (): Summary => ({
     createMockCount("good", 876),
     createMockCount("nice", 654),
   ],
+  top_word: "the",
+  stopword_config: { language: "auto", extra_count: 27 },
+  language_timeline: [
+    { month: "2024-01", top_language: "spanish", share: 0.82 },
+    { month: "2024-02", top_language: "english", share: 0.67 },
+  ],
+
   deleted_you: 12,
   deleted_others: 8,
   timeline: [
@@ -180,11 +216,70 @@ export const createMockSummary = (): Summary => ({
     createMockCount("2024-05", 987),
     createMockCount("2024-06", 1567),
   ],
+  timeline_unit: "month",
+  volume_trend: { slope: 0.04, direction: "growing" },
+  annotations: [{ date: "2024-05-14", kind: "birthday", evidence_count: 12 }],
+  special_days: [
+    {
+      date: "2024-05-14",
+      label: "Alice's birthday",
+      messages: 86,
+      vs_average_pct: 215.4,
+      top_phrase: "happy birthday",
+      sentiment_mean: 0.62,
+    },
+  ],
   weekly: Array.from({ length: 7 }, (_, i) => createMockCount(`Week ${i + 1}`, 200 + i * 50)),
+  iso_weekly: [
+    createMockCount("2024-W21", 182),
+    createMockCount("2024-W22", 240),
+    createMockCount("2024-W23", 96),
+  ],
+  weekly_streak: { weeks: 14, start_week: "2024-W10", end_week: "2024-W23", threshold: 10 },
   monthly: Array.from({ length: 12 }, (_, i) => createMockCount(`Month ${i + 1}`, 1000 + i * 100)),
+  busiest_month: createMockCount("Month 12", 2100),
+  quietest_month: createMockCount("Month 1", 1000),
+  swings: [
+    { kind: "volume_rise", month: "2024-07", prev: 1200, curr: 2100, delta: 900, percent: 75 },
+    { kind: "volume_drop", month: "2024-09", prev: 1900, curr: 1100, delta: -800, percent: -42.1 },
+    {
+      kind: "sentiment_swing",
+      month: "2024-03",
+      prev: 0.31,
+      curr: 0.12,
+      delta: -0.19,
+      percent: undefined,
+    },
+  ],
+  quarterly: [
+    createMockCount("2024-Q1", 3300),
+    createMockCount("2024-Q2", 3600),
+    createMockCount("2024-Q3", 3900),
+    createMockCount("2024-Q4", 3600),
+  ],
+  vocab_drift: [
+    {
+      period: "2024-Q2",
+      rising: [createMockCount("beach", 42), createMockCount("sunscreen", 18)],
+      falling: [createMockCount("snow", 37), createMockCount("exams", 21)],
+    },
+  ],
+  word_origins: [
+    { word: "bestie", coined_by: "Alice", first_date: "2024-02-11", adopted_count: 38 },
+    { word: "yeet", coined_by: "You", first_date: "2024-05-03", adopted_count: 12 },
+  ],
+  seasonal: [3600, 3900, 3600, 3300],
   share_of_speech: [createMockCount("Alice", 53.4), createMockCount("You", 46.6)],
+  imbalance: undefined,
   buckets_by_person: [createMockPersonBuckets("Alice", 8234), createMockPersonBuckets("You", 7198)],
+  weekday_leader: ["Alice", "You", "Alice", "Alice", "You", "Alice", null],
+  weekday_share: [
+    { name: "Alice", percent: [54.2, 47.1, 52.3, 55.0, 48.6, 57.9, 50.0] },
+    { name: "You", percent: [45.8, 52.9, 47.7, 45.0, 51.4, 42.1, 50.0] },
+  ],
   word_cloud: Array.from({ length: 50 }, (_, i) => createMockCount(`word${i}`, 500 - i * 10)),
+  keyword_counts: [createMockCount("love you", 321)],
+  theme_counts: [createMockCount("food", 214), createMockCount("travel", 87)],
   word_cloud_no_stop: Array.from({ length: 50 }, (_, i) =>
     createMockCount(`content${i}`, 400 - i * 8),
   ),
@@ -198,6 +293,52 @@ export const createMockSummary = (): Summary => ({
     createMockCount("💕", 98),
     createMockCount("✨", 87),
   ],
+  emoji_cloud_sentiment: [
+    { label: "😂", value: 523, sentiment: 1 },
+    { label: "❤️", value: 412, sentiment: 1 },
+    { label: "🔥", value: 187, sentiment: 0 },
+  ],
+  emoji_spam_by_sender: [createMockCount("Alice", 64), createMockCount("You", 21)],
+  per_person_emoji_monthly: [
+    {
+      name: "Alice",
+      monthly: [
+        { month: "2024-11", top: [createMockCount("😂", 42), createMockCount("❤️", 18)] },
+        { month: "2024-12", top: [createMockCount("🎄", 25), createMockCount("😂", 20)] },
+      ],
+    },
+  ],
+  emoji_categories: [
+    createMockCount("faces", 1240),
+    createMockCount("hearts", 530),
+    createMockCount("hands", 96),
+    createMockCount("objects", 41),
+  ],
+  emoji_pairs: [createMockCount("😂😭", 87), createMockCount("❤🔥", 23), createMockCount("🥺👉", 9)],
+  emoji_trend: [
+    { name: "Alice", early_rate: 0.42, late_rate: 0.71, direction: "growing" },
+    { name: "You", early_rate: 0.38, late_rate: 0.35, direction: "stable" },
+  ],
+  punctuation_energy: [
+    { month: "2024-05", questions: 412, exclamations: 538, exclamations_per_question: 1.31 },
+    { month: "2024-06", questions: 389, exclamations: 602, exclamations_per_question: 1.55 },
+  ],
+  punctuation_energy_by_person: [
+    {
+      name: "Alice",
+      monthly: [
+        { month: "2024-05", questions: 198, exclamations: 341, exclamations_per_question: 1.72 },
+        { month: "2024-06", questions: 176, exclamations: 372, exclamations_per_question: 2.11 },
+      ],
+    },
+    {
+      name: "You",
+      monthly: [
+        { month: "2024-05", questions: 214, exclamations: 197, exclamations_per_question: 0.92 },
+        { month: "2024-06", questions: 213, exclamations: 230, exclamations_per_question: 1.08 },
+      ],
+    },
+  ],
   salient_phrases: [
     createMockCount("that's hilarious", 45),
     createMockCount("can't wait", 38),
@@ -213,6 +354,17 @@ export const createMockSummary = (): Summary => ({
     createMockCount("sounds good", 145),
     createMockCount("can't wait", 132),
   ],
+  top_phrase_examples: [
+    { label: "I know", value: 234, example: "I know, right?" },
+    { label: "haha yeah", value: 189, example: "haha yeah that was wild" },
+    { label: "oh my god", value: 156, example: undefined },
+  ],
+  top_question_phrases: [
+    createMockCount("what are you doing?", 87),
+    createMockCount("did you eat?", 54),
+    createMockCount("are you home?", 31),
+  ],
+  phrase_min_count: { top_phrases: 1, salient_phrases: 2 },
   per_person_phrases: [createMockPersonPhrases("Alice"), createMockPersonPhrases("You")],
   per_person_phrases_no_stop: [createMockPersonPhrases("Alice"), createMockPersonPhrases("You")],
   fun_facts: [
@@ -225,19 +377,171 @@ export const createMockSummary = (): Summary => ({
       top_emojis: ["😂", "❤️", "👍"],
     },
   ],
+  highlights: [
+    { text: "Alice replies 3.2× faster than You", kind: "reply_speed", value: 3.2 },
+    { text: "Alice has sent 71% of all emojis", kind: "emoji_share", value: 71.3 },
+    {
+      text: "You've never gone more than 4 days without talking",
+      kind: "never_apart",
+      value: 4,
+    },
+  ],
+  spam_runs: [{ sender: "Alice", text_preview: "?", count: 24, date: "2024-03-02" }],
+  long_messages: [
+    {
+      sender: "You",
+      date: "2024-06-21",
+      token_count: 1240,
+      text_preview: "Okay so here are the lyrics",
+    },
+  ],
+  poll_stats: {
+    total_polls: 1,
+    total_votes: 5,
+    by_sender: [{ label: "Alice", value: 1 }],
+    polls: [
+      {
+        sender: "Alice",
+        date: "2024-03-02",
+        question: "Movie night?",
+        options: [
+          { label: "Friday", value: 3 },
+          { label: "Saturday", value: 2 },
+        ],
+      },
+    ],
+  },
   person_stats: [createMockPersonStat("Alice"), createMockPersonStat("You")],
+  word_firsts: [createMockCount("Alice", 1840), createMockCount("You", 1622)],
   per_person_daily: [
-    { name: "Alice", daily: [createMockCount("2024-12-01", 45)] },
-    { name: "You", daily: [createMockCount("2024-12-01", 38)] },
+    { name: "Alice", daily: [createMockCount("2024-12-01", 45)], longest_streak: 1 },
+    { name: "You", daily: [createMockCount("2024-12-01", 38)], longest_streak: 1 },
   ],
+  presence_calendar: {
+    start: "2024-12-01",
+    days: 1,
+    people: [
+      { name: "Alice", bitmap: "AQ==" },
+      { name: "You", bitmap: "AQ==" },
+    ],
+  },
   sentiment_by_day: [
     createMockSentimentDay("Alice", "2024-12-01"),
     createMockSentimentDay("You", "2024-12-01"),
   ],
   sentiment_overall: [createMockSentimentOverall("Alice"), createMockSentimentOverall("You")],
+  overall_sentiment: 0.31,
+  sentiment_exchange: [
+    { name: "Alice", given_mean: 0.35, received_mean: 0.28, delta: 0.07 },
+    { name: "You", given_mean: 0.28, received_mean: 0.35, delta: -0.07 },
+  ],
+  sentiment_series: [
+    { timestamp: "2024-01-15T10:30:00", sender: "Alice", score: 0.5 },
+    { timestamp: "2024-01-15T10:31:00", sender: "You", score: 0 },
+  ],
   conversation_starters: [createMockCount("Alice", 156), createMockCount("You", 132)],
+  starter_success: [
+    { label: "You", value: 0.94 },
+    { label: "Alice", value: 0.87 },
+  ],
+  presence_share: [
+    { label: "Alice", value: 0.91 },
+    { label: "You", value: 0.88 },
+  ],
+  revivals: [
+    { name: "You", count: 14, avg_followup_messages: 23.5 },
+    { name: "Alice", count: 9, avg_followup_messages: 41.2 },
+  ],
   conversation_count: 288,
+  total_conversation_minutes: 15330,
+  message_density: 60.4,
+  conversations_per_month: [
+    createMockCount("2024-01", 98),
+    createMockCount("2024-02", 91),
+    createMockCount("2024-03", 99),
+  ],
+  conversation_start_hours: [
+    6, 3, 1, 0, 0, 1, 4, 12, 21, 18, 14, 13, 17, 15, 11, 10, 12, 14, 19, 22, 24, 26, 21, 14,
+  ],
+  conversation_start_weekdays: [38, 44, 41, 39, 42, 46, 38],
+  edits_per_conversation: [
+    createMockCount("2024-03-01 18:00", 4),
+    createMockCount("2024-02-11 21:15", 2),
+  ],
+  response_latency: [
+    {
+      name: "Alice",
+      median_minutes: 3.5,
+      p25_minutes: 1.0,
+      p75_minutes: 12.0,
+      p95_minutes: 95.0,
+      replies: 4210,
+    },
+    {
+      name: "You",
+      median_minutes: 5.0,
+      p25_minutes: 1.5,
+      p75_minutes: 18.0,
+      p95_minutes: 140.0,
+      replies: 4188,
+    },
+  ],
+  avg_response_minutes: 4.2,
+  reply_time_histogram: [
+    {
+      name: "Alice",
+      buckets: [
+        createMockCount("<1m", 1520),
+        createMockCount("1-5m", 1380),
+        createMockCount("5-30m", 820),
+        createMockCount("30m-2h", 310),
+        createMockCount(">2h", 180),
+      ],
+    },
+  ],
+  biggest_conversation: {
+    date: "2024-06-15",
+    start: "2024-06-15T13:58:00",
+    end: "2024-06-15T23:41:00",
+    message_count: 342,
+    participants: ["Alice", "You"],
+    preview: ["This is so exciting!", "I know right! 🎉", "Tell me everything"],
+  },
+  voice_notes: {
+    count: 42,
+    unknown_duration: 2,
+    total_minutes: 37.5,
+    per_person: [
+      { name: "Alice", count: 30, minutes: 28.0 },
+      { name: "You", count: 12, minutes: 9.5 },
+    ],
+    longest: { sender: "Alice", date: "2024-06-15", seconds: 312 },
+  },
+  head_to_head: {
+    left: "Alice",
+    right: "You",
+    rows: [
+      { metric: "messages", left: 8420, right: 7031, winner: "Alice" },
+      { metric: "reply_minutes", left: 3.5, right: 5.0, winner: "Alice" },
+      { metric: "longest_streak_days", left: 42, right: 42, winner: undefined },
+    ],
+  },
+  relationship_score: {
+    score: 78.2,
+    reciprocity: 99.3,
+    responsiveness: 89.6,
+    positivity: 65.5,
+    consistency: 48.2,
+  },
   journey: createMockJourney(),
+  warnings: [],
+  merged: false,
+  sampled: undefined,
+  analysis_meta: {
+    fingerprint: "3f9a1c0d5e7b2468a0c4e6f8b1d3a5c7",
+    version: "0.0.1",
+    options_hash: "9b2e4d6f8a0c1e3b5d7f9a1c3e5b7d9f",
+  },
 });
 
 /**
@@ -246,32 +550,105 @@ export const createMockSummary = (): Summary => ({
 export const createEmptySummary = (): Summary => ({
   total_messages: 0,
   by_sender: [],
+  participants: [],
   daily: [],
+  longest_streak: undefined,
   hourly: [],
+  routines: [],
+  first_message_hour_by_month: [],
+  first_message_hour_by_person: [],
   top_emojis: [],
+  recent_emojis: [],
   top_words: [],
   top_words_no_stop: [],
+  top_word: undefined,
+  stopword_config: { language: "auto", extra_count: 27 },
+  language_timeline: [],
   deleted_you: 0,
   deleted_others: 0,
   timeline: [],
+  timeline_unit: "day",
+  volume_trend: { slope: 0, direction: "stable" },
+  annotations: [],
+  special_days: [],
   weekly: [],
+  iso_weekly: [],
+  weekly_streak: undefined,
   monthly: [],
+  busiest_month: undefined,
+  quietest_month: undefined,
+  swings: [],
+  quarterly: [],
+  vocab_drift: [],
+  word_origins: [],
+  seasonal: [0, 0, 0, 0],
   share_of_speech: [],
+  imbalance: undefined,
   buckets_by_person: [],
+  weekday_leader: [null, null, null, null, null, null, null],
+  weekday_share: [],
   word_cloud: [],
+  keyword_counts: [],
+  theme_counts: [],
   word_cloud_no_stop: [],
   emoji_cloud: [],
+  emoji_cloud_sentiment: [],
+  emoji_spam_by_sender: [],
+  per_person_emoji_monthly: [],
+  emoji_categories: [],
+  emoji_pairs: [],
+  emoji_trend: [],
+  punctuation_energy: [],
+  punctuation_energy_by_person: [],
   salient_phrases: [],
   top_phrases: [],
   top_phrases_no_stop: [],
+  top_phrase_examples: [],
+  top_question_phrases: [],
+  phrase_min_count: { top_phrases: 1, salient_phrases: 2 },
   per_person_phrases: [],
   per_person_phrases_no_stop: [],
   fun_facts: [],
+  highlights: [],
+  spam_runs: [],
+  long_messages: [],
+  poll_stats: { total_polls: 0, total_votes: 0, by_sender: [], polls: [] },
   person_stats: [],
+  word_firsts: [],
   per_person_daily: [],
+  presence_calendar: { start: "", days: 0, people: [] },
   sentiment_by_day: [],
   sentiment_overall: [],
+  overall_sentiment: 0,
+  sentiment_exchange: undefined,
+  sentiment_series: [],
   conversation_starters: [],
+  starter_success: [],
+  presence_share: [],
+  revivals: [],
   conversation_count: 0,
+  total_conversation_minutes: 0,
+  message_density: 0,
+  conversations_per_month: [],
+  conversation_start_hours: Array.from({ length: 24 }, () => 0),
+  conversation_start_weekdays: [0, 0, 0, 0, 0, 0, 0],
+  edits_per_conversation: [],
+  response_latency: [],
+  avg_response_minutes: 0,
+  reply_time_histogram: [],
+  biggest_conversation: undefined,
+  voice_notes: {
+    count: 0,
+    unknown_duration: 0,
+    total_minutes: 0,
+    per_person: [],
+    longest: undefined,
+  },
+  head_to_head: undefined,
+  relationship_score: { score: 0, reciprocity: 0, responsiveness: 0, positivity: 0, consistency: 0 },
   journey: undefined,
+  warnings: [],
+  merged: false,
+  sampled: undefined,
+  analysis_meta: { fingerprint: "", version: "", options_hash: "" },
 });
@@ -3,9 +3,13 @@
  * This prevents Chrome's "page unresponsive" dialog during long computations.
  */
 
-import init, { analyze_chat, init_panic_hook } from "../../pkg/chat_core_wasm.js";
+import init, { analyze_chat_with_options, init_panic_hook } from "../../pkg/chat_core_wasm.js";
+import type { AnalyzeOptions } from "../../pkg/chat_core_wasm.js";
 import type { Summary } from "./types";
 
+// Long chats chart more readably by week or month; the core defaults to days.
+const ANALYZE_OPTIONS = { timeline_granularity: "auto" } as AnalyzeOptions;
+
 let initialized = false;
 
 async function ensureWasm() {
@@ -33,7 +37,7 @@ self.onmessage = async (e: MessageEvent<WorkerRequest>) => {
   if (type === "analyze") {
     try {
       await ensureWasm();
-      const result = analyze_chat(raw, topWords, topEmojis);
+      const result = analyze_chat_with_options(raw, topWords, topEmojis, ANALYZE_OPTIONS);
       self.postMessage({ id, type: "success", result } satisfies WorkerResponse);
     } catch (err) {
       const message = err instanceof Error ? err.message : String(err);
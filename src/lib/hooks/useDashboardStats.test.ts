@@ -111,6 +111,17 @@ describe("useDashboardStats", () => {
       expect(byLabel["Quietest day"].detail).toBe("5 messages");
     });
 
+    it("counts active days from the daily series when the timeline is not daily", () => {
+      const monthly: Summary = {
+        ...summary,
+        timeline: [c("2024-01", 77)],
+        timeline_unit: "month",
+      };
+      const { result } = renderHook(() => useDashboardStats(monthly, true));
+      const byLabel = Object.fromEntries(result.current.kpis.map((k) => [k.label, k]));
+      expect(byLabel["Active days"].value).toBe("4");
+    });
+
     it("singularizes the message count detail when a day has exactly one message", () => {
       const single: Summary = {
         ...createEmptySummary(),
@@ -332,5 +343,19 @@ describe("useDashboardStats", () => {
       expect(result.current.longestStreakData.start).toBe("2024-01-01");
       expect(result.current.longestStreakData.end).toBe("2024-01-03");
     });
+
+    it("prefers the core's streak, which bridges ignored date ranges", () => {
+      const summary: Summary = {
+        ...createEmptySummary(),
+        daily: [c("2024-01-01", 1), c("2024-01-02", 1), c("2024-01-10", 1)],
+        longest_streak: { days: 3, start: "2024-01-01", end: "2024-01-10" },
+      };
+      const { result } = renderHook(() => useDashboardStats(summary, true));
+      expect(result.current.longestStreakData).toEqual({
+        days: 3,
+        start: "2024-01-01",
+        end: "2024-01-10",
+      });
+    });
   });
 });
@@ -45,6 +45,20 @@ function formatDayLabel(day: string): string {
   return date.toLocaleDateString(undefined, { month: "short", day: "numeric", year: "numeric" });
 }
 
+// Days from the first to the last message: the length of a daily timeline, or counted
+// from the daily series when the timeline is bucketed by week or month.
+function spanDays(summary: Summary): number {
+  if (summary.timeline_unit === "day") return summary.timeline.length;
+  const first = summary.daily[0];
+  const last = summary.daily[summary.daily.length - 1];
+  if (!first || !last) return 0;
+  const utc = (label: string) => {
+    const [year, month, day] = label.split("-").map(Number);
+    return Date.UTC(year, month - 1, day);
+  };
+  return Math.round((utc(last.label) - utc(first.label)) / 86_400_000) + 1;
+}
+
 /**
  * Hook that computes all derived statistics from the summary data.
  * This centralizes all the useMemo transformations for the dashboard.
@@ -68,7 +82,11 @@ export function useDashboardStats(
     return dailyData.reduce((min, d) => (d.messages < min.messages ? d : min), dailyData[0]);
   }, [dailyData]);
 
-  const longestStreakData = useMemo(() => calcLongestStreak(dailyData), [dailyData]);
+  // The core's streak honours the user's ignored date ranges; older summaries lack it.
+  const longestStreakData = useMemo(
+    () => summary?.longest_streak ?? calcLongestStreak(dailyData),
+    [summary, dailyData]
+  );
 
   const topStarter = summary?.conversation_starters[0];
   const topStarterShare =
@@ -86,7 +104,7 @@ export function useDashboardStats(
       },
       {
         label: "Active days",
-        value: summary.timeline.length.toLocaleString(),
+        value: spanDays(summary).toLocaleString(),
         detail: `Senders: ${summary.by_sender.length}`,
       },
       {
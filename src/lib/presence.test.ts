@@ -0,0 +1,18 @@
+import { describe, expect, it } from "vitest";
+import { decodePresenceBitmap } from "./presence";
+
+describe("decodePresenceBitmap", () => {
+  it("returns an empty array for an empty calendar", () => {
+    expect(decodePresenceBitmap("", 0)).toEqual([]);
+  });
+
+  it("reads bits least significant first", () => {
+    // Bytes [0b0000_0101, 0b0000_0010]: days 0, 2 and 9.
+    const days = decodePresenceBitmap(btoa(String.fromCharCode(0b101, 0b10)), 10);
+    expect(days.map((on, i) => (on ? i : -1)).filter((i) => i >= 0)).toEqual([0, 2, 9]);
+  });
+
+  it("ignores padding bits past the span", () => {
+    expect(decodePresenceBitmap("/w==", 3)).toEqual([true, true, true]);
+  });
+});
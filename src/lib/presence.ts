@@ -0,0 +1,11 @@
+/**
+ * Decode a `PersonPresence.bitmap` from `Summary.presence_calendar` into one flag per day.
+ * Day `i` after `start` is bit `i % 8` (least significant first) of byte `i / 8`.
+ */
+export function decodePresenceBitmap(bitmap: string, days: number): boolean[] {
+  const bytes = atob(bitmap);
+  return Array.from({ length: days }, (_, i) => {
+    const byte = bytes.charCodeAt(i >> 3) || 0;
+    return (byte & (1 << (i & 7))) !== 0;
+  });
+}
@@ -18,4 +18,6 @@ export type {
   Journey,
   SentimentDay,
   SentimentOverall,
+  PresenceCalendar,
+  PersonPresence,
 } from "../../pkg/chat_core_wasm";